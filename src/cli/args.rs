@@ -1,4 +1,8 @@
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use crate::formats::FileFormat;
+use crate::server::Compat;
 
 #[derive(Parser)]
 #[command(name = "fanyi")]
@@ -8,12 +12,12 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
     
-    /// 源语言 (例如: zh, en, ja)
-    #[arg(short, long)]
+    /// 源语言 (例如: zh, en, ja, japanese, 日语)
+    #[arg(short, long, global = true)]
     pub from: Option<String>,
     
-    /// 目标语言 (例如: zh, en, ja)
-    #[arg(short, long)]
+    /// 目标语言 (例如: zh, en, ja, japanese, 日语)
+    #[arg(short, long, global = true)]
     pub to: Option<String>,
     
     /// 要翻译的文本
@@ -21,16 +25,28 @@ pub struct Cli {
     pub text: Option<String>,
     
     /// 禁用颜色输出
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub no_color: bool,
     
+    /// 以JSON格式输出结果
+    #[arg(long, global = true)]
+    pub json: bool,
+    
     /// 禁用代理 (忽略环境变量和配置文件中的代理设置)
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub no_proxy: bool,
     
     /// 强制使用代理 (即使配置为禁用)
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub force_proxy: bool,
+    
+    /// 术语表文件 (CSV 或 TBX)，覆盖配置文件中的 glossary
+    #[arg(long, global = true, value_name = "FILE")]
+    pub glossary: Option<PathBuf>,
+    
+    /// 使用配置文件中的命名配置档案 [profiles.<NAME>] (也可通过环境变量 FANYI_PROFILE 指定)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -68,11 +84,251 @@ pub enum Commands {
         /// 显示当前配置
         #[arg(long)]
         show: bool,
+        
+        /// 与 --show 一起使用，列出每个配置项的值及其来源
+        #[arg(long, requires = "show")]
+        origin: bool,
     },
     /// 列出支持的语言
-    Languages,
+    Languages {
+        /// 翻译服务提供商 (默认使用配置文件中的 provider)
+        #[arg(long)]
+        provider: Option<String>,
+    },
     /// 显示代理状态和环境变量
     ProxyStatus,
+    /// 翻译文本文件 (自动识别 UTF-8、GBK、UTF-16 编码)
+    File {
+        /// 要翻译的文件
+        #[arg(value_name = "PATH", required = true)]
+        paths: Vec<PathBuf>,
+        
+        /// 输出文件 (多个输入文件时为输出目录)
+        #[arg(short, long, conflicts_with_all = ["suffix", "in_place"])]
+        output: Option<PathBuf>,
+        
+        /// 在原文件名的扩展名前插入后缀写出 (例如 --suffix .en: doc.txt -> doc.en.txt)
+        #[arg(long, conflicts_with = "in_place")]
+        suffix: Option<String>,
+        
+        /// 直接覆盖原文件
+        #[arg(long)]
+        in_place: bool,
+        
+        /// 文件格式 (默认根据扩展名判断，.md 按 Markdown 处理，.html 按 HTML 处理)
+        #[arg(long, value_enum, default_value = "auto")]
+        format: FileFormat,
+    },
+    /// 翻译源代码中的注释和文档字符串 (Rust、C/C++、Python、JS/TS、Go、Java)
+    Code {
+        /// 源文件或目录 (目录会递归查找支持的源文件)
+        #[arg(value_name = "PATH", required = true)]
+        paths: Vec<PathBuf>,
+        
+        /// 不修改文件，以 unified diff 格式输出改动
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// 翻译字幕文件 (SRT / WebVTT)
+    Subs {
+        /// 字幕文件
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+        
+        /// 输出文件 (默认输出到标准输出)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        /// 输出双语字幕 (原文一行，译文一行)
+        #[arg(long)]
+        bilingual: bool,
+    },
+    /// gettext PO/POT 文件翻译
+    Po {
+        #[command(subcommand)]
+        action: PoCommands,
+    },
+    /// i18n 资源文件 (JSON / YAML) 翻译
+    I18n {
+        #[command(subcommand)]
+        action: I18nCommands,
+    },
+    /// XLIFF 1.2 / 2.0 文件翻译
+    Xliff {
+        #[command(subcommand)]
+        action: XliffCommands,
+    },
+    /// 移动端资源文件翻译 (Android strings.xml / Apple .strings / .xcstrings)
+    Mobile {
+        #[command(subcommand)]
+        action: MobileCommands,
+    },
+    /// 启动本地 HTTP 翻译服务 (POST /translate、POST /detect、GET /languages)
+    Serve {
+        /// 监听地址 (默认使用配置文件中的 [server] listen，即 127.0.0.1:8787)
+        #[arg(long, value_name = "ADDR")]
+        listen: Option<String>,
+        
+        /// 客户端访问令牌，请求需携带 Authorization: Bearer <TOKEN>
+        #[arg(long)]
+        token: Option<String>,
+        
+        /// 接口风格: fanyi (默认)、libretranslate 或 deepl
+        #[arg(long, value_enum, default_value = "fanyi")]
+        compat: Compat,
+    },
+    /// 在标准输入输出上提供按行分隔的 JSON-RPC 2.0 服务，供编辑器插件调用
+    Rpc,
+    /// 在标准输入输出上运行 MCP (Model Context Protocol) 服务，向 AI 助手提供翻译工具
+    Mcp,
+    /// 运行 LSP 服务：悬停翻译注释和字符串，代码操作翻译选中的文本
+    Lsp,
+    /// 翻译记忆：保存审校过的原文和译文，翻译时优先复用
+    Tm {
+        #[command(subcommand)]
+        action: TmCommands,
+    },
+    /// 后台守护进程：保持连接和缓存，普通翻译命令会自动转发给它
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonCommands,
+    },
+    /// 翻译图片中的文字 (支持 png/jpg)
+    Image {
+        /// 图片文件路径
+        #[arg(value_name = "IMAGE")]
+        path: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PoCommands {
+    /// 为未翻译的条目填充机器译文 (标记为 fuzzy)
+    Translate {
+        /// PO/POT 文件
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+        
+        /// 输出文件 (默认覆盖原文件)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        /// 重新翻译已有译文的条目
+        #[arg(long)]
+        overwrite: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum I18nCommands {
+    /// 翻译资源文件中的所有字符串，保持键顺序、格式和插值占位符
+    Translate {
+        /// 源语言资源文件 (.json / .yml / .yaml)
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+        
+        /// 输出文件 (默认输出到标准输出)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// 增量同步目录中的语言文件，只翻译新增或变化的键
+    Sync {
+        /// 源语言 (对应目录中的 <source>.json / <source>.yml)
+        #[arg(long)]
+        source: String,
+        
+        /// 目标语言，逗号分隔 (例如: en,ja)
+        #[arg(long, value_delimiter = ',', required = true)]
+        targets: Vec<String>,
+        
+        /// 语言文件所在目录
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum XliffCommands {
+    /// 机器翻译没有 target 的单元，标记为待审校
+    Translate {
+        /// XLIFF 文件 (.xlf / .xliff)
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+        
+        /// 输出文件 (默认覆盖原文件)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MobileCommands {
+    /// 翻译资源文件，保留格式符并跳过不可翻译的条目
+    Translate {
+        /// strings.xml、.strings 或 .xcstrings 文件
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+        
+        /// 输出文件 (.xcstrings 默认覆盖原文件，其他格式默认输出到标准输出)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TmCommands {
+    /// 添加一条句段对 (语言由 --from / --to 指定)
+    Add {
+        /// 原文
+        #[arg(value_name = "SOURCE")]
+        source: String,
+        
+        /// 审校过的译文
+        #[arg(value_name = "TARGET")]
+        target: String,
+    },
+    /// 按相似度查找记忆库中的句段
+    Search {
+        /// 要查找的文本
+        #[arg(value_name = "TEXT")]
+        text: String,
+        
+        /// 最低相似度 (0.0 ~ 1.0，默认使用配置文件中的 fuzzy_threshold)
+        #[arg(long, value_parser = parse_threshold)]
+        threshold: Option<f64>,
+    },
+    /// 从 TMX 文件导入句段
+    Import {
+        /// TMX 文件
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+    },
+    /// 将记忆库导出为 TMX 1.4 文件
+    Export {
+        /// 输出文件
+        #[arg(value_name = "FILE")]
+        path: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DaemonCommands {
+    /// 在后台启动守护进程
+    Start,
+    /// 停止守护进程
+    Stop,
+    /// 显示守护进程状态
+    Status,
+    /// 在前台运行守护进程 (由 start 调用)
+    #[command(hide = true)]
+    Run,
+}
+
+fn parse_threshold(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(value) if (0.0..=1.0).contains(&value) => Ok(value),
+        _ => Err("相似度必须是 0.0 到 1.0 之间的数字".to_string()),
+    }
 }
 
 fn parse_proxy_mode(s: &str) -> Result<String, String> {
@@ -1,3 +1,3 @@
 pub mod args;
 
-pub use args::{Cli, Commands}; 
\ No newline at end of file
+pub use args::{Cli, Commands, DaemonCommands, I18nCommands, MobileCommands, PoCommands, TmCommands, XliffCommands}; 
\ No newline at end of file
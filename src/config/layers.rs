@@ -0,0 +1,188 @@
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 项目配置文件名，从当前目录向上查找
+pub const PROJECT_FILE: &str = ".fanyi.toml";
+
+/// 环境变量前缀，例如 FANYI_BAIDU_APP_ID 对应 [baidu] app_id
+const ENV_PREFIX: &str = "FANYI_";
+
+/// 配置项的来源
+#[derive(Debug, Clone, PartialEq)]
+pub enum Origin {
+    /// 内置默认值
+    Default,
+    /// 用户配置文件
+    UserFile(PathBuf),
+    /// 用户配置文件中的命名档案
+    Profile(String),
+    /// 项目中的 .fanyi.toml
+    ProjectFile(PathBuf),
+    /// 环境变量
+    Env(String),
+    /// 命令行参数
+    Cli(&'static str),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Default => write!(f, "默认值"),
+            Origin::UserFile(path) => write!(f, "用户配置 {}", path.display()),
+            Origin::Profile(name) => write!(f, "配置档案 {}", name),
+            Origin::ProjectFile(path) => write!(f, "项目配置 {}", path.display()),
+            Origin::Env(var) => write!(f, "环境变量 {}", var),
+            Origin::Cli(flag) => write!(f, "命令行参数 {}", flag),
+        }
+    }
+}
+
+/// 每个配置项 (例如 `baidu.app_id`) 的来源，没有记录的为默认值
+pub type Origins = BTreeMap<String, Origin>;
+
+/// 读取 TOML 配置文件
+pub fn read_table(path: &Path) -> Result<toml::Table> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("读取配置文件失败: {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("解析配置文件失败: {}", path.display()))
+}
+
+/// 从当前目录向上查找项目配置文件
+pub fn find_project_file() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors()
+        .map(|dir| dir.join(PROJECT_FILE))
+        .find(|path| path.is_file())
+}
+
+/// 将 overlay 中的字段逐层覆盖到 base 上，并记录被覆盖字段的来源
+pub fn merge(base: &mut toml::Table, overlay: &toml::Table, origin: &Origin, origins: &mut Origins, prefix: &str) {
+    for (key, value) in overlay {
+        let path = join(prefix, key);
+        match (base.get_mut(key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                merge(base, overlay, origin, origins, &path);
+            }
+            _ => {
+                base.insert(key.clone(), value.clone());
+                origins.insert(path, origin.clone());
+            }
+        }
+    }
+}
+
+/// 以 FANYI_ 开头且值不为空的环境变量，按名称排序
+pub fn env_vars() -> Vec<(String, String)> {
+    let mut vars: Vec<(String, String)> = std::env::vars()
+        .filter(|(var, value)| var.starts_with(ENV_PREFIX) && !value.is_empty())
+        .collect();
+    vars.sort();
+    vars
+}
+
+/// 应用 FANYI_<段>_<字段> 环境变量，值按该字段现有的类型解析
+///
+/// 每应用一个变量都用 normalize 解析整个配置并重新序列化，出错时指出是哪个环境变量；
+/// 重新序列化后不存在的字段（例如把 FANYI_BAIDU_APP_ID 拼成 FANYI_BAIDU_APPID）不是配置项，直接报错
+pub fn apply_env<F>(
+    table: &mut toml::Table,
+    origins: &mut Origins,
+    vars: Vec<(String, String)>,
+    normalize: F,
+) -> Result<()>
+where
+    F: Fn(&toml::Table) -> Result<toml::Table>,
+{
+    for (var, value) in vars {
+        let Some(name) = var.strip_prefix(ENV_PREFIX).map(str::to_lowercase) else { continue };
+        let Some((section, field)) = table
+            .iter()
+            .filter(|(_, v)| v.is_table())
+            .find_map(|(section, _)| {
+                let field = name.strip_prefix(section.as_str())?.strip_prefix('_')?;
+                Some((section.clone(), field.to_string()))
+            })
+        else {
+            continue;
+        };
+        let Some(toml::Value::Table(fields)) = table.get_mut(&section) else { continue };
+
+        let parsed = match fields.get(&field) {
+            Some(toml::Value::Boolean(_)) => match value.to_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => toml::Value::Boolean(true),
+                "0" | "false" | "no" | "off" => toml::Value::Boolean(false),
+                _ => bail!("环境变量 {} 的值必须是 true 或 false", var),
+            },
+            Some(toml::Value::Integer(_)) => toml::Value::Integer(
+                value.parse().with_context(|| format!("环境变量 {} 的值必须是整数", var))?,
+            ),
+            Some(toml::Value::Float(_)) => toml::Value::Float(
+                value.parse().with_context(|| format!("环境变量 {} 的值必须是数字", var))?,
+            ),
+            _ => toml::Value::String(value),
+        };
+        fields.insert(field.clone(), parsed);
+        let normalized = match normalize(table) {
+            Ok(normalized) => normalized,
+            Err(e) => {
+                let reason = e.to_string().split_whitespace().collect::<Vec<_>>().join(" ");
+                bail!("环境变量 {} 的值无效: {}", var, reason);
+            }
+        };
+        let known = normalized
+            .get(&section)
+            .and_then(|fields| fields.get(&field))
+            .is_some();
+        if !known {
+            bail!("环境变量 {} 不对应任何配置项: [{}] 中没有 {} 字段", var, section, field);
+        }
+        origins.insert(join(&section, &field), Origin::Env(var));
+    }
+    Ok(())
+}
+
+/// 展开为 (`段.字段`, 值) 列表
+pub fn flatten(table: &toml::Table, prefix: &str, entries: &mut Vec<(String, toml::Value)>) {
+    for (key, value) in table {
+        let path = join(prefix, key);
+        match value {
+            toml::Value::Table(table) => flatten(table, &path, entries),
+            _ => entries.push((path, value.clone())),
+        }
+    }
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_overrides_fields_and_records_origins() {
+        let mut base: toml::Table = toml::from_str("[baidu]\napp_id = \"\"\nsecret_key = \"\"\n[ui]\nenable_colors = true\n").unwrap();
+        let overlay: toml::Table = toml::from_str("[baidu]\napp_id = \"42\"\n").unwrap();
+        let origin = Origin::ProjectFile(PathBuf::from(".fanyi.toml"));
+        let mut origins = Origins::new();
+        merge(&mut base, &overlay, &origin, &mut origins, "");
+
+        assert_eq!(base["baidu"]["app_id"].as_str(), Some("42"));
+        assert_eq!(base["baidu"]["secret_key"].as_str(), Some(""));
+        assert_eq!(origins.len(), 1);
+        assert_eq!(origins["baidu.app_id"], origin);
+
+        let mut entries = Vec::new();
+        flatten(&base, "", &mut entries);
+        let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["baidu.app_id", "baidu.secret_key", "ui.enable_colors"]);
+    }
+}
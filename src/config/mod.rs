@@ -1,3 +1,5 @@
+pub mod layers;
 pub mod settings;
 
+pub use layers::Origin;
 pub use settings::{Config, BaiduConfig, ProxyConfig, ProxyMode}; 
\ No newline at end of file
@@ -1,14 +1,33 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
+use super::layers::{self, Origin, Origins};
+use crate::memory::TranslationMemory;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub translation: TranslationConfig,
     pub baidu: BaiduConfig,
     pub ui: UiConfig,
     pub proxy: ProxyConfig,
+    #[serde(default)]
+    pub memory: MemoryConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
+    /// 命名配置档案，只需写出与基础配置不同的字段，例如 `[profiles.work.baidu]`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, toml::Table>,
+    /// 当前使用的配置档案
+    #[serde(skip)]
+    pub profile: Option<String>,
+    /// 各配置项的来源
+    #[serde(skip)]
+    pub origins: Origins,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +35,9 @@ pub struct TranslationConfig {
     pub default_from: String,
     pub default_to: String,
     pub provider: String,
+    /// 术语表文件 (CSV 或 TBX)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glossary: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +45,15 @@ pub struct BaiduConfig {
     pub app_id: String,
     pub secret_key: String,
     pub api_url: String,
+    /// 图片翻译API地址
+    #[serde(default = "default_picture_api_url")]
+    pub picture_api_url: String,
+    /// 每秒最多请求次数，0 表示不限制（百度标准版为 1，高级版为 10）
+    #[serde(default = "default_qps")]
+    pub qps: u32,
+    /// 使用百度控制台中配置的术语库进行术语干预 (needIntervention=1)，本地术语表不受影响
+    #[serde(default)]
+    pub need_intervention: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +72,36 @@ pub struct ProxyConfig {
     pub https_proxy: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryConfig {
+    /// 翻译前是否查找翻译记忆
+    #[serde(default = "default_memory_enabled")]
+    pub enabled: bool,
+    /// 记忆库文件 (默认为数据目录下的 fanyi/memory.json)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// 模糊匹配的最低相似度 (0.0 ~ 1.0)
+    #[serde(default = "default_fuzzy_threshold")]
+    pub fuzzy_threshold: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheConfig {
+    /// 内存中最多缓存的译文条数，0 表示不缓存
+    #[serde(default = "default_cache_capacity")]
+    pub capacity: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerConfig {
+    /// `fanyi serve` 的监听地址
+    #[serde(default = "default_listen")]
+    pub listen: String,
+    /// 客户端访问令牌 (Authorization: Bearer <token>)，为空时不校验
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ProxyMode {
@@ -55,10 +116,64 @@ impl Default for BaiduConfig {
             app_id: String::new(),
             secret_key: String::new(),
             api_url: "https://fanyi-api.baidu.com/api/trans/vip/translate".to_string(),
+            picture_api_url: default_picture_api_url(),
+            qps: default_qps(),
+            need_intervention: false,
         }
     }
 }
 
+fn default_picture_api_url() -> String {
+    "https://fanyi-api.baidu.com/api/trans/sdk/picture".to_string()
+}
+
+fn default_qps() -> u32 {
+    1
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_memory_enabled(),
+            path: None,
+            fuzzy_threshold: default_fuzzy_threshold(),
+        }
+    }
+}
+
+fn default_memory_enabled() -> bool {
+    true
+}
+
+fn default_fuzzy_threshold() -> f64 {
+    0.75
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: default_cache_capacity(),
+        }
+    }
+}
+
+fn default_cache_capacity() -> usize {
+    1000
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            listen: default_listen(),
+            token: None,
+        }
+    }
+}
+
+fn default_listen() -> String {
+    "127.0.0.1:8787".to_string()
+}
+
 impl Default for ProxyConfig {
     fn default() -> Self {
         Self {
@@ -76,6 +191,7 @@ impl Default for Config {
                 default_from: "zh".to_string(),
                 default_to: "en".to_string(),
                 provider: "baidu".to_string(),
+                glossary: None,
             },
             baidu: BaiduConfig::default(),
             ui: UiConfig {
@@ -83,6 +199,12 @@ impl Default for Config {
                 show_language_detection: true,
             },
             proxy: ProxyConfig::default(),
+            memory: MemoryConfig::default(),
+            cache: CacheConfig::default(),
+            server: ServerConfig::default(),
+            profiles: BTreeMap::new(),
+            profile: None,
+            origins: Origins::new(),
         }
     }
 }
@@ -131,15 +253,60 @@ impl Config {
             .context("无法获取配置目录")?
             .join("fanyi");
         
-        // 确保配置目录存在
-        fs::create_dir_all(&config_dir)
-            .context("创建配置目录失败")?;
-        
         Ok(config_dir.join("config.toml"))
     }
 
-    /// 加载配置文件
-    pub fn load() -> Result<Self> {
+    /// 加载配置
+    ///
+    /// 依次叠加内置默认值、用户配置文件、配置档案（未指定时使用环境变量 FANYI_PROFILE）、
+    /// 从当前目录向上找到的项目配置 .fanyi.toml 和 FANYI_* 环境变量，并记录每项的来源
+    ///
+    /// 用户配置文件不存在时跳过这一层，不会创建文件（例如 CI 中只通过环境变量配置）
+    pub fn load(profile: Option<&str>) -> Result<Self> {
+        let mut origins = Origins::new();
+        let mut table = Self::default().base_table()?;
+
+        let config_path = Self::config_file_path()?;
+        let mut profiles = BTreeMap::new();
+        if config_path.is_file() {
+            let mut user = layers::read_table(&config_path)?;
+            if let Some(user_profiles) = user.remove("profiles") {
+                profiles = user_profiles.try_into().context("解析配置档案失败")?;
+            }
+            layers::merge(&mut table, &user, &Origin::UserFile(config_path), &mut origins, "");
+        }
+
+        let profile = Self::selected_profile(profile);
+        if let Some(name) = &profile {
+            let overrides = profiles.get(name).ok_or_else(|| unknown_profile(name, &profiles))?;
+            layers::merge(&mut table, overrides, &Origin::Profile(name.clone()), &mut origins, "");
+        }
+
+        if let Some(path) = layers::find_project_file() {
+            let mut project = layers::read_table(&path)?;
+            project.remove("profiles");
+            layers::merge(&mut table, &project, &Origin::ProjectFile(path), &mut origins, "");
+        }
+
+        // 先检查文件中的配置，之后的错误都来自环境变量
+        Self::from_table(&table).context("解析配置失败")?;
+        layers::apply_env(&mut table, &mut origins, layers::env_vars(), |table| {
+            Self::from_table(table)?.base_table()
+        })?;
+
+        let mut config: Config = table.try_into().context("解析配置失败")?;
+        config.profiles = profiles;
+        config.profile = profile;
+        config.origins = origins;
+        Ok(config)
+    }
+
+    fn from_table(table: &toml::Table) -> Result<Self> {
+        Ok(toml::Value::Table(table.clone()).try_into()?)
+    }
+
+    /// 加载配置文件中的基础配置，不应用配置档案
+    pub fn load_base() -> Result<Self> {
         let config_path = Self::config_file_path()?;
         
         if config_path.exists() {
@@ -151,17 +318,113 @@ impl Config {
             
             Ok(config)
         } else {
-            // 如果配置文件不存在，创建默认配置
-            let default_config = Config::default();
-            default_config.save()?;
-            Ok(default_config)
+            // 配置文件不存在时使用默认配置，保存修改时才创建文件
+            Ok(Config::default())
         }
     }
 
+    /// 要使用的配置档案：命令行参数优先，其次为环境变量 FANYI_PROFILE
+    pub fn selected_profile(profile: Option<&str>) -> Option<String> {
+        match profile {
+            Some(name) => Some(name.to_string()),
+            None => std::env::var("FANYI_PROFILE").ok().filter(|name| !name.is_empty()),
+        }
+    }
+
+    /// 切换到配置档案，档案不存在时新建，之后的修改保存到该档案中
+    pub fn into_profile(mut self, name: &str) -> Result<Self> {
+        if self.profiles.contains_key(name) {
+            return self.with_profile(name);
+        }
+        self.profile = Some(name.to_string());
+        Ok(self)
+    }
+
+    /// 将配置档案叠加到基础配置上
+    fn with_profile(self, name: &str) -> Result<Self> {
+        let overrides = self.profiles.get(name).ok_or_else(|| unknown_profile(name, &self.profiles))?;
+        let mut origins = self.origins.clone();
+        let mut table = self.base_table()?;
+        layers::merge(&mut table, overrides, &Origin::Profile(name.to_string()), &mut origins, "");
+        let mut config: Config = table.try_into()
+            .with_context(|| format!("解析配置档案失败: {}", name))?;
+        config.profiles = self.profiles;
+        config.profile = Some(name.to_string());
+        config.origins = origins;
+        Ok(config)
+    }
+
+    /// 所有生效的配置项及其来源，按 `段.字段` 列出
+    pub fn entries(&self) -> Result<Vec<(String, toml::Value, Origin)>> {
+        let mut values = Vec::new();
+        layers::flatten(&self.base_table()?, "", &mut values);
+        Ok(values
+            .into_iter()
+            .map(|(key, value)| {
+                let origin = self.origins.get(&key).cloned().unwrap_or(Origin::Default);
+                (key, value, origin)
+            })
+            .collect())
+    }
+
+    /// 影响翻译结果的配置的摘要，用于判断守护进程的配置是否与当前一致
+    ///
+    /// 默认语言和界面选项不影响翻译，不计入摘要。术语表和翻译记忆按规范化后的路径和修改时间计入，
+    /// 相对路径指向其他目录中的文件或者文件在守护进程启动后被修改时摘要不同
+    pub fn fingerprint(&self) -> Result<String> {
+        use std::hash::{Hash, Hasher};
+
+        let mut table = self.base_table()?;
+        table.remove("ui");
+        table.remove("server");
+        if let Some(toml::Value::Table(translation)) = table.get_mut("translation") {
+            translation.remove("default_from");
+            translation.remove("default_to");
+        }
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        toml::to_string(&table).context("序列化配置失败")?.hash(&mut hasher);
+
+        let mut files = Vec::new();
+        files.extend(self.translation.glossary.as_ref().map(PathBuf::from));
+        if self.memory.enabled {
+            files.push(self.memory_path()?);
+        }
+        for path in files {
+            let path = fs::canonicalize(&path).unwrap_or(path);
+            path.hash(&mut hasher);
+            fs::metadata(&path).and_then(|m| m.modified()).ok().hash(&mut hasher);
+        }
+        Ok(format!("{:016x}", hasher.finish()))
+    }
+
+    /// 不含配置档案的配置表
+    fn base_table(&self) -> Result<toml::Table> {
+        let mut table = toml::Table::try_from(self).context("序列化配置失败")?;
+        table.remove("profiles");
+        Ok(table)
+    }
+
     /// 保存配置文件
+    ///
+    /// 使用配置档案时，修改写入档案中，基础配置保持不变
     pub fn save(&self) -> Result<()> {
         let config_path = Self::config_file_path()?;
-        let content = toml::to_string_pretty(self)
+        if let Some(config_dir) = config_path.parent() {
+            // 确保配置目录存在
+            fs::create_dir_all(config_dir)
+                .context("创建配置目录失败")?;
+        }
+        let config = match &self.profile {
+            Some(name) => {
+                let mut base = Self::load_base()?;
+                let base_table = base.base_table()?;
+                let overrides = base.profiles.entry(name.clone()).or_default();
+                profile_overrides(overrides, &self.base_table()?, &base_table);
+                base
+            }
+            None => self.clone(),
+        };
+        let content = toml::to_string_pretty(&config)
             .context("序列化配置失败")?;
         
         fs::write(&config_path, content)
@@ -170,6 +433,14 @@ impl Config {
         Ok(())
     }
 
+    /// 翻译记忆库文件路径
+    pub fn memory_path(&self) -> Result<PathBuf> {
+        match &self.memory.path {
+            Some(path) => Ok(PathBuf::from(path)),
+            None => TranslationMemory::default_path(),
+        }
+    }
+
     /// 验证配置是否完整
     pub fn validate(&self) -> Result<()> {
         if self.baidu.app_id.is_empty() || self.baidu.secret_key.is_empty() {
@@ -177,4 +448,154 @@ impl Config {
         }
         Ok(())
     }
-} 
\ No newline at end of file
+}
+
+/// 配置档案不存在的错误，列出可选的档案
+fn unknown_profile(name: &str, profiles: &BTreeMap<String, toml::Table>) -> anyhow::Error {
+    let known: Vec<&str> = profiles.keys().map(String::as_str).collect();
+    if known.is_empty() {
+        anyhow::anyhow!("配置档案不存在: {} (配置文件中没有 [profiles.*])", name)
+    } else {
+        anyhow::anyhow!("配置档案不存在: {} (可选: {})", name, known.join(", "))
+    }
+}
+
+/// 更新配置档案：档案中已有的字段取当前值，其余字段只在与基础配置不同时写入
+fn profile_overrides(overrides: &mut toml::Table, current: &toml::Table, base: &toml::Table) {
+    for (key, value) in current {
+        let base_value = base.get(key);
+        match (value, base_value) {
+            (toml::Value::Table(current), Some(toml::Value::Table(base))) => {
+                let entry = overrides
+                    .entry(key.clone())
+                    .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+                if let toml::Value::Table(table) = entry {
+                    profile_overrides(table, current, base);
+                    if table.is_empty() {
+                        overrides.remove(key);
+                    }
+                }
+            }
+            _ if overrides.contains_key(key) || base_value != Some(value) => {
+                overrides.insert(key.clone(), value.clone());
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn apply_env(vars: &[(&str, &str)]) -> Result<(toml::Table, Origins)> {
+        let mut table = Config::default().base_table().unwrap();
+        let mut origins = Origins::new();
+        let vars = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        layers::apply_env(&mut table, &mut origins, vars, |table| Config::from_table(table)?.base_table())?;
+        Ok((table, origins))
+    }
+
+    #[test]
+    fn env_vars_follow_field_types_and_record_origins() {
+        let (table, origins) = apply_env(&[
+            ("FANYI_BAIDU_APP_ID", "123"),
+            ("FANYI_CACHE_CAPACITY", "5"),
+            ("FANYI_MEMORY_ENABLED", "off"),
+            ("FANYI_PROFILE", "work"),
+            ("FANYI_TRANSLATION_GLOSSARY", "terms.csv"),
+        ])
+        .unwrap();
+        let config = Config::from_table(&table).unwrap();
+        assert_eq!(config.baidu.app_id, "123");
+        assert_eq!(config.cache.capacity, 5);
+        assert!(!config.memory.enabled);
+        assert_eq!(config.translation.glossary.as_deref(), Some("terms.csv"));
+        assert_eq!(origins["baidu.app_id"], Origin::Env("FANYI_BAIDU_APP_ID".to_string()));
+        assert_eq!(origins["translation.glossary"], Origin::Env("FANYI_TRANSLATION_GLOSSARY".to_string()));
+        assert_eq!(origins.len(), 4);
+    }
+
+    #[test]
+    fn env_vars_reject_bad_values_and_unknown_fields() {
+        let error = apply_env(&[("FANYI_CACHE_CAPACITY", "many")]).unwrap_err();
+        assert!(error.to_string().contains("FANYI_CACHE_CAPACITY"));
+
+        let error = apply_env(&[("FANYI_BAIDU_APPID", "123")]).unwrap_err();
+        assert_eq!(error.to_string(), "环境变量 FANYI_BAIDU_APPID 不对应任何配置项: [baidu] 中没有 appid 字段");
+    }
+
+    fn with_work_profile() -> Config {
+        let mut config = Config::default();
+        config.baidu.app_id = "base".to_string();
+        config
+            .profiles
+            .insert("work".to_string(), toml::from_str("[baidu]\napp_id = \"work\"\n").unwrap());
+        config
+    }
+
+    #[test]
+    fn profile_overrides_base_fields_and_records_origin() {
+        let config = with_work_profile().into_profile("work").unwrap();
+        assert_eq!(config.profile.as_deref(), Some("work"));
+        assert_eq!(config.baidu.app_id, "work");
+        assert_eq!(config.origins["baidu.app_id"], Origin::Profile("work".to_string()));
+        assert!(!config.base_table().unwrap().contains_key("profiles"));
+
+        // 不存在的档案在切换时新建，加载时报错并列出可选档案
+        let config = with_work_profile().into_profile("home").unwrap();
+        assert_eq!(config.baidu.app_id, "base");
+        let error = with_work_profile().with_profile("home").unwrap_err();
+        assert_eq!(error.to_string(), "配置档案不存在: home (可选: work)");
+        let error = unknown_profile("home", &BTreeMap::new());
+        assert_eq!(error.to_string(), "配置档案不存在: home (配置文件中没有 [profiles.*])");
+    }
+
+    #[test]
+    fn profile_overrides_keep_only_changed_fields() {
+        let base = with_work_profile();
+        let mut current = base.clone().into_profile("work").unwrap();
+        current.cache.capacity += 1;
+
+        let mut overrides = base.profiles["work"].clone();
+        profile_overrides(&mut overrides, &current.base_table().unwrap(), &base.base_table().unwrap());
+        let expected: toml::Table = toml::from_str(&format!(
+            "[baidu]\napp_id = \"work\"\n[cache]\ncapacity = {}\n",
+            current.cache.capacity
+        ))
+        .unwrap();
+        assert_eq!(overrides, expected);
+    }
+
+    #[test]
+    fn fingerprint_ignores_languages_and_ui() {
+        let config = Config::default();
+        let mut other = Config::default();
+        other.translation.default_to = "ja".to_string();
+        other.ui.enable_colors = !other.ui.enable_colors;
+        assert_eq!(config.fingerprint().unwrap(), other.fingerprint().unwrap());
+
+        other.cache.capacity += 1;
+        assert_ne!(config.fingerprint().unwrap(), other.fingerprint().unwrap());
+    }
+
+    #[test]
+    fn fingerprint_changes_when_glossary_file_is_modified() {
+        let path = std::env::temp_dir().join(format!("fanyi-fingerprint-{}.csv", std::process::id()));
+        fs::write(&path, "en,zh\nhello,你好\n").unwrap();
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000)).unwrap();
+
+        let mut config = Config::default();
+        config.memory.enabled = false;
+        config.translation.glossary = Some(path.to_string_lossy().into_owned());
+        let before = config.fingerprint().unwrap();
+        assert_eq!(before, config.fingerprint().unwrap());
+
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(2_000_000)).unwrap();
+        let after = config.fingerprint().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_ne!(before, after);
+    }
+}
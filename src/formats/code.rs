@@ -0,0 +1,656 @@
+use anyhow::{Context, Result};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::translator::Translator;
+
+/// 支持翻译注释的编程语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeLanguage {
+    Rust,
+    /// C 和 C++
+    C,
+    /// JavaScript 和 TypeScript
+    JavaScript,
+    Go,
+    Java,
+    Python,
+}
+
+impl CodeLanguage {
+    /// 根据编辑器的语言标识 (LSP languageId) 判断语言
+    pub fn from_language_id(id: &str) -> Option<Self> {
+        match id {
+            "rust" => Some(CodeLanguage::Rust),
+            "c" | "cpp" | "objective-c" | "objective-cpp" => Some(CodeLanguage::C),
+            "javascript" | "javascriptreact" | "typescript" | "typescriptreact" => Some(CodeLanguage::JavaScript),
+            "go" => Some(CodeLanguage::Go),
+            "java" => Some(CodeLanguage::Java),
+            "python" => Some(CodeLanguage::Python),
+            _ => None,
+        }
+    }
+
+    /// 根据扩展名判断语言
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "rs" => Some(CodeLanguage::Rust),
+            "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" | "hxx" => Some(CodeLanguage::C),
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => Some(CodeLanguage::JavaScript),
+            "go" => Some(CodeLanguage::Go),
+            "java" => Some(CodeLanguage::Java),
+            "py" | "pyi" => Some(CodeLanguage::Python),
+            _ => None,
+        }
+    }
+}
+
+/// 目录遍历时跳过的目录
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "vendor", "build", "dist", "__pycache__"];
+
+/// 编译指令、lint 开关等机器读取的注释，翻译后会失效
+const DIRECTIVE_PREFIXES: &[&str] = &[
+    "go:", "+build", "nolint", "NOLINT", "eslint-", "@ts-", "prettier-ignore", "noqa", "type:",
+    "pylint:", "-*-", "clang-format", "SPDX-License-Identifier",
+];
+
+/// 注释的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CommentKind {
+    /// `//`、`///`、`//!` 和 `#` 注释
+    Line,
+    /// `/* */` 和 `/** */` 注释
+    Block,
+    /// Python 文档字符串
+    Docstring,
+    /// 字符串字面量，只用于定位光标处的文本，不参与注释翻译
+    Literal,
+}
+
+/// 源文件中的一条注释或字符串字面量
+struct Comment {
+    kind: CommentKind,
+    range: Range<usize>,
+}
+
+/// 收集路径下所有支持的源文件，目录会递归遍历并跳过隐藏目录和构建目录
+pub fn collect_source_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    let mut entries: Vec<_> = std::fs::read_dir(path)
+        .with_context(|| format!("读取目录失败: {}", path.display()))?
+        .collect::<std::io::Result<_>>()?;
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let entry_path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry_path.is_dir() {
+            if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                files.extend(collect_source_files(&entry_path)?);
+            }
+        } else if CodeLanguage::from_path(&entry_path).is_some() {
+            files.push(entry_path);
+        }
+    }
+    Ok(files)
+}
+
+/// 翻译源代码中的注释和文档字符串，返回新内容和翻译的行数
+///
+/// 字符串字面量和代码保持不变；注释逐行翻译，注释标记、缩进和行数不变，
+/// 因此结果可以直接用行对齐的 diff 查看
+pub async fn translate_code(
+    content: &str,
+    language: CodeLanguage,
+    translator: &dyn Translator,
+    from: &str,
+    to: &str,
+) -> Result<(String, usize)> {
+    let comments = scan(content, language);
+    let lines = prose_lines(content, &comments);
+
+    let texts: Vec<String> = lines.iter().map(|(_, r)| content[r.clone()].to_string()).collect();
+    let translated = translator.translate_batch(&texts, from, to).await?;
+
+    let mut output = String::with_capacity(content.len());
+    let mut cursor = 0;
+    let mut count = 0;
+    for ((kind, range), text) in lines.iter().zip(translated) {
+        // 译文不能包含换行或提前结束注释的标记
+        let text = text.replace(['\r', '\n'], " ");
+        let text = match kind {
+            CommentKind::Line | CommentKind::Literal => text,
+            // Rust 的块注释可以嵌套，译文保留原文中成对的 /* */ 时不需要改写
+            CommentKind::Block
+                if text.matches("/*").count() == content[range.clone()].matches("/*").count()
+                    && text.matches("*/").count() == content[range.clone()].matches("*/").count() =>
+            {
+                text
+            }
+            CommentKind::Block => text.replace("/*", "/ *").replace("*/", "* /"),
+            CommentKind::Docstring => text.replace("\"\"\"", "\\\"\\\"\\\"").replace("'''", "\\'\\'\\'"),
+        };
+        if text != content[range.clone()] {
+            count += 1;
+        }
+        output.push_str(&content[cursor..range.start]);
+        output.push_str(text.trim());
+        cursor = range.end;
+    }
+    output.push_str(&content[cursor..]);
+
+    Ok((output, count))
+}
+
+/// 找出注释中需要翻译的行
+///
+/// 跳过没有文字的行、编译指令，以及文档注释中 ``` 围起的代码块和 Python doctest
+/// （`>>>`、`...` 及其后直到空行的期望输出），翻译这些内容会破坏文档测试
+fn prose_lines(content: &str, comments: &[Comment]) -> Vec<(CommentKind, Range<usize>)> {
+    let mut lines = Vec::new();
+    let mut in_fence = false;
+    let mut in_doctest = false;
+    let mut previous: Option<&Comment> = None;
+    let mut previous_line_end = None;
+
+    for comment in comments.iter().filter(|c| c.kind != CommentKind::Literal) {
+        // 连续的单行注释（如多行 ///）属于同一段文档，其他情况重新开始
+        if !previous.is_some_and(|p| adjacent_line_comments(content, p, comment)) {
+            in_fence = false;
+            in_doctest = false;
+            previous_line_end = None;
+        }
+        previous = Some(comment);
+
+        for range in comment_lines(content, comment) {
+            let text = &content[range.clone()];
+            // 空行结束 doctest 的期望输出
+            if previous_line_end.is_some_and(|end| content[end..range.start].matches('\n').count() > 1) {
+                in_doctest = false;
+            }
+            previous_line_end = Some(range.end);
+
+            if text.starts_with("```") || text.starts_with("~~~") {
+                in_fence = !in_fence;
+                continue;
+            }
+            if text.starts_with(">>>") {
+                in_doctest = true;
+            }
+            if in_fence || in_doctest {
+                continue;
+            }
+            if text.chars().any(char::is_alphabetic) && !DIRECTIVE_PREFIXES.iter().any(|p| text.starts_with(p)) {
+                lines.push((comment.kind, range));
+            }
+        }
+    }
+    lines
+}
+
+/// 两条单行注释之间只隔着一个换行和缩进
+fn adjacent_line_comments(content: &str, a: &Comment, b: &Comment) -> bool {
+    let between = &content[a.range.end..b.range.start];
+    a.kind == CommentKind::Line
+        && b.kind == CommentKind::Line
+        && between.trim().is_empty()
+        && between.matches('\n').count() == 1
+}
+
+/// 把注释拆成逐行的正文范围，去掉注释标记、装饰星号和首尾空白
+fn comment_lines(src: &str, comment: &Comment) -> Vec<Range<usize>> {
+    let text = &src[comment.range.clone()];
+
+    // 注释开头的标记
+    let open_len = match comment.kind {
+        CommentKind::Line | CommentKind::Literal => text
+            .find(|c: char| !matches!(c, '/' | '#' | '!'))
+            .unwrap_or(text.len()),
+        CommentKind::Block => text[2..]
+            .find(|c: char| !matches!(c, '*' | '!'))
+            .map_or(text.len(), |i| i + 2),
+        CommentKind::Docstring => {
+            let quote = text.find(['"', '\'']).unwrap_or(0);
+            quote + 3
+        }
+    };
+    // 注释结尾的标记
+    let close_len = match comment.kind {
+        CommentKind::Line | CommentKind::Literal => 0,
+        CommentKind::Block => 2,
+        CommentKind::Docstring => 3,
+    };
+    let body_start = comment.range.start + open_len.min(text.len());
+    let body_end = (comment.range.end - close_len).max(body_start);
+
+    let mut ranges = Vec::new();
+    let mut line_start = body_start;
+    for line in src[body_start..body_end].split_inclusive('\n') {
+        let line_end = line_start + line.len();
+        let mut start = line_start;
+        let mut end = line_start + line.trim_end().len();
+
+        // 去掉缩进以及块注释每行开头的 " * "
+        start += src[start..end].len() - src[start..end].trim_start().len();
+        if comment.kind == CommentKind::Block && start > body_start {
+            start += src[start..end].len() - src[start..end].trim_start_matches('*').len();
+            start += src[start..end].len() - src[start..end].trim_start().len();
+        }
+        // 块注释末尾的装饰星号，例如 "text **/"
+        if comment.kind == CommentKind::Block && line_end >= body_end {
+            end = start + src[start..end].trim_end_matches('*').trim_end().len();
+        }
+
+        if end > start {
+            ranges.push(start..end);
+        }
+        line_start = line_end;
+    }
+    ranges
+}
+
+/// 光标处的注释或字符串字面量，返回其范围和去掉注释标记、引号后的正文
+///
+/// 相邻的单行注释（例如连续的 `///` 文档注释）合并为一段
+pub fn text_at(content: &str, language: CodeLanguage, offset: usize) -> Option<(Range<usize>, String)> {
+    let comments = scan(content, language);
+    let index = comments
+        .iter()
+        .position(|c| c.range.contains(&offset))?;
+
+    let mut first = index;
+    let mut last = index;
+    if comments[index].kind == CommentKind::Line {
+        let adjacent = |a: &Comment, b: &Comment| adjacent_line_comments(content, a, b);
+        while first > 0 && adjacent(&comments[first - 1], &comments[first]) {
+            first -= 1;
+        }
+        while last + 1 < comments.len() && adjacent(&comments[last], &comments[last + 1]) {
+            last += 1;
+        }
+    }
+
+    let range = comments[first].range.start..comments[last].range.end;
+    let text = if comments[index].kind == CommentKind::Literal {
+        literal_body(&content[range.clone()]).to_string()
+    } else {
+        comments[first..=last]
+            .iter()
+            .flat_map(|comment| comment_lines(content, comment))
+            .map(|line| &content[line])
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    (!text.trim().is_empty()).then_some((range, text))
+}
+
+/// 去掉字符串字面量的前缀、引号和原始字符串的定界符
+fn literal_body(literal: &str) -> &str {
+    // C++ 原始字符串 R"delim(...)delim"
+    if let Some(rest) = literal.strip_prefix("R\"") {
+        let open = rest.find('(').map_or(0, |i| i + 1);
+        let close = rest.rfind(')').filter(|&c| c >= open).unwrap_or(rest.len());
+        return &rest[open..close];
+    }
+
+    let Some(open) = literal.find(['"', '\'', '`']) else {
+        return literal;
+    };
+    let quote = literal.as_bytes()[open];
+    let quotes = if literal.as_bytes()[open..].starts_with(&[quote; 3]) && literal.len() >= open + 6 { 3 } else { 1 };
+    let body = &literal[open + quotes..];
+    let body = body.trim_end_matches('#');
+    let mut end = body.len();
+    for _ in 0..quotes {
+        if body.as_bytes()[..end].last() == Some(&quote) {
+            end -= 1;
+        }
+    }
+    &body[..end]
+}
+
+/// 扫描源代码，找出注释、文档字符串和字符串字面量
+fn scan(src: &str, language: CodeLanguage) -> Vec<Comment> {
+    let bytes = src.as_bytes();
+    let mut comments = Vec::new();
+    let mut i = 0;
+    // 上一个有效字符，用于判断 JavaScript 正则字面量
+    let mut prev = b'\n';
+    // Python: 下一个语句是否可能是文档字符串（文件开头或以冒号结尾的行之后）
+    let mut expect_docstring = true;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        // 注释和字符串的标记都是 ASCII，多字节字符只会出现在标识符或文本中
+        if !c.is_ascii() {
+            prev = b'_';
+            expect_docstring = false;
+            i += 1;
+            continue;
+        }
+        let rest = &src[i..];
+        let ident_before = i > 0 && (bytes[i - 1].is_ascii_alphanumeric() || bytes[i - 1] == b'_');
+
+        if language == CodeLanguage::Python {
+            if c == b'#' {
+                let end = line_end(src, i);
+                // 第一行的 #! 是解释器声明
+                if !(i == 0 && rest.starts_with("#!")) {
+                    comments.push(Comment { kind: CommentKind::Line, range: i..end });
+                }
+                i = end;
+                continue;
+            }
+            if c == b'"' || c == b'\'' {
+                let triple = rest.starts_with("\"\"\"") || rest.starts_with("'''");
+                let end = if triple {
+                    let close = &rest[..3];
+                    find_unescaped(src, i + 3, close).map_or(src.len(), |e| e + 3)
+                } else {
+                    string_end(bytes, i, c, false)
+                };
+                let at_line_start = src[..i].rsplit('\n').next().is_some_and(|p| p.trim().is_empty());
+                let kind = if triple && expect_docstring && at_line_start {
+                    CommentKind::Docstring
+                } else {
+                    CommentKind::Literal
+                };
+                comments.push(Comment { kind, range: i..end });
+                expect_docstring = false;
+                i = end;
+                continue;
+            }
+            if !c.is_ascii_whitespace() {
+                expect_docstring = c == b':';
+            }
+            i += 1;
+            continue;
+        }
+
+        // 以下为 C 风格语法
+        if rest.starts_with("//") {
+            let end = line_end(src, i);
+            comments.push(Comment { kind: CommentKind::Line, range: i..end });
+            i = end;
+            continue;
+        }
+        if rest.starts_with("/*") {
+            let end = block_comment_end(src, i, language == CodeLanguage::Rust);
+            comments.push(Comment { kind: CommentKind::Block, range: i..end });
+            i = end;
+            continue;
+        }
+
+        // 字面量的结束位置，以及是否为字符串（字符字面量和正则不算）
+        let (end, literal) = match (language, c) {
+            (CodeLanguage::Java, b'"') if rest.starts_with("\"\"\"") => {
+                (find_unescaped(src, i + 3, "\"\"\"").map_or(src.len(), |e| e + 3), true)
+            }
+            (CodeLanguage::Rust, b'"') => (string_end(bytes, i, b'"', true), true),
+            (_, b'"') => (string_end(bytes, i, b'"', false), true),
+            (CodeLanguage::Rust, b'r') if !ident_before && raw_string_start(rest).is_some() => {
+                let hashes = raw_string_start(rest).unwrap_or(0);
+                let close = format!("\"{}", "#".repeat(hashes));
+                let end = src[i + hashes + 2..]
+                    .find(&close)
+                    .map_or(src.len(), |e| i + hashes + 2 + e + close.len());
+                (end, true)
+            }
+            (CodeLanguage::Rust, b'\'') => (rust_quote_end(src, i), false),
+            // C++14 数字分隔符，例如 1'000'000
+            (CodeLanguage::C, b'\'') if i > 0 && bytes[i - 1].is_ascii_hexdigit() => (i + 1, false),
+            (CodeLanguage::C, b'R') if !ident_before && rest.starts_with("R\"") => {
+                let delimiter_end = rest.find('(').unwrap_or(rest.len());
+                let close = format!("){}\"", &rest[2..delimiter_end]);
+                (rest.find(&close).map_or(src.len(), |e| i + e + close.len()), true)
+            }
+            // JavaScript 的单引号为字符串，其他语言为字符字面量
+            (_, b'\'') => (string_end(bytes, i, b'\'', false), language == CodeLanguage::JavaScript),
+            (CodeLanguage::JavaScript | CodeLanguage::Go, b'`') => (string_end(bytes, i, b'`', true), true),
+            (CodeLanguage::JavaScript, b'/') if b"(,=:[!&|?{};+-*%<>~^\n".contains(&prev) => {
+                (regex_end(bytes, i), false)
+            }
+            _ => {
+                if !c.is_ascii_whitespace() {
+                    prev = c;
+                }
+                i += 1;
+                continue;
+            }
+        };
+        if literal {
+            comments.push(Comment { kind: CommentKind::Literal, range: i..end });
+        }
+        prev = bytes[end.saturating_sub(1).max(i)];
+        i = end.max(i + 1);
+    }
+
+    comments
+}
+
+fn line_end(src: &str, start: usize) -> usize {
+    src[start..]
+        .find(['\r', '\n'])
+        .map_or(src.len(), |i| start + i)
+}
+
+/// 块注释的结束位置，Rust 的块注释可以嵌套
+fn block_comment_end(src: &str, start: usize, nested: bool) -> usize {
+    let bytes = src.as_bytes();
+    let mut depth = 0;
+    let mut i = start;
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'/' && bytes[i + 1] == b'*' && (nested || depth == 0) {
+            depth += 1;
+            i += 2;
+        } else if bytes[i] == b'*' && bytes[i + 1] == b'/' {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    src.len()
+}
+
+/// 字符串字面量的结束位置（含结尾引号），未闭合的单行字符串在行尾结束
+fn string_end(bytes: &[u8], start: usize, quote: u8, multiline: bool) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'\n' if !multiline => return i,
+            c if c == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// 查找未被反斜杠转义的结束标记
+fn find_unescaped(src: &str, from: usize, close: &str) -> Option<usize> {
+    let bytes = src.as_bytes();
+    let mut i = from;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' {
+            i += 2;
+        } else if bytes[i..].starts_with(close.as_bytes()) {
+            return Some(i);
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+/// Rust 原始字符串 r"..." / r#"..."# 的 # 数量
+fn raw_string_start(rest: &str) -> Option<usize> {
+    let hashes = rest[1..].bytes().take_while(|b| *b == b'#').count();
+    rest[1 + hashes..].starts_with('"').then_some(hashes)
+}
+
+/// Rust 中的单引号既可能是字符字面量也可能是生命周期
+fn rust_quote_end(src: &str, start: usize) -> usize {
+    let bytes = src.as_bytes();
+    if bytes.get(start + 1) == Some(&b'\\') {
+        return string_end(bytes, start, b'\'', false);
+    }
+    // 'x' 为字符字面量（x 可能是多字节字符），否则为生命周期
+    let char_len = src[start + 1..].chars().next().map_or(1, char::len_utf8);
+    if bytes.get(start + 1 + char_len) == Some(&b'\'') {
+        start + char_len + 2
+    } else {
+        start + 1
+    }
+}
+
+/// JavaScript 正则字面量的结束位置
+fn regex_end(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    let mut in_class = false;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'[' => in_class = true,
+            b']' => in_class = false,
+            b'/' if !in_class => return i + 1,
+            // 不是正则（例如除法），只跳过斜杠本身
+            b'\n' => return start + 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    start + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::stub::UppercaseTranslator;
+
+    async fn translate(content: &str, language: CodeLanguage) -> (String, usize) {
+        translate_code(content, language, &UppercaseTranslator, "en", "zh").await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn rust_comments_and_doc_tests() {
+        let content = r##"//! Crate docs.
+/// Adds two numbers.
+///
+/// ```
+/// assert_eq!(add(1, 2), 3);
+/// ```
+fn add(a: i32, b: i32) -> i32 {
+    let s = "// not a comment"; // trailing note
+    let r = r#"/* raw */"#;
+    let c = '"'; /* outer /* nested */ still comment */
+    a + b
+}
+"##;
+        let expected = r##"//! CRATE DOCS.
+/// ADDS TWO NUMBERS.
+///
+/// ```
+/// assert_eq!(add(1, 2), 3);
+/// ```
+fn add(a: i32, b: i32) -> i32 {
+    let s = "// not a comment"; // TRAILING NOTE
+    let r = r#"/* raw */"#;
+    let c = '"'; /* OUTER /* NESTED */ STILL COMMENT */
+    a + b
+}
+"##;
+        assert_eq!(translate(content, CodeLanguage::Rust).await, (expected.to_string(), 4));
+    }
+
+    #[tokio::test]
+    async fn python_docstrings_and_doctests() {
+        let content = r##"#!/usr/bin/env python
+# -*- coding: utf-8 -*-
+def greet(name):
+    """Say hello.
+
+    >>> greet("bob")
+    'hello bob'
+
+    Returns nothing.
+    """
+    text = """not a docstring"""
+    return "# not a comment"  # noqa: E501
+"##;
+        let expected = r##"#!/usr/bin/env python
+# -*- coding: utf-8 -*-
+def greet(name):
+    """SAY HELLO.
+
+    >>> greet("bob")
+    'hello bob'
+
+    RETURNS NOTHING.
+    """
+    text = """not a docstring"""
+    return "# not a comment"  # noqa: E501
+"##;
+        assert_eq!(translate(content, CodeLanguage::Python).await, (expected.to_string(), 2));
+    }
+
+    #[tokio::test]
+    async fn javascript_regex_and_directives() {
+        let content = r#"// eslint-disable-next-line no-console
+const re = /\/\/ not a comment/g; // match slashes
+const url = 'http://example.com'; /* block
+ * second line
+ */
+const tpl = `// ${x}`;
+"#;
+        let expected = r#"// eslint-disable-next-line no-console
+const re = /\/\/ not a comment/g; // MATCH SLASHES
+const url = 'http://example.com'; /* BLOCK
+ * SECOND LINE
+ */
+const tpl = `// ${x}`;
+"#;
+        assert_eq!(translate(content, CodeLanguage::JavaScript).await, (expected.to_string(), 3));
+    }
+
+    #[tokio::test]
+    async fn go_and_c_comments() {
+        let go = "//go:generate stringer\n// Package x does things.\npackage x\n\nvar s = `// raw`\n";
+        assert_eq!(
+            translate(go, CodeLanguage::Go).await.0,
+            "//go:generate stringer\n// PACKAGE X DOES THINGS.\npackage x\n\nvar s = `// raw`\n"
+        );
+
+        let c = "int n = 1'000; // count\nconst char *s = R\"x(/* raw */)x\"; /** Doc. */\n";
+        assert_eq!(
+            translate(c, CodeLanguage::C).await.0,
+            "int n = 1'000; // COUNT\nconst char *s = R\"x(/* raw */)x\"; /** DOC. */\n"
+        );
+    }
+
+    #[test]
+    fn detects_languages() {
+        assert_eq!(CodeLanguage::from_path(Path::new("a/b.tsx")), Some(CodeLanguage::JavaScript));
+        assert_eq!(CodeLanguage::from_path(Path::new("x.HPP")), Some(CodeLanguage::C));
+        assert_eq!(CodeLanguage::from_path(Path::new("README.md")), None);
+        assert_eq!(CodeLanguage::from_language_id("python"), Some(CodeLanguage::Python));
+    }
+
+    #[test]
+    fn text_at_merges_adjacent_line_comments() {
+        let content = "/// First line.\n/// Second line.\nfn f() { let s = \"a string\"; }\n";
+        let (range, text) = text_at(content, CodeLanguage::Rust, 5).unwrap();
+        assert_eq!(range, 0..32);
+        assert_eq!(text, "First line.\nSecond line.");
+        let offset = content.find("a string").unwrap();
+        assert_eq!(text_at(content, CodeLanguage::Rust, offset).unwrap().1, "a string");
+    }
+}
@@ -0,0 +1,474 @@
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+use crate::formats::xml::{self, Token};
+use crate::language::registry;
+use crate::translator::protect::Protected;
+use crate::translator::Translator;
+
+/// 内容不翻译的元素
+const SKIP_ELEMENTS: &[&str] = &["script", "style", "code", "pre", "textarea", "kbd", "samp", "var"];
+
+/// 行内元素，连同其中的文本作为同一个句子翻译
+const INLINE_ELEMENTS: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "br", "cite", "code", "data", "dfn", "em", "i", "img", "kbd",
+    "mark", "q", "s", "samp", "small", "span", "strong", "sub", "sup", "time", "u", "var", "wbr",
+];
+
+/// 没有结束标签的元素
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// 需要翻译的属性
+const TRANSLATED_ATTRS: &[&str] = &["title", "alt", "placeholder"];
+
+/// 开始标签中的一个属性
+struct AttrSpan {
+    name: String,
+    /// 属性值在标签中的范围（不含引号）
+    value: Range<usize>,
+    quoted: bool,
+}
+
+/// 需要翻译的属性值
+struct AttrJob {
+    /// 所在开始标签的下标
+    token: usize,
+    /// 属性值在标签中的范围
+    value: Range<usize>,
+    quoted: bool,
+    text: String,
+}
+
+/// 句子中的一个片段
+enum Piece {
+    /// 文本节点
+    Text(usize),
+    /// 行内标签，翻译时整体保护
+    Tag(usize),
+    /// 不翻译的行内元素或注释，按原文整体保护
+    Raw(Range<usize>),
+}
+
+/// 由文本节点和行内元素组成的一个句子
+struct Segment {
+    range: Range<usize>,
+    pieces: Vec<Piece>,
+}
+
+/// 翻译 HTML 文档
+///
+/// 翻译文本节点以及 title、alt、placeholder 属性，跳过 script、style、code、pre
+/// 和 translate="no" 的元素，并将 <html> 的 lang 属性改为目标语言；
+/// 段落中的行内标签连同文本一起提交以保持句子完整，其余内容逐字节保留
+pub async fn translate_html(
+    content: &str,
+    translator: &dyn Translator,
+    from: &str,
+    to: &str,
+) -> Result<String> {
+    let tokens = xml::tokenize_html(content);
+    let (segments, attr_jobs, html_tag) = scan(content, &tokens);
+
+    // 先翻译属性，段落中的行内标签需要使用改写后的标签
+    let texts: Vec<String> = attr_jobs.iter().map(|job| job.text.clone()).collect();
+    let translated = translator.translate_batch(&texts, from, to).await?;
+
+    let mut tag_edits: HashMap<usize, Vec<(Range<usize>, String)>> = HashMap::new();
+    for (job, text) in attr_jobs.iter().zip(translated) {
+        // 属性值可能使用单引号，撇号一并转义
+        let escaped = xml::escape_attr(&text).replace('\'', "&#39;");
+        let value = if job.quoted { escaped } else { format!("\"{}\"", escaped) };
+        tag_edits.entry(job.token).or_default().push((job.value.clone(), value));
+    }
+    if let Some(index) = html_tag {
+        let lang = registry::locale_tag(to);
+        let raw = &content[tokens[index].range().clone()];
+        let edit = match attr_spans(raw).into_iter().find(|a| a.name == "lang") {
+            Some(attr) if attr.quoted => (attr.value, lang.to_string()),
+            Some(attr) => (attr.value, format!("\"{}\"", lang)),
+            None => {
+                let at = "<html".len();
+                (at..at, format!(" lang=\"{}\"", lang))
+            }
+        };
+        tag_edits.entry(index).or_default().push(edit);
+    }
+
+    let tags: HashMap<usize, String> = tag_edits
+        .into_iter()
+        .map(|(index, mut edits)| {
+            let raw = &content[tokens[index].range().clone()];
+            edits.sort_by_key(|(range, _)| range.start);
+            (index, apply_edits(raw, &edits))
+        })
+        .collect();
+    let tag_text = |index: usize| -> String {
+        tags.get(&index)
+            .cloned()
+            .unwrap_or_else(|| content[tokens[index].range().clone()].to_string())
+    };
+
+    // 句子首尾的空白不参与翻译，段落内的换行和缩进按原文保护
+    let inner: Vec<Range<usize>> = segments
+        .iter()
+        .map(|segment| {
+            let raw = &content[segment.range.clone()];
+            let start = segment.range.start + raw.len() - raw.trim_start().len();
+            start..start + raw.trim().len()
+        })
+        .collect();
+    let protected: Vec<Protected> = segments
+        .iter()
+        .zip(&inner)
+        .map(|(segment, inner)| {
+            let mut protected = Protected::default();
+            for piece in &segment.pieces {
+                match piece {
+                    Piece::Text(index) => {
+                        let range = tokens[*index].range();
+                        let range = range.start.max(inner.start)..range.end.min(inner.end);
+                        if range.start < range.end {
+                            push_html_text(&mut protected, &content[range]);
+                        }
+                    }
+                    Piece::Tag(index) => protected.push_protected(&tag_text(*index)),
+                    Piece::Raw(range) => protected.push_protected(&content[range.clone()]),
+                }
+            }
+            protected
+        })
+        .collect();
+
+    let queries: Vec<String> = protected.iter().map(|p| p.text.clone()).collect();
+    let translated = translator.translate_batch(&queries, from, to).await?;
+
+    let mut edits = Vec::new();
+    for ((protected, inner), text) in protected.iter().zip(inner).zip(translated) {
+        let restored = protected
+            .restore(&xml::escape_text(&text))
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        edits.push((inner, restored));
+    }
+    // 不在句子中的开始标签（例如带 title 的 <div>）单独改写
+    let in_segment = |index: usize| {
+        let range = tokens[index].range();
+        segments.iter().any(|s| s.range.start <= range.start && range.end <= s.range.end)
+    };
+    for (index, tag) in &tags {
+        if !in_segment(*index) {
+            edits.push((tokens[*index].range().clone(), tag.clone()));
+        }
+    }
+    edits.sort_by_key(|(range, _)| range.start);
+
+    Ok(apply_edits(content, &edits))
+}
+
+/// 遍历标记，找出需要翻译的句子、属性以及 <html> 标签的位置
+fn scan(content: &str, tokens: &[Token]) -> (Vec<Segment>, Vec<AttrJob>, Option<usize>) {
+    let mut segments = Vec::new();
+    let mut attr_jobs = Vec::new();
+    let mut html_tag = None;
+
+    // 打开的元素及其内容是否需要翻译
+    let mut stack: Vec<(String, bool)> = Vec::new();
+    let mut pieces: Vec<Piece> = Vec::new();
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        let translatable = stack.last().is_none_or(|(_, t)| *t);
+        let raw = &content[token.range().clone()];
+
+        match token {
+            Token::Text { .. } => {
+                if translatable {
+                    pieces.push(Piece::Text(i));
+                }
+            }
+            Token::Other { .. } => {
+                // 句子中的注释原样保留，不打断句子
+                if !pieces.is_empty() {
+                    pieces.push(Piece::Raw(token.range().clone()));
+                }
+            }
+            Token::Start { name, self_closing, .. } => {
+                let name = name.to_ascii_lowercase();
+                let attrs = attr_spans(raw);
+                let translate_attr = attrs
+                    .iter()
+                    .find(|a| a.name == "translate")
+                    .map(|a| raw[a.value.clone()].to_ascii_lowercase());
+                let own = match translate_attr.as_deref() {
+                    Some("no") => false,
+                    Some("yes") => true,
+                    _ => translatable && !SKIP_ELEMENTS.contains(&name.as_str()),
+                };
+                let is_void = *self_closing || VOID_ELEMENTS.contains(&name.as_str());
+
+                if name == "html" {
+                    html_tag = Some(i);
+                }
+                if own {
+                    for attr in attrs.into_iter().filter(|a| TRANSLATED_ATTRS.contains(&a.name.as_str())) {
+                        let text = xml::unescape(&raw[attr.value.clone()]);
+                        if !text.trim().is_empty() {
+                            attr_jobs.push(AttrJob {
+                                token: i,
+                                value: attr.value,
+                                quoted: attr.quoted,
+                                text,
+                            });
+                        }
+                    }
+                }
+
+                if INLINE_ELEMENTS.contains(&name.as_str()) && translatable {
+                    if !own && !is_void {
+                        // 不翻译的行内元素（如 <code>）连同内容整体保护
+                        let end = matching_end(tokens, i, &name);
+                        let end_pos = tokens.get(end).map_or(content.len(), |t| t.range().end);
+                        pieces.push(Piece::Raw(token.range().start..end_pos));
+                        i = end + 1;
+                        continue;
+                    }
+                    pieces.push(Piece::Tag(i));
+                } else {
+                    close_segment(content, tokens, &mut pieces, &mut segments);
+                }
+                if !is_void {
+                    stack.push((name, own));
+                }
+            }
+            Token::End { name, .. } => {
+                let name = name.to_ascii_lowercase();
+                if INLINE_ELEMENTS.contains(&name.as_str()) && translatable {
+                    pieces.push(Piece::Tag(i));
+                } else {
+                    close_segment(content, tokens, &mut pieces, &mut segments);
+                }
+                // 省略了结束标签的元素（如 <p>、<li>）在此一并关闭
+                if let Some(pos) = stack.iter().rposition(|(n, _)| *n == name) {
+                    stack.truncate(pos);
+                }
+            }
+        }
+        i += 1;
+    }
+    close_segment(content, tokens, &mut pieces, &mut segments);
+
+    (segments, attr_jobs, html_tag)
+}
+
+/// 结束当前句子，只有包含文字的句子才需要翻译
+fn close_segment(content: &str, tokens: &[Token], pieces: &mut Vec<Piece>, segments: &mut Vec<Segment>) {
+    let pieces = std::mem::take(pieces);
+    let has_text = pieces.iter().any(|p| match p {
+        Piece::Text(index) => content[tokens[*index].range().clone()].chars().any(char::is_alphabetic),
+        _ => false,
+    });
+    if !has_text {
+        return;
+    }
+
+    let piece_range = |piece: &Piece| match piece {
+        Piece::Text(index) | Piece::Tag(index) => tokens[*index].range().clone(),
+        Piece::Raw(range) => range.clone(),
+    };
+    let start = pieces.first().map(|p| piece_range(p).start).unwrap_or_default();
+    let end = pieces.last().map(|p| piece_range(p).end).unwrap_or_default();
+    segments.push(Segment {
+        range: start..end,
+        pieces,
+    });
+}
+
+/// 找到与开始标签对应的结束标签下标
+fn matching_end(tokens: &[Token], start: usize, name: &str) -> usize {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(start + 1) {
+        match token {
+            Token::Start { name: n, self_closing: false, .. } if n.eq_ignore_ascii_case(name) => depth += 1,
+            Token::End { name: n, .. } if n.eq_ignore_ascii_case(name) => {
+                if depth == 0 {
+                    return i;
+                }
+                depth -= 1;
+            }
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// 将 HTML 文本追加到句子中：常见实体反转义，&nbsp; 等其他实体原样保护
+fn push_html_text(protected: &mut Protected, raw: &str) {
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        push_spaced_text(protected, &rest[..amp]);
+        rest = &rest[amp..];
+        let entity_len = rest[1..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '#'))
+            .filter(|i| rest[1 + i..].starts_with(';'))
+            .map(|i| i + 2);
+        match entity_len {
+            Some(len) => {
+                let entity = &rest[..len];
+                let decoded = xml::unescape(entity);
+                if decoded == entity {
+                    protected.push_protected(entity);
+                } else {
+                    protected.push_text(&decoded);
+                }
+                rest = &rest[len..];
+            }
+            None => {
+                protected.push_text("&");
+                rest = &rest[1..];
+            }
+        }
+    }
+    push_spaced_text(protected, rest);
+}
+
+/// 追加文本，单个空格以外的空白（换行、缩进、连续空格）按原文保护
+fn push_spaced_text(protected: &mut Protected, text: &str) {
+    static WHITESPACE: OnceLock<Regex> = OnceLock::new();
+    let pattern = WHITESPACE.get_or_init(|| Regex::new(r"\s+").unwrap());
+
+    let mut cursor = 0;
+    for m in pattern.find_iter(text).filter(|m| m.as_str() != " ") {
+        protected.push_text(&text[cursor..m.start()]);
+        protected.push_break(m.as_str());
+        cursor = m.end();
+    }
+    protected.push_text(&text[cursor..]);
+}
+
+/// 解析开始标签中的属性，支持未加引号和没有值的属性
+fn attr_spans(tag: &str) -> Vec<AttrSpan> {
+    let bytes = tag.as_bytes();
+    let mut attrs = Vec::new();
+    // 跳过 '<' 和标签名
+    let mut pos = tag
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .unwrap_or(tag.len());
+
+    while pos < bytes.len() {
+        while pos < bytes.len() && (bytes[pos].is_ascii_whitespace() || bytes[pos] == b'/') {
+            pos += 1;
+        }
+        if pos >= bytes.len() || bytes[pos] == b'>' {
+            break;
+        }
+
+        let name_start = pos;
+        while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() && !matches!(bytes[pos], b'=' | b'>' | b'/') {
+            pos += 1;
+        }
+        let name = tag[name_start..pos].to_ascii_lowercase();
+
+        let mut after = pos;
+        while after < bytes.len() && bytes[after].is_ascii_whitespace() {
+            after += 1;
+        }
+        if after >= bytes.len() || bytes[after] != b'=' {
+            // 没有值的布尔属性
+            continue;
+        }
+        pos = after + 1;
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+
+        let (value, quoted) = match bytes.get(pos) {
+            Some(&quote @ (b'"' | b'\'')) => {
+                let end = tag[pos + 1..]
+                    .find(quote as char)
+                    .map_or(tag.len(), |i| pos + 1 + i);
+                let value = pos + 1..end;
+                pos = (end + 1).min(tag.len());
+                (value, true)
+            }
+            _ => {
+                let start = pos;
+                while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() && bytes[pos] != b'>' {
+                    pos += 1;
+                }
+                (start..pos, false)
+            }
+        };
+        attrs.push(AttrSpan { name, value, quoted });
+    }
+
+    attrs
+}
+
+/// 按顺序替换若干不重叠的区间
+fn apply_edits(src: &str, edits: &[(Range<usize>, String)]) -> String {
+    let mut output = String::with_capacity(src.len());
+    let mut cursor = 0;
+    for (range, text) in edits {
+        output.push_str(&src[cursor..range.start]);
+        output.push_str(text);
+        cursor = range.end;
+    }
+    output.push_str(&src[cursor..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::stub::UppercaseTranslator;
+
+    async fn translate(content: &str, to: &str) -> String {
+        translate_html(content, &UppercaseTranslator, "en", to).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn skips_code_like_elements_and_translate_no() {
+        let content = "<p>hello <code>let x</code> world</p>\n<pre>keep\n  this</pre>\n<script>var a = 'b';</script>\n<style>p { color: red }</style>\n<div translate=\"no\">brand <b>name</b></div>\n<p translate=\"no\">skip <span translate=\"yes\">but this</span></p>\n";
+        assert_eq!(
+            translate(content, "zh").await,
+            "<p>HELLO <code>let x</code> WORLD</p>\n<pre>keep\n  this</pre>\n<script>var a = 'b';</script>\n<style>p { color: red }</style>\n<div translate=\"no\">brand <b>name</b></div>\n<p translate=\"no\">skip <span translate=\"yes\">BUT THIS</span></p>\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn translates_title_alt_and_placeholder() {
+        let content = "<img src=\"a.png\" alt=\"a cat\"><input placeholder='your name' type=text>\n<div title=\"tip\" class=\"x\">text <a href=\"/x\" title=\"it's here\">link</a></div>\n";
+        assert_eq!(
+            translate(content, "zh").await,
+            "<img src=\"a.png\" alt=\"A CAT\"><input placeholder='YOUR NAME' type=text>\n<div title=\"TIP\" class=\"x\">TEXT <a href=\"/x\" title=\"IT&#39;S HERE\">LINK</a></div>\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn rewrites_or_adds_lang() {
+        assert_eq!(
+            translate("<html lang=\"en\"><body><p>hi</p></body></html>", "pt_br").await,
+            "<html lang=\"pt-BR\"><body><p>HI</p></body></html>"
+        );
+        assert_eq!(
+            translate("<html><p>hi</p></html>", "zh-hant").await,
+            "<html lang=\"zh-Hant\"><p>HI</p></html>"
+        );
+        assert_eq!(translate("<HTML LANG=en><p>hi</p></HTML>", "ja").await, "<HTML LANG=\"ja\"><p>HI</p></HTML>");
+    }
+
+    #[tokio::test]
+    async fn keeps_bytes_outside_segments_and_internal_whitespace() {
+        let content = "<!DOCTYPE html>\n<!-- note -->\n<ul class=\"list\">\n  <li>first   item</li>\n  <li>\n    second line\n    wraps &amp; &nbsp;here\n  </li>\n</ul>\n";
+        assert_eq!(
+            translate(content, "zh").await,
+            "<!DOCTYPE html>\n<!-- note -->\n<ul class=\"list\">\n  <li>FIRST   ITEM</li>\n  <li>\n    SECOND LINE\n    WRAPS &amp; &nbsp;HERE\n  </li>\n</ul>\n"
+        );
+    }
+}
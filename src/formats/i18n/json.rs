@@ -0,0 +1,244 @@
+use anyhow::{bail, Result};
+
+use super::{Leaf, LeafStyle, PathSegment};
+
+/// 扫描 JSON 文本，找出所有字符串叶子节点及其在原文中的位置
+///
+/// 只记录位置而不重新序列化，因此替换后键的顺序、缩进和空白都与原文一致
+pub fn extract(content: &str) -> Result<Vec<Leaf>> {
+    let mut scanner = Scanner {
+        src: content,
+        pos: 0,
+        path: Vec::new(),
+        leaves: Vec::new(),
+    };
+    // 跳过 UTF-8 BOM
+    if content.starts_with('\u{feff}') {
+        scanner.pos += '\u{feff}'.len_utf8();
+    }
+    scanner.value()?;
+    scanner.skip_whitespace();
+    if scanner.pos != content.len() {
+        bail!("JSON 解析失败: 位置 {} 之后存在多余内容", scanner.pos);
+    }
+    Ok(scanner.leaves)
+}
+
+/// 将字符串编码为 JSON 字符串字面量（含引号），非 ASCII 字符原样输出
+pub fn encode_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+struct Scanner<'a> {
+    src: &'a str,
+    pos: usize,
+    path: Vec<PathSegment>,
+    leaves: Vec<Leaf>,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<()> {
+        self.skip_whitespace();
+        if self.peek() != Some(byte) {
+            bail!("JSON 解析失败: 位置 {} 处应为 '{}'", self.pos, byte as char);
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => {
+                let start = self.pos;
+                let value = self.string()?;
+                self.leaves.push(Leaf {
+                    path: self.path.clone(),
+                    value,
+                    range: start..self.pos,
+                    style: LeafStyle::Json,
+                });
+                Ok(())
+            }
+            Some(_) => {
+                // 数字、true、false、null 原样保留
+                let rest = &self.src[self.pos..];
+                let len = rest
+                    .find(|c: char| matches!(c, ',' | '}' | ']') || c.is_whitespace())
+                    .unwrap_or(rest.len());
+                if len == 0 {
+                    bail!("JSON 解析失败: 位置 {} 处有无效字符", self.pos);
+                }
+                self.pos += len;
+                Ok(())
+            }
+            None => bail!("JSON 解析失败: 意外的文件结尾"),
+        }
+    }
+
+    fn object(&mut self) -> Result<()> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(());
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(b':')?;
+            self.path.push(PathSegment::Key(key));
+            self.value()?;
+            self.path.pop();
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                _ => bail!("JSON 解析失败: 位置 {} 处应为 ',' 或 '}}'", self.pos),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<()> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(());
+        }
+        let mut index = 0;
+        loop {
+            self.path.push(PathSegment::Index(index));
+            self.value()?;
+            self.path.pop();
+            index += 1;
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                _ => bail!("JSON 解析失败: 位置 {} 处应为 ',' 或 ']'", self.pos),
+            }
+        }
+    }
+
+    /// 读取字符串字面量并反转义
+    fn string(&mut self) -> Result<String> {
+        if self.peek() != Some(b'"') {
+            bail!("JSON 解析失败: 位置 {} 处应为字符串", self.pos);
+        }
+        self.pos += 1;
+
+        let mut value = String::new();
+        let mut chars = self.src[self.pos..].char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += offset + 1;
+                    return Ok(value);
+                }
+                '\\' => {
+                    let Some((_, escaped)) = chars.next() else { break };
+                    match escaped {
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        'r' => value.push('\r'),
+                        'b' => value.push('\u{8}'),
+                        'f' => value.push('\u{c}'),
+                        'u' => {
+                            let code = read_hex4(&mut chars)?;
+                            // 处理 UTF-16 代理对
+                            let code = if (0xD800..0xDC00).contains(&code) {
+                                let low = match (chars.next(), chars.next()) {
+                                    (Some((_, '\\')), Some((_, 'u'))) => read_hex4(&mut chars)?,
+                                    _ => bail!("JSON 解析失败: 无效的 Unicode 代理对"),
+                                };
+                                0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF)
+                            } else {
+                                code
+                            };
+                            value.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                        }
+                        other => value.push(other),
+                    }
+                }
+                c => value.push(c),
+            }
+        }
+        bail!("JSON 解析失败: 字符串未闭合")
+    }
+}
+
+fn read_hex4(chars: &mut std::str::CharIndices<'_>) -> Result<u32> {
+    let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+    u32::from_str_radix(&hex, 16).map_err(|_| anyhow::anyhow!("JSON 解析失败: 无效的 \\u 转义"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_leaf_positions() {
+        let content = "\u{feff}{\n  \"home\": {\"title\": \"Welcome\", \"count\": 2},\n  \"items\": [\"A \\\"quoted\\\" \\u00e9\", null, [\"nested\"]],\n  \"empty\": \"\"\n}\n";
+        let leaves = extract(content).unwrap();
+        let found: Vec<(String, &str, &str)> = leaves
+            .iter()
+            .map(|leaf| (leaf.key_path(), leaf.value.as_str(), &content[leaf.range.clone()]))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("home.title".to_string(), "Welcome", "\"Welcome\""),
+                ("items.0".to_string(), "A \"quoted\" é", "\"A \\\"quoted\\\" \\u00e9\""),
+                ("items.2.0".to_string(), "nested", "\"nested\""),
+                ("empty".to_string(), "", "\"\""),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_json() {
+        assert!(extract("{\"a\": \"b\"} trailing").is_err());
+        assert!(extract("{\"a\": \"b\"").is_err());
+    }
+
+    #[test]
+    fn encodes_strings() {
+        assert_eq!(encode_string("说 \"hi\"\n\t\\"), "\"说 \\\"hi\\\"\\n\\t\\\\\"");
+        assert_eq!(encode_string("\u{1}"), "\"\\u0001\"");
+    }
+}
@@ -0,0 +1,233 @@
+pub mod json;
+pub mod sync;
+pub mod yaml;
+
+use anyhow::Result;
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::language::registry;
+use crate::translator::protect::{protect, I18N_KINDS};
+use crate::translator::Translator;
+
+/// i18n 资源文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum I18nFormat {
+    Json,
+    Yaml,
+}
+
+impl I18nFormat {
+    /// 根据扩展名判断格式
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "json" => Some(I18nFormat::Json),
+            "yml" | "yaml" => Some(I18nFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// 键路径中的一段
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Key(key) => f.write_str(key),
+            PathSegment::Index(index) => write!(f, "{}", index),
+        }
+    }
+}
+
+/// 字符串叶子节点在原文中的写法，替换时按相同写法编码
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LeafStyle {
+    Json,
+    YamlDouble,
+    YamlSingle,
+    YamlPlain,
+    YamlBlock { indent: String, ending: &'static str },
+}
+
+/// 资源文件中的一个字符串叶子节点
+#[derive(Debug, Clone)]
+pub struct Leaf {
+    pub path: Vec<PathSegment>,
+    /// 反转义后的字符串值
+    pub value: String,
+    /// 字符串（含引号）在原文中的字节范围
+    pub range: Range<usize>,
+    pub style: LeafStyle,
+}
+
+impl Leaf {
+    /// 以点号连接的键路径，例如 "home.title" 或 "menu.items.0"
+    pub fn key_path(&self) -> String {
+        self.path
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// 将新值按原有写法编码
+    fn encode(&self, value: &str) -> String {
+        match &self.style {
+            LeafStyle::Json => json::encode_string(value),
+            LeafStyle::YamlDouble => yaml::encode_double(value),
+            LeafStyle::YamlSingle if !value.contains('\n') => {
+                format!("'{}'", value.replace('\'', "''"))
+            }
+            LeafStyle::YamlSingle => yaml::encode_double(value),
+            LeafStyle::YamlPlain if yaml::needs_quotes(value) => yaml::encode_double(value),
+            LeafStyle::YamlPlain => value.to_string(),
+            LeafStyle::YamlBlock { indent, ending } => value
+                .split('\n')
+                .enumerate()
+                .map(|(i, line)| match (i, line.is_empty()) {
+                    (0, _) | (_, true) => line.to_string(),
+                    _ => format!("{}{}", indent, line),
+                })
+                .collect::<Vec<_>>()
+                .join(ending),
+        }
+    }
+}
+
+/// 提取资源文件中的所有字符串叶子节点
+pub fn extract(content: &str, format: I18nFormat) -> Result<Vec<Leaf>> {
+    match format {
+        I18nFormat::Json => json::extract(content),
+        I18nFormat::Yaml => Ok(yaml::extract(content)),
+    }
+}
+
+/// 用新值替换叶子节点，`values` 与 `leaves` 一一对应，None 表示保留原文
+pub fn render(content: &str, leaves: &[Leaf], values: &[Option<String>]) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut cursor = 0;
+    for (leaf, value) in leaves.iter().zip(values) {
+        let Some(value) = value else { continue };
+        output.push_str(&content[cursor..leaf.range.start]);
+        output.push_str(&leaf.encode(value));
+        cursor = leaf.range.end;
+    }
+    output.push_str(&content[cursor..]);
+    output
+}
+
+/// 如果文件以语言代码作为唯一的顶层键（Rails 风格），返回该键的位置
+pub fn locale_root(content: &str, format: I18nFormat) -> Option<(String, Range<usize>)> {
+    if format != I18nFormat::Yaml {
+        return None;
+    }
+    yaml::root_key(content).filter(|(key, _)| registry::resolve(key).is_some())
+}
+
+/// 翻译失败、保留原文的叶子节点
+#[derive(Debug, Clone)]
+pub struct LeafFailure {
+    pub key_path: String,
+    pub reason: String,
+}
+
+/// 翻译一组字符串，保护插值占位符；占位符被破坏的条目返回 Err 并保留原文
+pub async fn translate_values(
+    values: &[String],
+    translator: &dyn Translator,
+    from: &str,
+    to: &str,
+) -> Result<Vec<Result<String, String>>> {
+    let protected: Vec<_> = values.iter().map(|v| protect(v, I18N_KINDS)).collect();
+    let texts: Vec<String> = protected.iter().map(|p| p.text.clone()).collect();
+    let translated = translator.translate_batch(&texts, from, to).await?;
+
+    Ok(protected
+        .iter()
+        .zip(translated)
+        .map(|(p, t)| p.restore(&t).map_err(|e| e.to_string()))
+        .collect())
+}
+
+/// 资源文件翻译结果
+pub struct I18nOutcome {
+    pub content: String,
+    pub translated: usize,
+    pub failures: Vec<LeafFailure>,
+}
+
+/// 翻译资源文件中所有字符串叶子节点，键顺序和格式保持不变
+///
+/// Rails 风格的顶层语言键会改为目标语言的 BCP-47 标签
+pub async fn translate_file(
+    content: &str,
+    format: I18nFormat,
+    translator: &dyn Translator,
+    from: &str,
+    to: &str,
+) -> Result<I18nOutcome> {
+    let leaves = extract(content, format)?;
+    let pending: Vec<usize> = (0..leaves.len())
+        .filter(|&i| !leaves[i].value.trim().is_empty())
+        .collect();
+    let texts: Vec<String> = pending.iter().map(|&i| leaves[i].value.clone()).collect();
+    let results = translate_values(&texts, translator, from, to).await?;
+
+    let mut values = vec![None; leaves.len()];
+    let mut failures = Vec::new();
+    let mut translated = 0;
+    for (index, result) in pending.into_iter().zip(results) {
+        match result {
+            Ok(value) => {
+                values[index] = Some(value);
+                translated += 1;
+            }
+            Err(reason) => failures.push(LeafFailure {
+                key_path: leaves[index].key_path(),
+                reason,
+            }),
+        }
+    }
+
+    let mut output = render(content, &leaves, &values);
+    if let Some((_, range)) = locale_root(&output, format) {
+        output.replace_range(range, &registry::locale_tag(to));
+    }
+
+    Ok(I18nOutcome {
+        content: output,
+        translated,
+        failures,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::stub::UppercaseTranslator;
+
+    #[tokio::test]
+    async fn rails_root_key_keeps_region() {
+        let content = "en:\n  greeting: \"Hello %{name}\"\n  count: 3\n";
+        for (to, root) in [("pt-BR", "pt-BR"), ("zh_tw", "zh-TW"), ("zh-CN", "zh-CN"), ("jp", "ja")] {
+            let outcome = translate_file(content, I18nFormat::Yaml, &UppercaseTranslator, "en", to).await.unwrap();
+            assert_eq!(outcome.content, format!("{}:\n  greeting: \"HELLO %{{name}}\"\n  count: 3\n", root));
+        }
+    }
+
+    #[tokio::test]
+    async fn translates_json_leaves_in_place() {
+        let content = "{\n  \"title\": \"Hi {{user}}\",\n  \"list\": [\"one\", \"\"],\n  \"n\": 1\n}\n";
+        let outcome = translate_file(content, I18nFormat::Json, &UppercaseTranslator, "en", "de").await.unwrap();
+        assert_eq!(outcome.translated, 2);
+        assert!(outcome.failures.is_empty());
+        assert_eq!(outcome.content, "{\n  \"title\": \"HI {{user}}\",\n  \"list\": [\"ONE\", \"\"],\n  \"n\": 1\n}\n");
+    }
+}
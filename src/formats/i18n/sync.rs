@@ -0,0 +1,340 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{extract, locale_root, render, translate_values, I18nFormat, Leaf, LeafFailure};
+use crate::language::registry;
+use crate::translator::Translator;
+
+/// 快照文件名，记录上次同步时源语言各键的哈希
+const SNAPSHOT_FILE: &str = ".fanyi-sync.json";
+
+/// 同步快照：目标语言 -> (键路径 -> 上次同步时源文本的哈希)
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Snapshot {
+    #[serde(default)]
+    targets: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// 单个目标语言的同步结果
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub target: String,
+    pub path: PathBuf,
+    /// 新增并已翻译的键
+    pub added: Vec<String>,
+    /// 源文本变化后重新翻译的键
+    pub changed: Vec<String>,
+    /// 源语言中已删除、从目标文件移除的键
+    pub removed: Vec<String>,
+    /// 未变化而保留原译文的键数
+    pub unchanged: usize,
+    pub failures: Vec<LeafFailure>,
+}
+
+/// 在目录中查找语言对应的资源文件
+fn find_locale_file(dir: &Path, locale: &str) -> Option<(PathBuf, I18nFormat)> {
+    ["json", "yml", "yaml"].iter().find_map(|ext| {
+        let path = dir.join(format!("{}.{}", locale, ext));
+        let format = I18nFormat::from_path(&path)?;
+        path.is_file().then_some((path, format))
+    })
+}
+
+/// 叶子节点的同步键，Rails 风格文件去掉顶层的语言键
+fn sync_key(leaf: &Leaf, has_locale_root: bool) -> String {
+    let skip = usize::from(has_locale_root && leaf.path.len() > 1);
+    leaf.path[skip..]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn hash(value: &str) -> String {
+    format!("{:x}", md5::compute(value.as_bytes()))
+}
+
+/// 增量同步目录中的语言资源文件
+///
+/// 以源语言文件为模板生成各目标文件：只翻译新增或源文本发生变化的键，
+/// 删除源语言中已不存在的键，其余键保留目标文件中的现有译文（包括人工修改）。
+/// 翻译失败的新键写为空字符串，不会把源文本当作译文；每个目标语言写完后立即保存快照，
+/// 中途出错时已完成的语言不会在下次同步时重复翻译
+pub async fn sync_locales(
+    dir: &Path,
+    source: &str,
+    targets: &[String],
+    translator: &dyn Translator,
+) -> Result<Vec<SyncReport>> {
+    let (source_path, format) = find_locale_file(dir, source)
+        .with_context(|| format!("在 {} 中找不到源语言文件 {}.json/.yml", dir.display(), source))?;
+    let source_content = fs::read_to_string(&source_path)
+        .with_context(|| format!("读取文件失败: {}", source_path.display()))?;
+    let source_leaves = extract(&source_content, format)
+        .with_context(|| format!("解析文件失败: {}", source_path.display()))?;
+    let source_has_root = locale_root(&source_content, format).is_some();
+
+    let snapshot_path = dir.join(SNAPSHOT_FILE);
+    let mut snapshot: Snapshot = match fs::read_to_string(&snapshot_path) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("解析同步快照失败: {}", snapshot_path.display()))?,
+        Err(_) => Snapshot::default(),
+    };
+
+    let source_keys: Vec<String> = source_leaves
+        .iter()
+        .map(|leaf| sync_key(leaf, source_has_root))
+        .collect();
+
+    // 新建的目标文件沿用源文件的扩展名
+    let source_ext = source_path
+        .extension()
+        .map(|e| e.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    // 目标文件按源文件重新生成，格式不同时会丢失目标文件中的内容，在写入任何文件前拒绝
+    let mut target_paths = Vec::with_capacity(targets.len());
+    for target in targets {
+        let target_path = match find_locale_file(dir, target) {
+            Some((path, target_format)) if target_format != format => bail!(
+                "{} 与源语言文件 {} 的格式不同，请先转换为相同格式再同步",
+                path.display(),
+                source_path.display()
+            ),
+            Some((path, _)) => path,
+            None => dir.join(format!("{}.{}", target, source_ext)),
+        };
+        target_paths.push(target_path);
+    }
+
+    let mut reports = Vec::new();
+    for (target, target_path) in targets.iter().zip(target_paths) {
+        // 读取目标文件中现有的译文
+        let mut existing: HashMap<String, String> = HashMap::new();
+        if target_path.is_file() {
+            let content = fs::read_to_string(&target_path)
+                .with_context(|| format!("读取文件失败: {}", target_path.display()))?;
+            let has_root = locale_root(&content, format).is_some();
+            for leaf in extract(&content, format)
+                .with_context(|| format!("解析文件失败: {}", target_path.display()))?
+            {
+                existing.insert(sync_key(&leaf, has_root), leaf.value);
+            }
+        }
+
+        let previous = snapshot.targets.get(target).cloned().unwrap_or_default();
+        let mut report = SyncReport {
+            target: target.clone(),
+            path: target_path.clone(),
+            ..Default::default()
+        };
+
+        let mut values: Vec<Option<String>> = vec![None; source_leaves.len()];
+        let mut pending = Vec::new();
+        let mut next_hashes = BTreeMap::new();
+
+        for (index, (leaf, key)) in source_leaves.iter().zip(&source_keys).enumerate() {
+            let source_hash = hash(&leaf.value);
+            if leaf.value.trim().is_empty() {
+                next_hashes.insert(key.clone(), source_hash);
+                continue;
+            }
+
+            // 空的译文（例如上次翻译失败留下的）视为缺失
+            let current = existing.get(key).filter(|value| !value.trim().is_empty());
+            match (current, previous.get(key)) {
+                // 源文本未变（或首次同步时已有译文），保留现有译文
+                (Some(current), Some(old_hash)) if *old_hash == source_hash => {
+                    values[index] = Some(current.clone());
+                    report.unchanged += 1;
+                    next_hashes.insert(key.clone(), source_hash);
+                }
+                (Some(current), None) => {
+                    values[index] = Some(current.clone());
+                    report.unchanged += 1;
+                    next_hashes.insert(key.clone(), source_hash);
+                }
+                (Some(_), Some(_)) => {
+                    report.changed.push(key.clone());
+                    pending.push((index, source_hash));
+                }
+                (None, _) => {
+                    report.added.push(key.clone());
+                    pending.push((index, source_hash));
+                }
+            }
+        }
+
+        let source_key_set: std::collections::HashSet<&String> = source_keys.iter().collect();
+        report.removed = existing
+            .keys()
+            .filter(|key| !source_key_set.contains(key))
+            .cloned()
+            .collect();
+        report.removed.sort();
+
+        let texts: Vec<String> = pending
+            .iter()
+            .map(|(index, _)| source_leaves[*index].value.clone())
+            .collect();
+        let results = translate_values(&texts, translator, source, target).await?;
+
+        for ((index, source_hash), result) in pending.into_iter().zip(results) {
+            let key = &source_keys[index];
+            match result {
+                Ok(value) => {
+                    values[index] = Some(value);
+                    next_hashes.insert(key.clone(), source_hash);
+                }
+                Err(reason) => {
+                    // 翻译失败的键不记录哈希，下次同步时重试；已有译文时保留，否则留空
+                    values[index] = Some(existing.get(key).cloned().unwrap_or_default());
+                    report.added.retain(|k| k != key);
+                    report.changed.retain(|k| k != key);
+                    report.failures.push(LeafFailure {
+                        key_path: key.clone(),
+                        reason,
+                    });
+                }
+            }
+        }
+
+        let mut output = render(&source_content, &source_leaves, &values);
+        if let Some((_, range)) = locale_root(&output, format) {
+            output.replace_range(range, &registry::locale_tag(target));
+        }
+        fs::write(&target_path, output)
+            .with_context(|| format!("写入文件失败: {}", target_path.display()))?;
+
+        snapshot.targets.insert(target.clone(), next_hashes);
+        let content = serde_json::to_string_pretty(&snapshot).context("序列化同步快照失败")?;
+        fs::write(&snapshot_path, content)
+            .with_context(|| format!("写入同步快照失败: {}", snapshot_path.display()))?;
+        reports.push(report);
+    }
+
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::Provider;
+    use crate::translator::stub::UppercaseTranslator;
+    use crate::translator::trait_def::TranslationResult;
+    use async_trait::async_trait;
+
+    /// 丢掉占位标记，模拟服务商破坏插值占位符；翻译成日语时请求失败
+    struct LossyTranslator;
+
+    #[async_trait]
+    impl Translator for LossyTranslator {
+        async fn translate(&self, text: &str, from: &str, to: &str) -> Result<TranslationResult> {
+            if to == "ja" {
+                bail!("请求失败");
+            }
+            Ok(TranslationResult {
+                source: text.to_string(),
+                target: regex::Regex::new(r"__\d+__").unwrap().replace_all(text, "").to_uppercase(),
+                from: from.to_string(),
+                to: to.to_string(),
+                detected_language: None,
+            })
+        }
+
+        fn provider(&self) -> Provider {
+            Provider::Baidu
+        }
+
+        fn name(&self) -> &'static str {
+            "lossy"
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fanyi-sync-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn read_snapshot(dir: &Path) -> Snapshot {
+        serde_json::from_str(&fs::read_to_string(dir.join(SNAPSHOT_FILE)).unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn reports_added_changed_and_removed_keys_and_keeps_manual_edits() {
+        let dir = temp_dir("changes");
+        let targets = vec!["zh".to_string()];
+        fs::write(dir.join("en.json"), "{\n  \"a\": \"hello\",\n  \"b\": \"bye\",\n  \"c\": \"gone\"\n}\n").unwrap();
+
+        let reports = sync_locales(&dir, "en", &targets, &UppercaseTranslator).await.unwrap();
+        assert_eq!(reports[0].added, ["a", "b", "c"]);
+        assert_eq!(
+            fs::read_to_string(dir.join("zh.json")).unwrap(),
+            "{\n  \"a\": \"HELLO\",\n  \"b\": \"BYE\",\n  \"c\": \"GONE\"\n}\n"
+        );
+        assert_eq!(read_snapshot(&dir).targets["zh"]["a"], hash("hello"));
+
+        // 人工修改 b 的译文，再修改源文件
+        fs::write(dir.join("zh.json"), "{\n  \"a\": \"HELLO\",\n  \"b\": \"再见\",\n  \"c\": \"GONE\"\n}\n").unwrap();
+        fs::write(dir.join("en.json"), "{\n  \"a\": \"hello there\",\n  \"b\": \"bye\",\n  \"d\": \"new\"\n}\n").unwrap();
+
+        let report = &sync_locales(&dir, "en", &targets, &UppercaseTranslator).await.unwrap()[0];
+        assert_eq!(report.added, ["d"]);
+        assert_eq!(report.changed, ["a"]);
+        assert_eq!(report.removed, ["c"]);
+        assert_eq!(report.unchanged, 1);
+        assert_eq!(
+            fs::read_to_string(dir.join("zh.json")).unwrap(),
+            "{\n  \"a\": \"HELLO THERE\",\n  \"b\": \"再见\",\n  \"d\": \"NEW\"\n}\n"
+        );
+        let snapshot = read_snapshot(&dir);
+        assert_eq!(snapshot.targets["zh"]["a"], hash("hello there"));
+        assert!(!snapshot.targets["zh"].contains_key("c"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn failed_new_keys_are_left_empty_and_retried() {
+        let dir = temp_dir("failures");
+        let targets = vec!["zh".to_string()];
+        fs::write(dir.join("en.yml"), "en:\n  greeting: \"Hi {{name}}\"\n  plain: ok\n").unwrap();
+
+        let report = &sync_locales(&dir, "en", &targets, &LossyTranslator).await.unwrap()[0];
+        assert_eq!(report.added, ["plain"]);
+        assert_eq!(report.failures[0].key_path, "greeting");
+        assert_eq!(fs::read_to_string(dir.join("zh.yml")).unwrap(), "zh:\n  greeting: \"\"\n  plain: OK\n");
+        assert!(!read_snapshot(&dir).targets["zh"].contains_key("greeting"));
+
+        let report = &sync_locales(&dir, "en", &targets, &UppercaseTranslator).await.unwrap()[0];
+        assert_eq!(report.added, ["greeting"]);
+        assert_eq!(report.unchanged, 1);
+        assert_eq!(
+            fs::read_to_string(dir.join("zh.yml")).unwrap(),
+            "zh:\n  greeting: \"HI {{name}}\"\n  plain: OK\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn snapshot_is_saved_after_each_locale() {
+        let dir = temp_dir("snapshot");
+        fs::write(dir.join("en.json"), "{\"a\": \"hello\"}").unwrap();
+
+        let targets = vec!["zh".to_string(), "ja".to_string()];
+        assert!(sync_locales(&dir, "en", &targets, &LossyTranslator).await.is_err());
+        let snapshot = read_snapshot(&dir);
+        assert_eq!(snapshot.targets["zh"]["a"], hash("hello"));
+        assert!(!snapshot.targets.contains_key("ja"));
+
+        // 快照往返后已同步的语言不再翻译
+        let report = &sync_locales(&dir, "en", &targets[..1], &LossyTranslator).await.unwrap()[0];
+        assert!(report.added.is_empty());
+        assert_eq!(report.unchanged, 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,411 @@
+use std::ops::Range;
+
+use super::{Leaf, LeafStyle, PathSegment};
+
+/// 路径栈中的一层
+struct Level {
+    indent: usize,
+    segment: PathSegment,
+    /// 是否为序列项（"- "）
+    is_item: bool,
+}
+
+/// 文件中的一行
+struct RawLine<'a> {
+    /// 行首在文件中的字节偏移
+    offset: usize,
+    /// 不含换行符的行内容
+    body: &'a str,
+}
+
+/// 扫描 i18n 风格的 YAML，找出字符串叶子节点的位置
+///
+/// 支持嵌套映射、序列、单/双引号字符串、普通标量和 | / > 块标量；
+/// 锚点、别名和流式集合（[...] / {...}）不参与翻译，原样保留
+pub fn extract(content: &str) -> Vec<Leaf> {
+    let ending = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let lines = split_lines(content);
+    let mut leaves = Vec::new();
+    let mut stack: Vec<Level> = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = &lines[index];
+        index += 1;
+
+        let trimmed = line.body.trim_start();
+        if trimmed.is_empty()
+            || trimmed.starts_with('#')
+            || line.body.starts_with("---")
+            || line.body.starts_with("...")
+        {
+            continue;
+        }
+
+        let indent = line.body.len() - trimmed.len();
+        let is_item = trimmed == "-" || trimmed.starts_with("- ");
+
+        // 弹出不再是当前行祖先的层级，同缩进的序列项保留其父键
+        let mut previous_item = None;
+        while let Some(top) = stack.last() {
+            let same_level_parent = top.indent == indent && is_item && !top.is_item;
+            if top.indent < indent || same_level_parent {
+                break;
+            }
+            let popped = stack.pop().unwrap();
+            if popped.indent == indent && popped.is_item {
+                if let PathSegment::Index(i) = popped.segment {
+                    previous_item = Some(i);
+                }
+            }
+        }
+
+        let (mut rest, mut rest_offset, mut rest_indent) = (trimmed, line.offset + indent, indent);
+
+        if is_item {
+            let item_index = previous_item.map_or(0, |i| i + 1);
+            let after_dash = trimmed[1..].trim_start();
+            let skipped = trimmed.len() - after_dash.len();
+            rest = after_dash;
+            rest_offset += skipped;
+            rest_indent += skipped;
+
+            let mut path: Vec<PathSegment> = stack.iter().map(|l| l.segment.clone()).collect();
+            path.push(PathSegment::Index(item_index));
+            stack.push(Level {
+                indent,
+                segment: PathSegment::Index(item_index),
+                is_item: true,
+            });
+
+            if rest.is_empty() {
+                continue;
+            }
+            // "- 文本" 形式的标量序列项；"- key: value" 形式按映射继续处理
+            if split_key(rest).is_none() {
+                if let Some(leaf) = scalar_leaf(path, rest, rest_offset) {
+                    leaves.push(leaf);
+                }
+                continue;
+            }
+        }
+
+        let Some((key, value_start)) = split_key(rest) else {
+            continue;
+        };
+        let value = rest[value_start..].trim_start();
+        let value_offset = rest_offset + (rest.len() - value.len());
+
+        let mut path: Vec<PathSegment> = stack.iter().map(|l| l.segment.clone()).collect();
+        path.push(PathSegment::Key(key.clone()));
+
+        if value.is_empty() || value.starts_with('#') {
+            // 嵌套映射或序列的开始
+            stack.push(Level {
+                indent: rest_indent,
+                segment: PathSegment::Key(key),
+                is_item: false,
+            });
+        } else if value.starts_with('|') || value.starts_with('>') {
+            let (leaf, consumed) = block_leaf(path, &lines[index..], rest_indent, ending);
+            if let Some(leaf) = leaf {
+                leaves.push(leaf);
+            }
+            index += consumed;
+        } else if let Some(leaf) = scalar_leaf(path, value, value_offset) {
+            leaves.push(leaf);
+        }
+    }
+
+    leaves
+}
+
+/// 找出唯一的顶层键（如 Rails 的 "zh:"），返回其名称和位置
+pub fn root_key(content: &str) -> Option<(String, Range<usize>)> {
+    let mut root = None;
+    for line in split_lines(content) {
+        let body = line.body;
+        if body.is_empty() || body.starts_with([' ', '\t', '#']) || body.starts_with("---") {
+            continue;
+        }
+        let (key, _) = split_key(body)?;
+        if root.is_some() {
+            return None;
+        }
+        let key_len = body.find(':')?;
+        root = Some((key, line.offset..line.offset + key_len));
+    }
+    root
+}
+
+fn split_lines(content: &str) -> Vec<RawLine<'_>> {
+    let mut offset = 0;
+    content
+        .split_inclusive('\n')
+        .map(|raw| {
+            let line = RawLine {
+                offset,
+                body: raw.trim_end_matches(['\n', '\r']),
+            };
+            offset += raw.len();
+            line
+        })
+        .collect()
+}
+
+/// 拆分 "key: value"，返回键名和值在行中的起始位置
+fn split_key(s: &str) -> Option<(String, usize)> {
+    if let Some(quote @ ('"' | '\'')) = s.chars().next() {
+        let close = s[1..].find(quote)? + 1;
+        let after = &s[close + 1..];
+        let colon = after.strip_prefix(':')?;
+        if !(colon.is_empty() || colon.starts_with(' ')) {
+            return None;
+        }
+        return Some((s[1..close].to_string(), close + 2));
+    }
+
+    let mut search = 0;
+    while let Some(pos) = s[search..].find(':') {
+        let colon = search + pos;
+        let after = &s[colon + 1..];
+        if after.is_empty() || after.starts_with(' ') || after.starts_with('\t') {
+            let key = s[..colon].trim();
+            if key.is_empty() || key.contains(" #") {
+                return None;
+            }
+            return Some((key.to_string(), colon + 1));
+        }
+        search = colon + 1;
+    }
+    None
+}
+
+/// 解析单行标量值
+fn scalar_leaf(path: Vec<PathSegment>, value: &str, offset: usize) -> Option<Leaf> {
+    match value.chars().next()? {
+        '"' => {
+            let end = find_closing_double_quote(value)?;
+            Some(Leaf {
+                path,
+                value: unescape_double(&value[1..end]),
+                range: offset..offset + end + 1,
+                style: LeafStyle::YamlDouble,
+            })
+        }
+        '\'' => {
+            let end = find_closing_single_quote(value)?;
+            Some(Leaf {
+                path,
+                value: value[1..end].replace("''", "'"),
+                range: offset..offset + end + 1,
+                style: LeafStyle::YamlSingle,
+            })
+        }
+        '&' | '*' | '!' | '[' | '{' | '|' | '>' | '%' | '@' | '`' => None,
+        _ => {
+            let plain = match value.find(" #") {
+                Some(comment) => value[..comment].trim_end(),
+                None => value.trim_end(),
+            };
+            if is_non_string_scalar(plain) {
+                return None;
+            }
+            Some(Leaf {
+                path,
+                value: plain.to_string(),
+                range: offset..offset + plain.len(),
+                style: LeafStyle::YamlPlain,
+            })
+        }
+    }
+}
+
+/// 解析块标量，返回叶子节点和消耗的行数
+fn block_leaf(
+    path: Vec<PathSegment>,
+    following: &[RawLine<'_>],
+    key_indent: usize,
+    ending: &'static str,
+) -> (Option<Leaf>, usize) {
+    let mut consumed = 0;
+    let mut last_content = None;
+    for (i, line) in following.iter().enumerate() {
+        let trimmed = line.body.trim_start();
+        if trimmed.is_empty() {
+            consumed = i + 1;
+            continue;
+        }
+        if line.body.len() - trimmed.len() <= key_indent {
+            break;
+        }
+        consumed = i + 1;
+        last_content = Some(i);
+    }
+
+    let Some(last) = last_content else {
+        return (None, consumed);
+    };
+    // 末尾的空行不属于块内容
+    let consumed = consumed.min(last + 1);
+    let block = &following[..=last];
+
+    let indent = block
+        .iter()
+        .filter(|l| !l.body.trim().is_empty())
+        .map(|l| l.body.len() - l.body.trim_start().len())
+        .min()
+        .unwrap_or(key_indent + 2);
+
+    let value = block
+        .iter()
+        .map(|l| l.body.get(indent..).unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let start = block[0].offset + indent;
+    let end = block[last].offset + block[last].body.len();
+    let leaf = Leaf {
+        path,
+        value,
+        range: start..end,
+        style: LeafStyle::YamlBlock {
+            indent: " ".repeat(indent),
+            ending,
+        },
+    };
+    (Some(leaf), consumed)
+}
+
+fn find_closing_double_quote(s: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in s.char_indices().skip(1) {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return Some(i),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+fn find_closing_single_quote(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let mut i = 1;
+    while i < bytes.len() {
+        if bytes[i] == b'\'' {
+            if bytes.get(i + 1) == Some(&b'\'') {
+                i += 2;
+                continue;
+            }
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+fn unescape_double(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// 编码为双引号字符串
+pub fn encode_double(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t");
+    format!("\"{}\"", escaped)
+}
+
+/// 普通标量是否需要加引号才能保持为字符串
+pub fn needs_quotes(value: &str) -> bool {
+    value.is_empty()
+        || value.trim() != value
+        || value.contains('\n')
+        || value.contains(": ")
+        || value.contains(" #")
+        || value.ends_with(':')
+        || value.starts_with([
+            '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%',
+            '@', '`',
+        ])
+        || is_non_string_scalar(value)
+}
+
+/// 判断普通标量是否会被解析为非字符串（布尔、空值、数字）
+fn is_non_string_scalar(value: &str) -> bool {
+    matches!(
+        value.to_lowercase().as_str(),
+        "" | "~" | "null" | "true" | "false" | "yes" | "no" | "on" | "off"
+    ) || value.parse::<f64>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (键路径, 值, 原文中的写法)
+    fn leaves(content: &str) -> Vec<(String, String, &str)> {
+        extract(content)
+            .into_iter()
+            .map(|leaf| (leaf.key_path(), leaf.value.clone(), &content[leaf.range]))
+            .collect()
+    }
+
+    #[test]
+    fn finds_leaf_positions() {
+        let content = r#"en:
+  # comment
+  home:
+    title: Welcome home
+    subtitle: "Hello, \"%{name}\"\n"
+    note: 'It''s here'
+  count: 3
+  enabled: true
+  menu:
+    - Open
+    - label: Save
+  about: |
+    First line
+    Second line
+  anchor: &a shared
+"#;
+        assert_eq!(
+            leaves(content),
+            vec![
+                ("en.home.title".into(), "Welcome home".into(), "Welcome home"),
+                ("en.home.subtitle".into(), "Hello, \"%{name}\"\n".into(), r#""Hello, \"%{name}\"\n""#),
+                ("en.home.note".into(), "It's here".into(), "'It''s here'"),
+                ("en.menu.0".into(), "Open".into(), "Open"),
+                ("en.menu.1.label".into(), "Save".into(), "Save"),
+                ("en.about".into(), "First line\nSecond line".into(), "First line\n    Second line"),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_single_root_key() {
+        let content = "---\nzh-CN:\n  hello: 你好\n";
+        let (key, range) = root_key(content).unwrap();
+        assert_eq!(key, "zh-CN");
+        assert_eq!(&content[range], "zh-CN");
+        assert!(root_key("en:\n  a: b\nfr:\n  a: c\n").is_none());
+    }
+}
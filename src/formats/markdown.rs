@@ -0,0 +1,266 @@
+use anyhow::Result;
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use std::ops::Range;
+
+use crate::translator::protect::Protected;
+use crate::translator::Translator;
+
+/// 翻译Markdown文档，只翻译正文文本节点
+///
+/// 标题、段落、列表项、表格单元格和链接文字中的文本会被翻译；代码块、行内代码、
+/// URL、HTML 和 YAML front matter 保持不变。每个段落、标题或单元格连同其中的
+/// 强调、链接等行内标记作为一个句子提交，标记和折行处的换行、缩进、引用前缀以占位符保护，
+/// 其余内容逐字节保留
+pub async fn translate_markdown(
+    content: &str,
+    translator: &dyn Translator,
+    from: &str,
+    to: &str,
+) -> Result<String> {
+    let body_start = front_matter_end(content);
+    let body = &content[body_start..];
+
+    let blocks = prose_blocks(body);
+    if blocks.is_empty() {
+        return Ok(content.to_string());
+    }
+
+    let protected: Vec<Protected> = blocks.iter().map(|block| block.protect(body)).collect();
+    let queries: Vec<String> = protected.iter().map(|p| p.text.clone()).collect();
+    let translated = translator.translate_batch(&queries, from, to).await?;
+
+    let mut output = String::with_capacity(content.len());
+    output.push_str(&content[..body_start]);
+    let mut cursor = 0;
+    for ((block, protected), text) in blocks.iter().zip(&protected).zip(&translated) {
+        let restored = protected.restore(text).map_err(|e| anyhow::anyhow!("{}", e))?;
+        output.push_str(&body[cursor..block.range.start]);
+        output.push_str(&restored);
+        cursor = block.range.end;
+    }
+    output.push_str(&body[cursor..]);
+
+    Ok(output)
+}
+
+/// 返回 YAML front matter 结束后的字节偏移，没有 front matter 时返回 0
+fn front_matter_end(content: &str) -> usize {
+    let mut lines = content.split_inclusive('\n');
+    match lines.next() {
+        Some(first) if first.trim_end() == "---" => {}
+        _ => return 0,
+    }
+
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        offset += line.len();
+        if offset > line.len() && matches!(line.trim_end(), "---" | "...") {
+            return offset;
+        }
+    }
+    // 没有闭合的分隔线，不视为 front matter
+    0
+}
+
+/// 句子中的一个片段
+enum Piece {
+    /// 可翻译的文本
+    Text(Range<usize>),
+    /// 软换行连同下一行的缩进或引用前缀，按原文保护，翻译时两侧加空格以分隔单词
+    SoftBreak(Range<usize>),
+    /// 强调符号、链接语法、行内代码、实体等，按原文整体保护
+    Raw(Range<usize>),
+}
+
+/// 一个段落、标题或表格单元格中的内容
+struct Block {
+    range: Range<usize>,
+    pieces: Vec<Piece>,
+}
+
+impl Block {
+    /// 组装成一个句子，行内标记替换为占位标记
+    fn protect(&self, body: &str) -> Protected {
+        let mut protected = Protected::default();
+        for piece in &self.pieces {
+            match piece {
+                Piece::Text(range) => protected.push_text(&body[range.clone()]),
+                Piece::SoftBreak(range) => protected.push_break(&body[range.clone()]),
+                Piece::Raw(range) => protected.push_protected(&body[range.clone()]),
+            }
+        }
+        protected
+    }
+}
+
+impl Piece {
+    fn range(&self) -> &Range<usize> {
+        match self {
+            Piece::Text(range) | Piece::SoftBreak(range) | Piece::Raw(range) => range,
+        }
+    }
+}
+
+/// 将文本节点连同其间的行内标记按段落、标题和表格单元格分组
+fn prose_blocks(body: &str) -> Vec<Block> {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+
+    let mut blocks = Vec::new();
+    let mut pieces: Vec<Piece> = Vec::new();
+    // 上一个行内节点的结束位置，与下一个节点之间的内容是行内标记
+    let mut cursor = 0usize;
+    let mut in_block = false;
+    let mut after_soft_break = false;
+    let mut skip_depth = 0usize;
+
+    for (event, range) in Parser::new_ext(body, options).into_offset_iter() {
+        match event {
+            Event::Start(Tag::CodeBlock(_) | Tag::HtmlBlock) => {
+                close_block(body, &mut pieces, &mut blocks);
+                skip_depth += 1;
+                continue;
+            }
+            Event::End(TagEnd::CodeBlock | TagEnd::HtmlBlock) => {
+                skip_depth = skip_depth.saturating_sub(1);
+                continue;
+            }
+            Event::Start(tag) if !is_inline(&tag) => {
+                close_block(body, &mut pieces, &mut blocks);
+                in_block = false;
+                continue;
+            }
+            Event::End(tag) if !is_inline_end(&tag) => {
+                close_block(body, &mut pieces, &mut blocks);
+                in_block = false;
+                continue;
+            }
+            _ if skip_depth > 0 => continue,
+            // 行内元素的开始和结束事件覆盖整个元素，句首的开始标记和句尾的结束标记
+            // 也要放进句子里，其余标记由相邻节点之间的内容体现
+            Event::Start(_) => {
+                if !in_block {
+                    in_block = true;
+                    cursor = range.start;
+                }
+                continue;
+            }
+            Event::End(_) => {
+                if in_block && cursor < range.end {
+                    pieces.push(Piece::Raw(cursor..range.end));
+                    cursor = range.end;
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        // 软换行与下一个节点之间是下一行的缩进或引用前缀，与换行符一起保护
+        if after_soft_break {
+            pieces.push(Piece::SoftBreak(cursor..range.start));
+        } else if in_block && cursor < range.start {
+            pieces.push(Piece::Raw(cursor..range.start));
+        }
+        in_block = true;
+        after_soft_break = false;
+        match event {
+            // 含转义或实体的文本与解码结果不一致，按原文保护
+            Event::Text(text) if body.get(range.clone()) == Some(text.as_ref()) => {
+                pieces.push(Piece::Text(range.clone()));
+            }
+            Event::SoftBreak => {
+                after_soft_break = true;
+                cursor = range.start;
+                continue;
+            }
+            _ => pieces.push(Piece::Raw(range.clone())),
+        }
+        cursor = range.end;
+    }
+    close_block(body, &mut pieces, &mut blocks);
+
+    blocks
+}
+
+/// 结束当前块，只有包含文字的块才需要翻译
+fn close_block(body: &str, pieces: &mut Vec<Piece>, blocks: &mut Vec<Block>) {
+    let pieces = std::mem::take(pieces);
+    let has_text = pieces.iter().any(|p| match p {
+        Piece::Text(range) => body[range.clone()].chars().any(char::is_alphabetic),
+        _ => false,
+    });
+    if !has_text {
+        return;
+    }
+
+    let start = pieces.first().map(|p| p.range().start);
+    let end = pieces.last().map(|p| p.range().end);
+    if let (Some(start), Some(end)) = (start, end) {
+        blocks.push(Block {
+            range: start..end,
+            pieces,
+        });
+    }
+}
+
+/// 行内元素不打断句子
+fn is_inline(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. } | Tag::Image { .. }
+    )
+}
+
+fn is_inline_end(tag: &TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link | TagEnd::Image
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::stub::UppercaseTranslator;
+
+    async fn translate(content: &str) -> String {
+        translate_markdown(content, &UppercaseTranslator, "en", "zh").await.unwrap()
+    }
+
+    #[test]
+    fn groups_inline_markup_into_one_sentence() {
+        let body = "Say *hello* to [the docs](https://x.io) and `code`,\nthen &amp; \\* stop.\n";
+        let blocks = prose_blocks(body);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(
+            blocks[0].protect(body).text,
+            "Say __0__hello__1__ to __2__the docs__3__ and __4__, __5__ then __6__ __7__* stop."
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_soft_breaks_and_line_prefixes() {
+        let content = "- first line\n  continued *here*\n\n> > nested one\n> > nested two\n";
+        assert_eq!(
+            translate(content).await,
+            "- FIRST LINE\n  CONTINUED *HERE*\n\n> > NESTED ONE\n> > NESTED TWO\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn translates_blocks_and_keeps_markup() {
+        let content = "---\ntitle: x\n---\n# Hello *world*\n\n> quoted one\n> quoted two\n\n- item **bold**\n\n| a | b |\n|---|---|\n| cell | ![alt](i.png) |\n\n```\ncode\n```\n";
+        assert_eq!(
+            translate(content).await,
+            "---\ntitle: x\n---\n# HELLO *WORLD*\n\n> QUOTED ONE\n> QUOTED TWO\n\n- ITEM **BOLD**\n\n| A | B |\n|---|---|\n| CELL | ![ALT](i.png) |\n\n```\ncode\n```\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_hard_breaks_and_skips_html_blocks() {
+        let content = "first line  \n> not a quote\n\n<div>html block</div>\n";
+        assert_eq!(translate(content).await, "FIRST LINE  \n> NOT A QUOTE\n\n<div>html block</div>\n");
+    }
+}
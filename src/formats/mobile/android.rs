@@ -0,0 +1,217 @@
+use anyhow::Result;
+
+use super::MobileStats;
+use crate::formats::xml::{self, Token};
+use crate::translator::protect::{ProtectKind, Protected};
+use crate::translator::Translator;
+
+/// 需要翻译的一个字符串（string 或 plurals/string-array 中的 item）
+struct Entry {
+    name: String,
+    /// 内容在原文中的范围（开始标签之后到结束标签之前）
+    range: std::ops::Range<usize>,
+    protected: Protected,
+}
+
+/// 翻译 Android strings.xml
+///
+/// 处理 string、plurals 和 string-array，跳过 translatable="false" 的条目；
+/// %1$s、%d 等格式符和 <xliff:g> 内容保持不变，撇号和引号按 Android 规则转义
+pub async fn translate(
+    content: &str,
+    translator: &dyn Translator,
+    from: &str,
+    to: &str,
+) -> Result<(String, MobileStats)> {
+    let tokens = xml::tokenize(content);
+    let mut stats = MobileStats::default();
+    let mut entries = Vec::new();
+
+    let mut group: Option<(String, bool)> = None;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        let translatable = token.attr("translatable") != Some("false");
+        match (token, token.local_name()) {
+            (Token::Start { self_closing: false, .. }, Some("plurals" | "string-array")) => {
+                let name = token.attr("name").unwrap_or_default().to_string();
+                group = Some((name, translatable));
+            }
+            (Token::End { .. }, Some("plurals" | "string-array")) => group = None,
+            (Token::Start { self_closing: false, .. }, Some(tag @ ("string" | "item"))) => {
+                let (name, group_translatable) = match (tag, &group) {
+                    ("string", _) => (token.attr("name").unwrap_or_default().to_string(), true),
+                    (_, Some((group_name, group_translatable))) => {
+                        let quantity = token.attr("quantity").map(|q| format!("[{}]", q));
+                        (format!("{}{}", group_name, quantity.unwrap_or_default()), *group_translatable)
+                    }
+                    // 不在 plurals / string-array 中的 item（如 <item type="id">）不处理
+                    _ => {
+                        i += 1;
+                        continue;
+                    }
+                };
+
+                let (protected, end) = read_content(content, &tokens, i);
+                let range = token.range().end..tokens.get(end).map_or(content.len(), |t| t.range().start);
+                i = end;
+
+                if !translatable || !group_translatable {
+                    stats.skipped += 1;
+                } else if !protected.text.trim().is_empty() {
+                    entries.push(Entry { name, range, protected });
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let texts: Vec<String> = entries.iter().map(|e| e.protected.text.clone()).collect();
+    let translated = translator.translate_batch(&texts, from, to).await?;
+
+    let mut output = String::with_capacity(content.len());
+    let mut cursor = 0;
+    for (entry, text) in entries.iter().zip(translated) {
+        // 先转义译文再还原受保护内容，受保护内容保持原始写法
+        match entry.protected.restore(&escape(&text)) {
+            Ok(value) => {
+                output.push_str(&content[cursor..entry.range.start]);
+                output.push_str(&value);
+                cursor = entry.range.end;
+                stats.translated += 1;
+            }
+            Err(e) => stats.failures.push((entry.name.clone(), e.to_string())),
+        }
+    }
+    output.push_str(&content[cursor..]);
+
+    Ok((output, stats))
+}
+
+/// 读取元素内容，返回受保护文本和结束标签下标
+fn read_content(content: &str, tokens: &[Token], start: usize) -> (Protected, usize) {
+    let mut protected = Protected::default();
+    let mut depth = 0;
+    let mut i = start + 1;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        let raw = &content[token.range().clone()];
+        match token {
+            Token::Text { .. } => {
+                protected.push_text_with(&unescape(&xml::unescape(raw)), &[ProtectKind::Printf]);
+            }
+            Token::End { .. } if depth == 0 => return (protected, i),
+            // <xliff:g> 标记不可翻译的内容，连同内容整体保护
+            Token::Start { self_closing: false, .. } if token.local_name() == Some("g") => {
+                let mut j = i + 1;
+                while j < tokens.len() && !matches!(&tokens[j], Token::End { .. } if tokens[j].local_name() == Some("g")) {
+                    j += 1;
+                }
+                let end = tokens.get(j).map_or(content.len(), |t| t.range().end);
+                protected.push_protected(&content[token.range().start..end]);
+                i = j;
+            }
+            Token::Start { self_closing, .. } => {
+                if !self_closing {
+                    depth += 1;
+                }
+                protected.push_protected(raw);
+            }
+            Token::End { .. } => {
+                depth -= 1;
+                protected.push_protected(raw);
+            }
+            Token::Other { .. } => protected.push_protected(raw),
+        }
+        i += 1;
+    }
+    (protected, tokens.len())
+}
+
+/// 去掉 Android 字符串转义（\' \" \n \t \\ \@ \?）以及包裹整个字符串的双引号
+fn unescape(s: &str) -> String {
+    let s = match s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        Some(inner) if !inner.contains('"') => inner,
+        _ => s,
+    };
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// 按 Android 规则转义：XML 实体、撇号、引号、换行，开头的 @ 和 ? 需要转义
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for (i, c) in s.chars().enumerate() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\'' => out.push_str("\\'"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '@' | '?' if i == 0 => {
+                out.push('\\');
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::stub::UppercaseTranslator;
+
+    #[test]
+    fn escapes_apostrophes_and_quotes() {
+        assert_eq!(escape("It's \"ok\""), "It\\'s \\\"ok\\\"");
+        assert_eq!(escape("@home & <b>?"), "\\@home &amp; &lt;b>?");
+        assert_eq!(escape("a\nb\\"), "a\\nb\\\\");
+        assert_eq!(unescape("It\\'s \\\"ok\\\"\\n"), "It's \"ok\"\n");
+        assert_eq!(unescape("\"Don't stop\""), "Don't stop");
+    }
+
+    #[tokio::test]
+    async fn translates_strings_plurals_and_arrays() {
+        let content = r#"<resources xmlns:xliff="urn:oasis:names:tc:xliff:document:1.2">
+    <string name="welcome">Don\'t give up, %1$s!</string>
+    <string name="app_name" translatable="false">Fanyi</string>
+    <string name="link">Tap <b>here</b> for <xliff:g id="count">%d</xliff:g> items</string>
+    <plurals name="files">
+        <item quantity="one">"One file's ready"</item>
+        <item quantity="other">%d files</item>
+    </plurals>
+    <string-array name="colors">
+        <item>Red &amp; blue</item>
+    </string-array>
+</resources>
+"#;
+        let (output, stats) = translate(content, &UppercaseTranslator, "en", "zh").await.unwrap();
+        assert_eq!((stats.translated, stats.skipped), (5, 1));
+        assert!(stats.failures.is_empty());
+
+        assert!(output.contains(r#"<string name="welcome">DON\'T GIVE UP, %1$s!</string>"#));
+        assert!(output.contains(r#"<string name="app_name" translatable="false">Fanyi</string>"#));
+        assert!(output.contains(r#"<string name="link">TAP <b>HERE</b> FOR <xliff:g id="count">%d</xliff:g> ITEMS</string>"#));
+        assert!(output.contains(r#"<item quantity="one">ONE FILE\'S READY</item>"#));
+        assert!(output.contains(r#"<item quantity="other">%d FILES</item>"#));
+        assert!(output.contains("<item>RED &amp; BLUE</item>"));
+    }
+}
@@ -0,0 +1,161 @@
+use anyhow::{bail, Result};
+use std::ops::Range;
+
+use super::MobileStats;
+use crate::translator::protect::{protect, ProtectKind};
+use crate::translator::Translator;
+
+/// Apple 格式符: %@、%1$@、%d、%ld、%.2f 等
+pub const APPLE_KINDS: &[ProtectKind] = &[ProtectKind::Printf];
+
+/// .strings 文件中的一条 "key" = "value";
+struct Entry {
+    key: String,
+    value: String,
+    /// 值（含引号）在原文中的范围
+    range: Range<usize>,
+}
+
+/// 翻译 Apple .strings 文件，保留注释和键，只替换值
+pub async fn translate(
+    content: &str,
+    translator: &dyn Translator,
+    from: &str,
+    to: &str,
+) -> Result<(String, MobileStats)> {
+    let entries = parse(content)?;
+    let mut stats = MobileStats::default();
+
+    let pending: Vec<&Entry> = entries.iter().filter(|e| !e.value.trim().is_empty()).collect();
+    let protected: Vec<_> = pending.iter().map(|e| protect(&e.value, APPLE_KINDS)).collect();
+    let texts: Vec<String> = protected.iter().map(|p| p.text.clone()).collect();
+    let translated = translator.translate_batch(&texts, from, to).await?;
+
+    let mut output = String::with_capacity(content.len());
+    let mut cursor = 0;
+    for ((entry, protected), text) in pending.iter().zip(&protected).zip(translated) {
+        match protected.restore(&text) {
+            Ok(value) => {
+                output.push_str(&content[cursor..entry.range.start]);
+                output.push('"');
+                output.push_str(&escape(&value));
+                output.push('"');
+                cursor = entry.range.end;
+                stats.translated += 1;
+            }
+            Err(e) => stats.failures.push((entry.key.clone(), e.to_string())),
+        }
+    }
+    output.push_str(&content[cursor..]);
+
+    Ok((output, stats))
+}
+
+/// 解析 .strings 文件，跳过 /* */ 和 // 注释
+fn parse(content: &str) -> Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    let bytes = content.as_bytes();
+    let mut pos = 0;
+
+    // 依次读取: 键、'='、值、';'
+    let mut key: Option<String> = None;
+    let mut expect_value = false;
+
+    while pos < bytes.len() {
+        let rest = &content[pos..];
+        if rest.starts_with("/*") {
+            pos += rest.find("*/").map_or(rest.len(), |i| i + 2);
+        } else if rest.starts_with("//") {
+            pos += rest.find('\n').unwrap_or(rest.len());
+        } else if rest.starts_with('"') {
+            let (value, len) = read_quoted(rest)?;
+            match (&key, expect_value) {
+                (Some(k), true) => {
+                    entries.push(Entry {
+                        key: k.clone(),
+                        value,
+                        range: pos..pos + len,
+                    });
+                    key = None;
+                    expect_value = false;
+                }
+                _ => key = Some(value),
+            }
+            pos += len;
+        } else if rest.starts_with('=') {
+            if key.is_none() {
+                bail!(".strings 解析失败: 位置 {} 处缺少键", pos);
+            }
+            expect_value = true;
+            pos += 1;
+        } else {
+            pos += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// 读取双引号字符串，返回反转义后的值和占用的字节数
+fn read_quoted(s: &str) -> Result<(String, usize)> {
+    let mut value = String::new();
+    let mut chars = s.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, i + 1)),
+            '\\' => match chars.next().map(|(_, c)| c) {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('r') => value.push('\r'),
+                Some('U') | Some('u') => {
+                    let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                    let c = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                    value.push(c.unwrap_or('\u{fffd}'));
+                }
+                Some(other) => value.push(other),
+                None => break,
+            },
+            c => value.push(c),
+        }
+    }
+    bail!(".strings 解析失败: 字符串未闭合")
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::stub::UppercaseTranslator;
+
+    #[test]
+    fn parses_entries_and_skips_comments() {
+        let content = "/* \"not\" = \"this\"; */\n\"greeting\" = \"Hello \\\"%@\\\"\\n\";\n// \"skip\" = \"me\";\n\"unicode\"=\"caf\\U00e9\";\n";
+        let entries = parse(content).unwrap();
+        let found: Vec<(&str, &str, &str)> = entries
+            .iter()
+            .map(|e| (e.key.as_str(), e.value.as_str(), &content[e.range.clone()]))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("greeting", "Hello \"%@\"\n", "\"Hello \\\"%@\\\"\\n\""),
+                ("unicode", "café", "\"caf\\U00e9\""),
+            ]
+        );
+        assert!(parse("\"open\" = \"unterminated;").is_err());
+    }
+
+    #[tokio::test]
+    async fn translates_values_and_keeps_keys() {
+        let content = "/* Title */\n\"title\" = \"%1$@ has %2$ld items\";\n\"empty\" = \"\";\n";
+        let (output, stats) = translate(content, &UppercaseTranslator, "en", "zh").await.unwrap();
+        assert_eq!(stats.translated, 1);
+        assert_eq!(output, "/* Title */\n\"title\" = \"%1$@ HAS %2$ld ITEMS\";\n\"empty\" = \"\";\n");
+    }
+}
@@ -0,0 +1,65 @@
+pub mod android;
+pub mod apple;
+pub mod xcstrings;
+
+use anyhow::Result;
+use std::path::Path;
+
+use crate::translator::Translator;
+
+/// 移动端资源文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MobileFormat {
+    /// Android res/values/strings.xml
+    AndroidXml,
+    /// Apple Localizable.strings
+    AppleStrings,
+    /// Xcode 15+ 字符串目录 Localizable.xcstrings
+    XcStrings,
+}
+
+impl MobileFormat {
+    /// 根据扩展名判断格式
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "xml" => Some(MobileFormat::AndroidXml),
+            "strings" => Some(MobileFormat::AppleStrings),
+            "xcstrings" => Some(MobileFormat::XcStrings),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MobileFormat::AndroidXml => "Android strings.xml",
+            MobileFormat::AppleStrings => "Apple .strings",
+            MobileFormat::XcStrings => "Xcode .xcstrings",
+        }
+    }
+}
+
+/// 移动端资源文件翻译统计
+#[derive(Debug, Default)]
+pub struct MobileStats {
+    pub translated: usize,
+    /// 标记为不翻译或已有译文而跳过的条目数
+    pub skipped: usize,
+    /// 格式符丢失或重复而保留原文的条目 (名称, 原因)
+    pub failures: Vec<(String, String)>,
+}
+
+/// 翻译移动端资源文件
+pub async fn translate(
+    content: &str,
+    format: MobileFormat,
+    translator: &dyn Translator,
+    from: &str,
+    to: &str,
+) -> Result<(String, MobileStats)> {
+    match format {
+        MobileFormat::AndroidXml => android::translate(content, translator, from, to).await,
+        MobileFormat::AppleStrings => apple::translate(content, translator, from, to).await,
+        MobileFormat::XcStrings => xcstrings::translate(content, translator, from, to).await,
+    }
+}
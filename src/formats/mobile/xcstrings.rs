@@ -0,0 +1,239 @@
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::io;
+
+use super::apple::APPLE_KINDS;
+use super::MobileStats;
+use crate::language::registry;
+use crate::translator::protect::protect;
+use crate::translator::Translator;
+
+/// 机器译文在字符串目录中的状态，Xcode 会将其显示为需要审校
+const MACHINE_STATE: &str = "needs_review";
+
+/// 一个需要翻译的 stringUnit 在目录中的位置
+struct Pending {
+    key: String,
+    /// 复数等变体在 variations 中的路径，例如 ["plural", "one"]
+    variation: Vec<String>,
+    source: String,
+}
+
+/// 翻译 Xcode 字符串目录（.xcstrings）
+///
+/// 为目标语言添加 stringUnit（含 plural/device 变体），跳过 shouldTranslate 为 false
+/// 以及目标语言已有译文的条目
+pub async fn translate(
+    content: &str,
+    translator: &dyn Translator,
+    from: &str,
+    to: &str,
+) -> Result<(String, MobileStats)> {
+    let mut catalog: Value = serde_json::from_str(content).context("解析 .xcstrings 失败")?;
+    // localizations 的键是带地区或文字的 BCP-47 标签（zh-Hans、pt-BR），
+    // --to 也可能是 jp、japanese、日语 等写法
+    let locale = registry::locale_tag(to);
+    let source_language = catalog["sourceLanguage"].as_str().unwrap_or("en").to_string();
+    let mut stats = MobileStats::default();
+    let mut pending = Vec::new();
+
+    if let Some(strings) = catalog["strings"].as_object() {
+        for (key, entry) in strings {
+            if entry["shouldTranslate"] == Value::Bool(false) {
+                stats.skipped += 1;
+                continue;
+            }
+            if !entry["localizations"][&locale].is_null() {
+                stats.skipped += 1;
+                continue;
+            }
+
+            let source = &entry["localizations"][&source_language];
+            let mut units = Vec::new();
+            collect_units(source, &mut Vec::new(), &mut units);
+            if units.is_empty() {
+                // 没有源语言本地化时，键本身就是源文本
+                units.push((Vec::new(), key.clone()));
+            }
+            for (variation, source) in units {
+                if !source.trim().is_empty() {
+                    pending.push(Pending { key: key.clone(), variation, source });
+                }
+            }
+        }
+    }
+
+    let protected: Vec<_> = pending.iter().map(|p| protect(&p.source, APPLE_KINDS)).collect();
+    let texts: Vec<String> = protected.iter().map(|p| p.text.clone()).collect();
+    let translated = translator.translate_batch(&texts, from, to).await?;
+
+    let strings = catalog["strings"].as_object_mut().context(".xcstrings 缺少 strings")?;
+    let mut translated_keys = std::collections::HashSet::new();
+    for ((item, protected), text) in pending.iter().zip(&protected).zip(translated) {
+        let value = match protected.restore(&text) {
+            Ok(value) => value,
+            Err(e) => {
+                stats.failures.push((item.key.clone(), e.to_string()));
+                continue;
+            }
+        };
+
+        let Some(entry) = strings.get_mut(&item.key).and_then(Value::as_object_mut) else {
+            continue;
+        };
+        let localizations = entry
+            .entry("localizations")
+            .or_insert_with(|| Value::Object(Map::new()));
+        let mut node = localizations
+            .as_object_mut()
+            .context(".xcstrings 中 localizations 格式错误")?
+            .entry(locale.as_str())
+            .or_insert_with(|| Value::Object(Map::new()));
+        for segment in &item.variation {
+            node = node
+                .as_object_mut()
+                .context(".xcstrings 中 variations 格式错误")?
+                .entry(segment.as_str())
+                .or_insert_with(|| Value::Object(Map::new()));
+        }
+
+        let mut unit = Map::new();
+        unit.insert("state".to_string(), Value::String(MACHINE_STATE.to_string()));
+        unit.insert("value".to_string(), Value::String(value));
+        if let Some(node) = node.as_object_mut() {
+            node.insert("stringUnit".to_string(), Value::Object(unit));
+        }
+        if translated_keys.insert(item.key.clone()) {
+            stats.translated += 1;
+        }
+    }
+
+    Ok((to_xcode_json(&catalog)?, stats))
+}
+
+/// 递归收集 stringUnit，记录其所在的变体路径
+fn collect_units(node: &Value, path: &mut Vec<String>, units: &mut Vec<(Vec<String>, String)>) {
+    if let Some(value) = node["stringUnit"]["value"].as_str() {
+        units.push((path.clone(), value.to_string()));
+    }
+    if let Some(variations) = node["variations"].as_object() {
+        for (kind, cases) in variations {
+            let Some(cases) = cases.as_object() else { continue };
+            for (case, child) in cases {
+                path.extend(["variations".to_string(), kind.clone(), case.clone()]);
+                collect_units(child, path, units);
+                path.truncate(path.len() - 3);
+            }
+        }
+    }
+}
+
+/// 按 Xcode 的格式输出 JSON（两空格缩进，键值之间为 " : "）
+fn to_xcode_json(value: &Value) -> Result<String> {
+    struct XcodeFormatter(serde_json::ser::PrettyFormatter<'static>);
+
+    impl serde_json::ser::Formatter for XcodeFormatter {
+        fn begin_array<W: ?Sized + io::Write>(&mut self, w: &mut W) -> io::Result<()> {
+            self.0.begin_array(w)
+        }
+        fn end_array<W: ?Sized + io::Write>(&mut self, w: &mut W) -> io::Result<()> {
+            self.0.end_array(w)
+        }
+        fn begin_array_value<W: ?Sized + io::Write>(&mut self, w: &mut W, first: bool) -> io::Result<()> {
+            self.0.begin_array_value(w, first)
+        }
+        fn end_array_value<W: ?Sized + io::Write>(&mut self, w: &mut W) -> io::Result<()> {
+            self.0.end_array_value(w)
+        }
+        fn begin_object<W: ?Sized + io::Write>(&mut self, w: &mut W) -> io::Result<()> {
+            self.0.begin_object(w)
+        }
+        fn end_object<W: ?Sized + io::Write>(&mut self, w: &mut W) -> io::Result<()> {
+            self.0.end_object(w)
+        }
+        fn begin_object_key<W: ?Sized + io::Write>(&mut self, w: &mut W, first: bool) -> io::Result<()> {
+            self.0.begin_object_key(w, first)
+        }
+        fn begin_object_value<W: ?Sized + io::Write>(&mut self, w: &mut W) -> io::Result<()> {
+            w.write_all(b" : ")
+        }
+        fn end_object_value<W: ?Sized + io::Write>(&mut self, w: &mut W) -> io::Result<()> {
+            self.0.end_object_value(w)
+        }
+    }
+
+    let mut buffer = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(
+        &mut buffer,
+        XcodeFormatter(serde_json::ser::PrettyFormatter::new()),
+    );
+    serde::Serialize::serialize(value, &mut serializer).context("序列化 .xcstrings 失败")?;
+    let mut output = String::from_utf8(buffer).context("序列化 .xcstrings 失败")?;
+    output.push('\n');
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::stub::UppercaseTranslator;
+
+    const CATALOG: &str = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "Done" : {
+      "localizations" : {
+        "zh-Hans" : {
+          "stringUnit" : {
+            "state" : "translated",
+            "value" : "完成"
+          }
+        }
+      }
+    },
+    "files" : {
+      "localizations" : {
+        "en" : {
+          "variations" : {
+            "plural" : {
+              "one" : { "stringUnit" : { "state" : "translated", "value" : "%lld file" } },
+              "other" : { "stringUnit" : { "state" : "translated", "value" : "%lld files" } }
+            }
+          }
+        }
+      }
+    },
+    "id" : {
+      "shouldTranslate" : false
+    }
+  },
+  "version" : "1.0"
+}"#;
+
+    #[tokio::test]
+    async fn keeps_script_subtag_and_skips_existing() {
+        let (output, stats) = translate(CATALOG, &UppercaseTranslator, "en", "zh-Hans").await.unwrap();
+        assert_eq!((stats.translated, stats.skipped), (1, 2));
+
+        let catalog: Value = serde_json::from_str(&output).unwrap();
+        let strings = &catalog["strings"];
+        assert_eq!(strings["Done"]["localizations"]["zh-Hans"]["stringUnit"]["value"], "完成");
+        assert!(strings["Done"]["localizations"]["zh"].is_null());
+        let plural = &strings["files"]["localizations"]["zh-Hans"]["variations"]["plural"];
+        assert_eq!(plural["one"]["stringUnit"]["value"], "%lld FILE");
+        assert_eq!(plural["other"]["stringUnit"]["state"], "needs_review");
+        assert!(output.contains("\"sourceLanguage\" : \"en\""));
+    }
+
+    #[tokio::test]
+    async fn keeps_region_subtag() {
+        let (output, stats) = translate(CATALOG, &UppercaseTranslator, "en", "pt_br").await.unwrap();
+        assert_eq!(stats.translated, 2);
+
+        let catalog: Value = serde_json::from_str(&output).unwrap();
+        let strings = &catalog["strings"];
+        assert_eq!(strings["Done"]["localizations"]["pt-BR"]["stringUnit"]["value"], "DONE");
+        assert!(strings["Done"]["localizations"]["pt"].is_null());
+        assert!(strings["files"]["localizations"]["pt-BR"]["variations"]["plural"]["other"].is_object());
+    }
+}
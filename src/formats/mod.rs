@@ -0,0 +1,45 @@
+pub mod code;
+pub mod html;
+pub mod i18n;
+pub mod markdown;
+pub mod mobile;
+pub mod output;
+pub mod po;
+pub mod subtitle;
+pub mod text;
+pub mod xliff;
+pub mod xml;
+
+use std::path::Path;
+
+/// 文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FileFormat {
+    /// 根据扩展名自动判断
+    Auto,
+    /// 纯文本，逐行翻译
+    Text,
+    /// Markdown，只翻译正文
+    Markdown,
+    /// HTML，只翻译文本节点和 title/alt/placeholder 属性
+    Html,
+}
+
+impl FileFormat {
+    /// 确定文件的实际格式，Auto 时根据扩展名判断
+    pub fn resolve(self, path: &Path) -> FileFormat {
+        if self != FileFormat::Auto {
+            return self;
+        }
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+        match ext.as_str() {
+            "md" | "markdown" | "mdx" => FileFormat::Markdown,
+            "html" | "htm" | "xhtml" => FileFormat::Html,
+            _ => FileFormat::Text,
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// 翻译结果的写出方式
+#[derive(Debug, Clone)]
+pub enum OutputMode {
+    /// 输出到标准输出
+    Stdout,
+    /// 写入指定文件；输入有多个文件时视为目录
+    Path(PathBuf),
+    /// 在原文件名的扩展名前插入后缀，例如 doc.txt -> doc.en.txt
+    Suffix(String),
+    /// 覆盖原文件
+    InPlace,
+}
+
+impl OutputMode {
+    /// 根据命令行参数确定输出方式
+    pub fn from_args(output: &Option<PathBuf>, suffix: &Option<String>, in_place: bool) -> Self {
+        if in_place {
+            OutputMode::InPlace
+        } else if let Some(path) = output {
+            OutputMode::Path(path.clone())
+        } else if let Some(suffix) = suffix {
+            OutputMode::Suffix(suffix.clone())
+        } else {
+            OutputMode::Stdout
+        }
+    }
+
+    /// 计算输入文件对应的输出路径，输出到标准输出时返回 None
+    pub fn output_path(&self, input: &Path, multiple_inputs: bool) -> Result<Option<PathBuf>> {
+        let path = match self {
+            OutputMode::Stdout => return Ok(None),
+            OutputMode::InPlace => input.to_path_buf(),
+            OutputMode::Path(path) if multiple_inputs || path.is_dir() => {
+                let file_name = input
+                    .file_name()
+                    .ok_or_else(|| anyhow::anyhow!("无效的文件路径: {}", input.display()))?;
+                path.join(file_name)
+            }
+            OutputMode::Path(path) => path.clone(),
+            OutputMode::Suffix(suffix) => with_suffix(input, suffix),
+        };
+        Ok(Some(path))
+    }
+}
+
+/// 在扩展名前插入后缀
+fn with_suffix(input: &Path, suffix: &str) -> PathBuf {
+    let stem = input
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let file_name = match input.extension() {
+        Some(ext) => format!("{}{}.{}", stem, suffix, ext.to_string_lossy()),
+        None => format!("{}{}", stem, suffix),
+    };
+    input.with_file_name(file_name)
+}
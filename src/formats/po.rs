@@ -0,0 +1,420 @@
+use anyhow::Result;
+use std::ops::Range;
+
+use crate::formats::text::split_line_ending;
+use crate::translator::protect::{protect, Protected, I18N_KINDS};
+use crate::translator::Translator;
+
+/// PO 文件翻译统计
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PoStats {
+    /// 本次机器翻译的条目数
+    pub translated: usize,
+    /// 已有译文而跳过的条目数
+    pub skipped: usize,
+    /// 译文中格式占位符被破坏、保持未翻译的条目数
+    pub failed: usize,
+}
+
+/// PO 文件中的一个条目（以空行分隔的一组行）
+struct PoEntry {
+    /// 条目的所有行（不含换行符）
+    lines: Vec<String>,
+    msgid: String,
+    msgid_plural: Option<String>,
+    /// msgstr / msgstr[n] 的序号和内容，单数形式序号为 None
+    msgstrs: Vec<(Option<usize>, String)>,
+    /// msgstr 相关行在 `lines` 中的范围
+    msgstr_lines: Option<Range<usize>>,
+    obsolete: bool,
+}
+
+impl PoEntry {
+    fn parse(lines: Vec<String>) -> Self {
+        let mut entry = PoEntry {
+            lines,
+            msgid: String::new(),
+            msgid_plural: None,
+            msgstrs: Vec::new(),
+            msgstr_lines: None,
+            obsolete: false,
+        };
+
+        // 当前正在读取的字段，用于拼接续行
+        #[derive(PartialEq)]
+        enum Field {
+            None,
+            Msgid,
+            MsgidPlural,
+            Msgstr,
+            Other,
+        }
+        let mut field = Field::None;
+
+        for (index, line) in entry.lines.iter().enumerate() {
+            let line = line.trim();
+            if line.starts_with("#~") {
+                entry.obsolete = true;
+                continue;
+            }
+            if line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(rest) = line.strip_prefix("msgid_plural ") {
+                field = Field::MsgidPlural;
+                entry.msgid_plural = Some(unquote(rest));
+            } else if let Some(rest) = line.strip_prefix("msgid ") {
+                field = Field::Msgid;
+                entry.msgid = unquote(rest);
+            } else if let Some(rest) = line.strip_prefix("msgstr") {
+                field = Field::Msgstr;
+                let (plural_index, value) = match rest.strip_prefix('[') {
+                    Some(rest) => {
+                        let (n, value) = rest.split_once(']').unwrap_or((rest, ""));
+                        (n.trim().parse().ok(), value)
+                    }
+                    None => (None, rest),
+                };
+                entry.msgstrs.push((plural_index, unquote(value.trim())));
+                let start = entry.msgstr_lines.as_ref().map_or(index, |r| r.start);
+                entry.msgstr_lines = Some(start..index + 1);
+            } else if line.starts_with('"') {
+                let value = unquote(line);
+                match field {
+                    Field::Msgid => entry.msgid.push_str(&value),
+                    Field::MsgidPlural => {
+                        if let Some(plural) = entry.msgid_plural.as_mut() {
+                            plural.push_str(&value);
+                        }
+                    }
+                    Field::Msgstr => {
+                        if let Some((_, msgstr)) = entry.msgstrs.last_mut() {
+                            msgstr.push_str(&value);
+                        }
+                        if let Some(range) = entry.msgstr_lines.as_mut() {
+                            range.end = index + 1;
+                        }
+                    }
+                    _ => {}
+                }
+            } else {
+                // msgctxt 等其他字段
+                field = Field::Other;
+            }
+        }
+
+        entry
+    }
+
+    /// 是否为需要处理的翻译条目（排除文件头和废弃条目）
+    fn is_message(&self) -> bool {
+        !self.obsolete && !self.msgid.is_empty() && self.msgstr_lines.is_some()
+    }
+
+    fn is_translated(&self) -> bool {
+        self.msgstrs.iter().any(|(_, s)| !s.is_empty())
+    }
+
+    /// 写入译文并标记为 fuzzy
+    ///
+    /// 已知目标语言的复数形式数量时按其生成 msgstr[n]；只有一种形式的语言（如中文、日语）
+    /// 的 msgstr[0] 要覆盖所有数量，使用复数的译文
+    fn apply_translation(&mut self, singular: &str, plural: Option<&str>, nplurals: Option<usize>) {
+        let Some(range) = self.msgstr_lines.clone() else {
+            return;
+        };
+
+        let indices: Vec<Option<usize>> = match nplurals {
+            Some(n) if n > 0 && plural.is_some() => (0..n).map(Some).collect(),
+            _ => self.msgstrs.iter().map(|(index, _)| *index).collect(),
+        };
+        let mut new_lines = Vec::new();
+        for plural_index in indices {
+            match plural_index {
+                Some(0) if nplurals != Some(1) => new_lines.extend(format_field("msgstr[0]", singular)),
+                Some(n) => {
+                    let keyword = format!("msgstr[{}]", n);
+                    new_lines.extend(format_field(&keyword, plural.unwrap_or(singular)));
+                }
+                None => new_lines.extend(format_field("msgstr", singular)),
+            }
+        }
+        self.lines.splice(range, new_lines);
+        self.mark_fuzzy();
+    }
+
+    /// 在标志注释中加入 fuzzy，没有标志注释时在第一个字段前插入
+    fn mark_fuzzy(&mut self) {
+        if let Some(flags) = self.lines.iter_mut().find(|l| l.starts_with("#,")) {
+            if !flags.split(',').any(|f| f.trim() == "fuzzy") {
+                flags.push_str(", fuzzy");
+            }
+            return;
+        }
+        let position = self
+            .lines
+            .iter()
+            .position(|l| !l.starts_with('#'))
+            .unwrap_or(self.lines.len());
+        self.lines.insert(position, "#, fuzzy".to_string());
+    }
+}
+
+/// 翻译 PO/POT 文件中未翻译的条目
+///
+/// 处理 msgid_plural/msgstr[n]（复数形式数量取自文件头的 Plural-Forms），保留注释和引用，
+/// 机器翻译的条目标记为 fuzzy；`%d`、`%(name)s`、`{0}` 等占位符在翻译前被保护，
+/// 被破坏的条目保持未翻译。已有译文的条目只有在 `overwrite` 为 true 时才会重新翻译
+pub async fn translate_po(
+    content: &str,
+    translator: &dyn Translator,
+    from: &str,
+    to: &str,
+    overwrite: bool,
+) -> Result<(String, PoStats)> {
+    let ending = if content.contains("\r\n") { "\r\n" } else { "\n" };
+
+    // 按空行拆分为条目，空行本身保留为分隔
+    let mut blocks: Vec<Result<PoEntry, String>> = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    for raw in content.split_inclusive('\n') {
+        let (line, _) = split_line_ending(raw);
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(Ok(PoEntry::parse(std::mem::take(&mut current))));
+            }
+            blocks.push(Err(line.to_string()));
+        } else {
+            current.push(line.to_string());
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(Ok(PoEntry::parse(current)));
+    }
+
+    let nplurals = blocks.iter().find_map(|block| match block {
+        Ok(entry) if !entry.obsolete && entry.msgid.is_empty() && entry.msgid_plural.is_none() => {
+            entry.msgstrs.first().and_then(|(_, header)| nplurals(header))
+        }
+        _ => None,
+    });
+
+    let mut stats = PoStats::default();
+    let mut texts = Vec::new();
+    let mut pending = Vec::new();
+    for (index, block) in blocks.iter().enumerate() {
+        let Ok(entry) = block else { continue };
+        if !entry.is_message() {
+            continue;
+        }
+        if entry.is_translated() && !overwrite {
+            stats.skipped += 1;
+            continue;
+        }
+        texts.push(entry.msgid.clone());
+        if let Some(plural) = &entry.msgid_plural {
+            texts.push(plural.clone());
+        }
+        pending.push(index);
+    }
+
+    let protected: Vec<Protected> = texts.iter().map(|text| protect(text, I18N_KINDS)).collect();
+    let queries: Vec<String> = protected.iter().map(|p| p.text.clone()).collect();
+    let translated = translator.translate_batch(&queries, from, to).await?;
+    let mut translated = protected.iter().zip(translated).map(|(p, t)| p.restore(&t).ok());
+    for index in pending {
+        let Ok(entry) = &mut blocks[index] else { continue };
+        let singular = translated.next().flatten();
+        let plural = match entry.msgid_plural {
+            Some(_) => translated.next().flatten().map(Some),
+            None => Some(None),
+        };
+        match (singular, plural) {
+            (Some(singular), Some(plural)) => {
+                entry.apply_translation(&singular, plural.as_deref(), nplurals);
+                stats.translated += 1;
+            }
+            _ => stats.failed += 1,
+        }
+    }
+
+    let mut output = String::with_capacity(content.len());
+    let block_count = blocks.len();
+    for (index, block) in blocks.into_iter().enumerate() {
+        let lines = match block {
+            Ok(entry) => entry.lines,
+            Err(blank) => vec![blank],
+        };
+        for line in lines {
+            output.push_str(&line);
+            output.push_str(ending);
+        }
+        // 保持文件末尾是否有换行与原文一致
+        if index + 1 == block_count && !content.ends_with('\n') {
+            output.truncate(output.len() - ending.len());
+        }
+    }
+
+    Ok((output, stats))
+}
+
+/// 从文件头的 Plural-Forms 中读取复数形式的数量，例如 `nplurals=2; plural=(n != 1);`
+fn nplurals(header: &str) -> Option<usize> {
+    header
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Plural-Forms:"))?
+        .split(';')
+        .find_map(|part| part.trim().strip_prefix("nplurals="))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// 格式化一个字段，多行文本按 gettext 惯例拆成续行
+fn format_field(keyword: &str, value: &str) -> Vec<String> {
+    if !value.trim_end_matches('\n').contains('\n') {
+        return vec![format!("{} \"{}\"", keyword, escape(value))];
+    }
+
+    let mut lines = vec![format!("{} \"\"", keyword)];
+    for part in value.split_inclusive('\n') {
+        lines.push(format!("\"{}\"", escape(part)));
+    }
+    lines
+}
+
+/// 去掉引号并反转义
+fn unquote(s: &str) -> String {
+    let s = s.trim();
+    let inner = s
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s);
+
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// 按 PO 字符串规则转义
+fn escape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '"' => result.push_str("\\\""),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '\r' => result.push_str("\\r"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::stub::UppercaseTranslator;
+
+    const SOURCE: &str = r#"msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+
+#: src/main.rs:10
+msgid "One file"
+msgid_plural "%d files"
+msgstr[0] ""
+msgstr[1] ""
+
+#, c-format
+msgid "Hello %s"
+msgstr ""
+
+msgid "Done"
+msgstr "完成"
+
+#~ msgid "Old"
+#~ msgstr ""
+"#;
+
+    #[tokio::test]
+    async fn translates_plurals_and_marks_fuzzy() {
+        let (output, stats) = translate_po(SOURCE, &UppercaseTranslator, "en", "zh", false).await.unwrap();
+
+        assert_eq!(stats.translated, 2);
+        assert_eq!(stats.skipped, 1);
+        assert!(output.contains(
+            "#: src/main.rs:10\n#, fuzzy\nmsgid \"One file\"\nmsgid_plural \"%d files\"\nmsgstr[0] \"ONE FILE\"\nmsgstr[1] \"%d FILES\"\n"
+        ));
+        assert!(output.contains("#, c-format, fuzzy\nmsgid \"Hello %s\"\nmsgstr \"HELLO %s\"\n"));
+        assert!(output.contains("msgid \"Done\"\nmsgstr \"完成\"\n"));
+        // 文件头和废弃条目保持不变
+        assert!(output.starts_with("msgid \"\"\nmsgstr \"\"\n\"Content-Type"));
+        assert!(output.ends_with("#~ msgid \"Old\"\n#~ msgstr \"\"\n"));
+    }
+
+    #[tokio::test]
+    async fn round_trip_keeps_untouched_content() {
+        let (once, _) = translate_po(SOURCE, &UppercaseTranslator, "en", "zh", false).await.unwrap();
+        let (twice, stats) = translate_po(&once, &UppercaseTranslator, "en", "zh", false).await.unwrap();
+        assert_eq!(once, twice);
+        assert_eq!(stats.translated, 0);
+        assert_eq!(stats.skipped, 3);
+
+        // 重新翻译时 fuzzy 标志不会重复添加
+        let (again, stats) = translate_po(&once, &UppercaseTranslator, "en", "zh", true).await.unwrap();
+        assert_eq!(stats.translated, 3);
+        assert_eq!(again.matches("fuzzy").count(), 3);
+    }
+
+    #[tokio::test]
+    async fn keeps_crlf_and_multiline_strings() {
+        let source = "msgid \"\"\r\n\"first line\\n\"\r\n\"second line\"\r\nmsgstr \"\"";
+        let (output, _) = translate_po(source, &UppercaseTranslator, "en", "zh", false).await.unwrap();
+        assert_eq!(
+            output,
+            "#, fuzzy\r\nmsgid \"\"\r\n\"first line\\n\"\r\n\"second line\"\r\nmsgstr \"\"\r\n\"FIRST LINE\\n\"\r\n\"SECOND LINE\""
+        );
+    }
+
+    #[tokio::test]
+    async fn follows_plural_forms_from_the_header() {
+        let entry = "msgid \"%d file\"\nmsgid_plural \"%d files\"\nmsgstr[0] \"\"\nmsgstr[1] \"\"\n";
+        let header = |forms: &str| {
+            format!("msgid \"\"\nmsgstr \"\"\n\"Plural-Forms: {}\\n\"\n\n{}", forms, entry)
+        };
+
+        let (output, _) = translate_po(&header("nplurals=1; plural=0;"), &UppercaseTranslator, "en", "zh", false)
+            .await
+            .unwrap();
+        assert!(output.ends_with("msgid_plural \"%d files\"\nmsgstr[0] \"%d FILES\"\n"));
+
+        let forms = "nplurals=3; plural=(n%10==1 && n%100!=11 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2);";
+        let (output, _) = translate_po(&header(forms), &UppercaseTranslator, "en", "ru", false).await.unwrap();
+        assert!(output.ends_with(
+            "msgstr[0] \"%d FILE\"\nmsgstr[1] \"%d FILES\"\nmsgstr[2] \"%d FILES\"\n"
+        ));
+    }
+
+    #[test]
+    fn reads_nplurals() {
+        assert_eq!(nplurals("Language: ja\nPlural-Forms: nplurals=1; plural=0;\n"), Some(1));
+        assert_eq!(nplurals("Plural-Forms: nplurals=INTEGER; plural=EXPRESSION;\n"), None);
+        assert_eq!(nplurals("Content-Type: text/plain\n"), None);
+    }
+}
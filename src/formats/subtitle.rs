@@ -0,0 +1,319 @@
+use anyhow::Result;
+use std::ops::Range;
+
+use crate::formats::text::split_line_ending;
+use crate::translator::protect::Protected;
+use crate::translator::Translator;
+
+/// 字幕格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleFormat {
+    Srt,
+    WebVtt,
+}
+
+impl SubtitleFormat {
+    /// 根据文件头判断字幕格式
+    pub fn detect(content: &str) -> Self {
+        if content.trim_start_matches('\u{feff}').starts_with("WEBVTT") {
+            SubtitleFormat::WebVtt
+        } else {
+            SubtitleFormat::Srt
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SubtitleFormat::Srt => "SRT",
+            SubtitleFormat::WebVtt => "WebVTT",
+        }
+    }
+}
+
+/// 一条字幕的文本行
+struct Cue<'a> {
+    /// 每行的内容和换行符
+    lines: Vec<(&'a str, &'a str)>,
+    /// 在批量请求中的序号，没有文字（例如只有 ♪）时为 None
+    query: Option<usize>,
+}
+
+/// 字幕文件中的一行
+enum Line<'a> {
+    /// 序号、时间轴、空行以及 WebVTT 的 NOTE/STYLE 等块，原样保留
+    Verbatim(&'a str),
+    /// 一条字幕的全部文本行
+    Cue(Cue<'a>),
+}
+
+/// 翻译字幕文件，保留序号、时间轴和样式标签
+///
+/// 同一条字幕的多行文字连同样式标签作为一个句子提交，标签以占位符保护，
+/// 译文按原来的行数重新断行；`bilingual` 为 true 时先输出原文再输出译文
+pub async fn translate_subtitles(
+    content: &str,
+    translator: &dyn Translator,
+    from: &str,
+    to: &str,
+    bilingual: bool,
+) -> Result<String> {
+    let mut lines: Vec<Line> = Vec::new();
+    let mut in_cue_text = false;
+
+    for raw in content.split_inclusive('\n') {
+        let (body, ending) = split_line_ending(raw);
+
+        if body.trim().is_empty() {
+            in_cue_text = false;
+            lines.push(Line::Verbatim(raw));
+        } else if body.contains("-->") {
+            // 时间轴之后直到空行为止都是字幕文本
+            in_cue_text = true;
+            lines.push(Line::Verbatim(raw));
+        } else if in_cue_text {
+            match lines.last_mut() {
+                Some(Line::Cue(cue)) => cue.lines.push((body, ending)),
+                _ => lines.push(Line::Cue(Cue {
+                    lines: vec![(body, ending)],
+                    query: None,
+                })),
+            }
+        } else {
+            lines.push(Line::Verbatim(raw));
+        }
+    }
+
+    let mut protected: Vec<Protected> = Vec::new();
+    for line in &mut lines {
+        let Line::Cue(cue) = line else { continue };
+        let mut sentence = Protected::default();
+        let mut has_text = false;
+        for (i, (body, _)) in cue.lines.iter().enumerate() {
+            if i > 0 {
+                sentence.push_text(" ");
+            }
+            for (is_tag, piece) in split_styling(body.trim()) {
+                if is_tag {
+                    sentence.push_protected(piece);
+                } else {
+                    has_text |= piece.chars().any(char::is_alphabetic);
+                    sentence.push_text(piece);
+                }
+            }
+        }
+        if has_text {
+            cue.query = Some(protected.len());
+            protected.push(sentence);
+        }
+    }
+
+    let queries: Vec<String> = protected.iter().map(|p| p.text.clone()).collect();
+    let translated = translator.translate_batch(&queries, from, to).await?;
+    // 最后一行没有换行符时，双语的原文和译文之间沿用文件的换行风格
+    let default_ending = if content.contains("\r\n") { "\r\n" } else { "\n" };
+
+    let mut output = String::with_capacity(content.len() * 2);
+    for line in lines {
+        match line {
+            Line::Verbatim(raw) => output.push_str(raw),
+            Line::Cue(Cue { lines, query: None }) => {
+                for (body, ending) in lines {
+                    output.push_str(body);
+                    output.push_str(ending);
+                }
+            }
+            Line::Cue(Cue { lines, query: Some(index) }) => {
+                let restored = protected[index]
+                    .restore(&translated[index])
+                    .map_err(|e| anyhow::anyhow!("{}", e))?;
+                let last_ending = lines.last().map_or("", |(_, ending)| *ending);
+
+                if bilingual {
+                    for (body, ending) in &lines {
+                        output.push_str(body);
+                        output.push_str(if ending.is_empty() { default_ending } else { ending });
+                    }
+                }
+                let split = split_lines(&restored, lines.len());
+                for (i, text) in split.iter().enumerate() {
+                    output.push_str(text);
+                    let ending = match lines[i].1 {
+                        _ if i + 1 == split.len() => last_ending,
+                        "" => default_ending,
+                        ending => ending,
+                    };
+                    output.push_str(ending);
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// 将译文按可见宽度大致均分为不超过 `count` 行
+///
+/// 优先在空格处断开，中日韩文字之间也可以断开；不会断在样式标签内或句末标点之前
+fn split_lines(text: &str, count: usize) -> Vec<String> {
+    if count <= 1 {
+        return vec![text.to_string()];
+    }
+
+    // 可断开的位置：(断开时去掉的字节范围, 之前的可见字符数)
+    let mut breaks: Vec<(Range<usize>, usize)> = Vec::new();
+    let mut width = 0;
+    let mut offset = 0;
+    let mut prev: Option<char> = None;
+    for (is_tag, piece) in split_styling(text) {
+        if !is_tag {
+            for (i, c) in piece.char_indices() {
+                let pos = offset + i;
+                if c.is_whitespace() {
+                    if width > 0 {
+                        breaks.push((pos..pos + c.len_utf8(), width));
+                    }
+                } else {
+                    let wide = |c: char| c >= '\u{2e80}';
+                    if width > 0 && prev.is_some_and(|p| wide(p) || wide(c)) && !CLOSING_PUNCTUATION.contains(c) {
+                        breaks.push((pos..pos, width));
+                    }
+                    width += 1;
+                }
+                prev = Some(c);
+            }
+        }
+        offset += piece.len();
+    }
+
+    // 依次选择最接近每行目标宽度的位置
+    let mut chosen: Vec<Range<usize>> = Vec::new();
+    let mut min_width = 0;
+    for k in 1..count {
+        let target = width * k / count;
+        let best = breaks
+            .iter()
+            .filter(|(_, w)| *w > min_width && *w < width)
+            .min_by_key(|(_, w)| w.abs_diff(target));
+        if let Some((range, w)) = best {
+            chosen.push(range.clone());
+            min_width = *w;
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut cursor = 0;
+    for range in chosen.iter().chain(std::iter::once(&(text.len()..text.len()))) {
+        let line = text[cursor..range.start].trim();
+        if !line.is_empty() {
+            lines.push(line.to_string());
+        }
+        cursor = range.end;
+    }
+    lines
+}
+
+/// 不能出现在行首的标点
+const CLOSING_PUNCTUATION: &str = "，。！？、；：）」』》”’…,.!?;:)";
+
+/// 将字幕行拆分为样式标签和文字，返回 (是否为标签, 片段)
+///
+/// 支持 HTML 风格的 <i>、<c.yellow>、<00:01.000> 以及 ASS 风格的 {\an8}
+fn split_styling(line: &str) -> Vec<(bool, &str)> {
+    let mut pieces = Vec::new();
+    let mut text_start = 0;
+    let mut chars = line.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        let close = match c {
+            '<' => '>',
+            '{' => '}',
+            _ => continue,
+        };
+        let Some(len) = line[i..].find(close) else {
+            continue;
+        };
+        if i > text_start {
+            pieces.push((false, &line[text_start..i]));
+        }
+        let end = i + len + close.len_utf8();
+        pieces.push((true, &line[i..end]));
+        text_start = end;
+        // 跳过标签内部的字符
+        for _ in line[i + c.len_utf8()..end].chars() {
+            chars.next();
+        }
+    }
+
+    if text_start < line.len() {
+        pieces.push((false, &line[text_start..]));
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::stub::UppercaseTranslator;
+
+    #[test]
+    fn split_lines_balances_words() {
+        assert_eq!(
+            split_lines("<i>Hello there,</i> how are you doing today?", 2),
+            vec!["<i>Hello there,</i> how", "are you doing today?"]
+        );
+        assert_eq!(split_lines("Hello", 2), vec!["Hello"]);
+    }
+
+    #[test]
+    fn split_lines_breaks_between_cjk_characters() {
+        assert_eq!(split_lines("你好，今天过得怎么样？", 2), vec!["你好，今天", "过得怎么样？"]);
+        // 不会断在标签内部或句末标点之前
+        assert_eq!(
+            split_lines("<font color=\"red\">好的</font>。", 2),
+            vec!["<font color=\"red\">好", "的</font>。"]
+        );
+    }
+
+    #[test]
+    fn detects_webvtt_by_header() {
+        assert_eq!(SubtitleFormat::detect("\u{feff}WEBVTT\n\n00:01.000 --> 00:02.000\nHi\n"), SubtitleFormat::WebVtt);
+        assert_eq!(SubtitleFormat::detect("1\n00:00:01,000 --> 00:00:02,000\nWEBVTT\n"), SubtitleFormat::Srt);
+    }
+
+    #[tokio::test]
+    async fn translates_srt_cues_keeping_numbers_and_timestamps() {
+        let srt = concat!(
+            "1\n00:00:01,000 --> 00:00:02,500\n<i>Hello</i> there\nfriend\n\n",
+            "2\n00:00:03,000 --> 00:00:04,000\n♪ ♪\n\n",
+            "3\n00:00:05,000 --> 00:00:06,000\nBye\n",
+        );
+        let translated = translate_subtitles(srt, &UppercaseTranslator, "en", "zh", false).await.unwrap();
+        // 两行文字合并翻译后按原行数重新断行，没有文字的字幕原样保留
+        assert_eq!(
+            translated,
+            concat!(
+                "1\n00:00:01,000 --> 00:00:02,500\n<i>HELLO</i> THERE\nFRIEND\n\n",
+                "2\n00:00:03,000 --> 00:00:04,000\n♪ ♪\n\n",
+                "3\n00:00:05,000 --> 00:00:06,000\nBYE\n",
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn writes_bilingual_webvtt() {
+        let vtt = concat!(
+            "WEBVTT\r\n\r\nNOTE keep this\r\n\r\n",
+            "intro\r\n00:01.000 --> 00:02.000 align:start\r\n{\\an8}Good <00:01.500>morning",
+        );
+        let translated = translate_subtitles(vtt, &UppercaseTranslator, "en", "zh", true).await.unwrap();
+        // 原文在前、译文在后，最后一行没有换行符时沿用文件的 CRLF
+        assert_eq!(
+            translated,
+            concat!(
+                "WEBVTT\r\n\r\nNOTE keep this\r\n\r\n",
+                "intro\r\n00:01.000 --> 00:02.000 align:start\r\n{\\an8}Good <00:01.500>morning\r\n",
+                "{\\an8}GOOD <00:01.500>MORNING",
+            )
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use anyhow::Result;
+
+use crate::translator::Translator;
+
+/// 逐行翻译纯文本，保留原有的换行符（LF / CRLF）、空行和行首缩进
+pub async fn translate_text(
+    content: &str,
+    translator: &dyn Translator,
+    from: &str,
+    to: &str,
+) -> Result<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut endings: Vec<&str> = Vec::new();
+
+    for line in content.split_inclusive('\n') {
+        let (body, ending) = split_line_ending(line);
+        lines.push(body.to_string());
+        endings.push(ending);
+    }
+
+    let translated = translator.translate_batch(&lines, from, to).await?;
+
+    let mut output = String::with_capacity(content.len());
+    for (line, ending) in translated.iter().zip(endings) {
+        output.push_str(line);
+        output.push_str(ending);
+    }
+    Ok(output)
+}
+
+/// 拆分行内容和行尾换行符
+pub fn split_line_ending(line: &str) -> (&str, &str) {
+    if let Some(body) = line.strip_suffix("\r\n") {
+        (body, "\r\n")
+    } else if let Some(body) = line.strip_suffix('\n') {
+        (body, "\n")
+    } else {
+        (line, "")
+    }
+}
@@ -0,0 +1,492 @@
+use anyhow::{bail, Result};
+use std::ops::Range;
+
+use crate::formats::xml::{self, Token};
+use crate::language::registry;
+use crate::translator::protect::Protected;
+use crate::translator::Translator;
+
+/// XLIFF 版本
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XliffVersion {
+    V12,
+    V20,
+}
+
+impl XliffVersion {
+    pub fn name(&self) -> &'static str {
+        match self {
+            XliffVersion::V12 => "1.2",
+            XliffVersion::V20 => "2.0",
+        }
+    }
+}
+
+/// XLIFF 翻译统计
+#[derive(Debug, Default)]
+pub struct XliffStats {
+    pub translated: usize,
+    /// 已有译文的单元数
+    pub skipped: usize,
+    /// 内联标记在译文中丢失或重复而未写入的单元 (单元 id, 原因)
+    pub failures: Vec<(String, String)>,
+}
+
+/// 需要翻译的 source 元素
+struct PendingSource {
+    /// 所属 trans-unit / unit 的 id
+    unit_id: String,
+    /// 插入 target 的位置（source 或 seg-source 结束标签之后）
+    insert_at: usize,
+    /// source 所在行的缩进
+    indent: String,
+    /// 2.0 中 segment 开始标签的范围，用于写入 state
+    segment_tag: Option<Range<usize>>,
+    /// 2.0 中 unit 开始标签的结束位置及已有 notes 开始标签的结束位置，用于写入备注
+    unit_tag_end: usize,
+    notes_tag_end: Option<usize>,
+    /// 已有的空 target（`<target/>` 或 `<target></target>`）的范围及其开始标签，译文替换到这里
+    empty_target: Option<(Range<usize>, String)>,
+    protected: Protected,
+}
+
+/// 对原文的一次修改：将 `at` 开始的 `remove` 个字节替换为 `text`
+struct Edit {
+    at: usize,
+    remove: usize,
+    text: String,
+}
+
+impl Edit {
+    fn insert(at: usize, text: String) -> Self {
+        Self { at, remove: 0, text }
+    }
+}
+
+/// 检测 XLIFF 版本
+pub fn detect_version(content: &str) -> Result<XliffVersion> {
+    let root = xml::tokenize(content)
+        .into_iter()
+        .find(|t| t.local_name() == Some("xliff") && matches!(t, Token::Start { .. }));
+    let Some(root) = root else {
+        bail!("不是有效的 XLIFF 文件: 缺少 <xliff> 根元素");
+    };
+    match root.attr("version") {
+        Some(v) if v.starts_with('2') => Ok(XliffVersion::V20),
+        Some(v) if v.starts_with('1') => Ok(XliffVersion::V12),
+        _ => bail!("不支持的 XLIFF 版本，仅支持 1.2 和 2.0"),
+    }
+}
+
+/// 翻译 XLIFF 中没有 target 的单元
+///
+/// 译文状态设为待审校（1.2: needs-review-translation，2.0: translated + fanyi:needs-review），
+/// 并加入注明翻译服务的备注；`<ph>`、`<g>`、`<pc>` 等内联标记原样保留
+pub async fn translate_xliff(
+    content: &str,
+    translator: &dyn Translator,
+    from: &str,
+    to: &str,
+) -> Result<(String, XliffStats)> {
+    let version = detect_version(content)?;
+    let tokens = xml::tokenize(content);
+    let mut stats = XliffStats::default();
+    let pending = collect_pending(content, &tokens, version, &mut stats);
+
+    let texts: Vec<String> = pending.iter().map(|p| p.protected.text.clone()).collect();
+    let translated = translator.translate_batch(&texts, from, to).await?;
+
+    let note = format!("Machine translated by {} via fanyi", translator.name());
+    let mut edits = Vec::new();
+    let mut noted_units = std::collections::HashSet::new();
+
+    for (source, target) in pending.iter().zip(translated) {
+        // 先转义译文再还原内联标记，标记本身已是合法的 XML
+        let target = match source.protected.restore(&xml::escape_text(&target)) {
+            Ok(target) => target,
+            Err(e) => {
+                stats.failures.push((source.unit_id.clone(), e.to_string()));
+                continue;
+            }
+        };
+        stats.translated += 1;
+
+        match version {
+            XliffVersion::V12 => {
+                let state = " state=\"needs-review-translation\"";
+                let note = format!("<note from=\"fanyi\">{}</note>", xml::escape_text(&note));
+                edits.push(match &source.empty_target {
+                    Some((range, tag)) => Edit {
+                        at: range.start,
+                        remove: range.len(),
+                        text: format!(
+                            "{}{}</target>\n{}{}",
+                            rewrite_start_tag(tag, &["state"], state, false),
+                            target,
+                            xml::indent_before(content, range.start),
+                            note
+                        ),
+                    },
+                    None => Edit::insert(
+                        source.insert_at,
+                        format!(
+                            "\n{indent}<target{state}>{target}</target>\n{indent}{note}",
+                            indent = source.indent,
+                        ),
+                    ),
+                });
+            }
+            XliffVersion::V20 => {
+                edits.push(match &source.empty_target {
+                    Some((range, tag)) => Edit {
+                        at: range.start,
+                        remove: range.len(),
+                        text: format!("{}{}</target>", rewrite_start_tag(tag, &[], "", false), target),
+                    },
+                    None => Edit::insert(
+                        source.insert_at,
+                        format!("\n{}<target>{}</target>", source.indent, target),
+                    ),
+                });
+                if let Some(tag) = &source.segment_tag {
+                    // 没有译文的 segment 原有的 state（通常为 initial）不再适用
+                    let original = &content[tag.clone()];
+                    edits.push(Edit {
+                        at: tag.start,
+                        remove: tag.len(),
+                        text: rewrite_start_tag(
+                            original,
+                            &["state", "subState"],
+                            " state=\"translated\" subState=\"fanyi:needs-review\"",
+                            original.ends_with("/>"),
+                        ),
+                    });
+                }
+                if noted_units.insert(source.unit_tag_end) {
+                    // 与 segment 保持相同缩进
+                    let indent = source
+                        .segment_tag
+                        .as_ref()
+                        .map_or("", |tag| xml::indent_before(content, tag.start));
+                    let note = format!("<note category=\"provenance\">{}</note>", xml::escape_text(&note));
+                    let text = match source.notes_tag_end {
+                        Some(_) => note,
+                        None => format!("\n{}<notes>{}</notes>", indent, note),
+                    };
+                    edits.push(Edit::insert(source.notes_tag_end.unwrap_or(source.unit_tag_end), text));
+                }
+            }
+        }
+    }
+
+    if stats.translated > 0 {
+        edits.extend(target_language_edit(content, &tokens, version, to));
+    }
+
+    // 从后往前修改，保证前面的位置不受影响
+    edits.sort_by_key(|e| std::cmp::Reverse(e.at));
+    let mut output = content.to_string();
+    for edit in edits {
+        output.replace_range(edit.at..edit.at + edit.remove, &edit.text);
+    }
+
+    Ok((output, stats))
+}
+
+/// 找出所有需要翻译的 source
+fn collect_pending(
+    content: &str,
+    tokens: &[Token],
+    version: XliffVersion,
+    stats: &mut XliffStats,
+) -> Vec<PendingSource> {
+    // 1.2 以 trans-unit 为单位，2.0 以 segment 为单位判断是否已有 target
+    let container = match version {
+        XliffVersion::V12 => "trans-unit",
+        XliffVersion::V20 => "segment",
+    };
+
+    let mut pending = Vec::new();
+    let mut unit_id = String::new();
+    let mut unit_tag_end = 0;
+    let mut notes_tag_end = None;
+    let mut segment_tag = None;
+    let mut translatable = true;
+    let mut current: Option<PendingSource> = None;
+    let mut has_target = false;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        match (token, token.local_name()) {
+            (Token::Start { self_closing: false, range, .. }, Some("trans-unit" | "unit")) => {
+                unit_id = token.attr("id").unwrap_or_default().to_string();
+                unit_tag_end = range.end;
+                notes_tag_end = None;
+                translatable = token.attr("translate") != Some("no");
+                if version == XliffVersion::V12 {
+                    has_target = false;
+                    current = None;
+                }
+            }
+            (Token::Start { self_closing: false, range, .. }, Some("notes")) => {
+                notes_tag_end = Some(range.end);
+            }
+            (Token::Start { range, .. }, Some(name)) if name == container => {
+                segment_tag = Some(range.clone());
+                has_target = false;
+                current = None;
+            }
+            // 1.2 中 alt-trans 里的 target 是候选译文，不代表单元已翻译
+            (Token::Start { self_closing: false, .. }, Some("alt-trans")) => {
+                i = skip_element(tokens, i);
+            }
+            (Token::Start { self_closing, range, .. }, Some("target")) => {
+                let end = if *self_closing { i } else { skip_element(tokens, i) };
+                let empty = tokens
+                    .get(i + 1..end)
+                    .unwrap_or_default()
+                    .iter()
+                    .all(|t| matches!(t, Token::Text { .. }) && content[t.range().clone()].trim().is_empty());
+                match current.as_mut() {
+                    Some(p) if empty => {
+                        let element_end = tokens.get(end).map_or(content.len(), |t| t.range().end);
+                        p.empty_target = Some((range.start..element_end, content[range.clone()].to_string()));
+                    }
+                    _ => has_target = true,
+                }
+                i = end;
+            }
+            (Token::Start { self_closing: false, range, .. }, Some("source" | "seg-source")) => {
+                let is_source = token.local_name() == Some("source");
+                let (protected, end_index) = read_inline(content, tokens, i);
+                let close_end = tokens.get(end_index).map_or(content.len(), |t| t.range().end);
+
+                match current.as_mut() {
+                    // seg-source 出现时 target 需要放在它之后
+                    Some(p) if !is_source => p.insert_at = close_end,
+                    _ if is_source => {
+                        current = Some(PendingSource {
+                            unit_id: unit_id.clone(),
+                            insert_at: close_end,
+                            indent: xml::indent_before(content, range.start).to_string(),
+                            segment_tag: (version == XliffVersion::V20).then(|| segment_tag.clone()).flatten(),
+                            unit_tag_end,
+                            notes_tag_end,
+                            empty_target: None,
+                            protected,
+                        });
+                    }
+                    _ => {}
+                }
+                i = end_index;
+            }
+            (Token::End { .. }, Some(name)) if name == container => {
+                if let Some(p) = current.take() {
+                    if has_target {
+                        stats.skipped += 1;
+                    } else if translatable && !p.protected.text.trim().is_empty() {
+                        pending.push(p);
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    pending
+}
+
+/// 读取 source 的内联内容：文本反转义后参与翻译，内联标签整体作为受保护内容
+///
+/// 返回受保护文本和 source 结束标签的下标
+fn read_inline(content: &str, tokens: &[Token], start: usize) -> (Protected, usize) {
+    let mut protected = Protected::default();
+    let mut depth = 0;
+    let mut i = start + 1;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        let raw = &content[token.range().clone()];
+        match token {
+            Token::Text { .. } => protected.push_text(&xml::unescape(raw)),
+            Token::End { .. } if depth == 0 => return (protected, i),
+            // 1.2 中 ph/bpt/ept/it 的内容是原始代码，连同内容整体保护
+            Token::Start { self_closing: false, .. }
+                if matches!(token.local_name(), Some("ph" | "bpt" | "ept" | "it")) =>
+            {
+                let end = skip_element(tokens, i);
+                let range_end = tokens.get(end).map_or(content.len(), |t| t.range().end);
+                protected.push_protected(&content[token.range().start..range_end]);
+                i = end;
+            }
+            Token::Start { self_closing, .. } => {
+                if !self_closing {
+                    depth += 1;
+                }
+                protected.push_protected(raw);
+            }
+            Token::End { .. } => {
+                depth -= 1;
+                protected.push_protected(raw);
+            }
+            Token::Other { .. } => protected.push_protected(raw),
+        }
+        i += 1;
+    }
+    (protected, tokens.len())
+}
+
+/// 返回与 `start` 处开始标签匹配的结束标签下标
+fn skip_element(tokens: &[Token], start: usize) -> usize {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::Start { self_closing: false, .. } => depth += 1,
+            Token::End { .. } => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// 重写开始标签：去掉指定属性并追加新属性，`self_closing` 决定结尾是 `/>` 还是 `>`
+fn rewrite_start_tag(tag: &str, remove: &[&str], extra: &str, self_closing: bool) -> String {
+    let mut body = tag.trim_end_matches('>').trim_end_matches('/').trim_end().to_string();
+    for attr in remove {
+        let pattern = format!(r#"\s+{}\s*=\s*("[^"]*"|'[^']*')"#, regex::escape(attr));
+        if let Ok(re) = regex::Regex::new(&pattern) {
+            body = re.replace_all(&body, "").into_owned();
+        }
+    }
+    format!("{}{}{}", body, extra, if self_closing { "/>" } else { ">" })
+}
+
+/// 没有声明目标语言时补充 target-language (1.2) 或 trgLang (2.0)，值为 BCP-47 标签
+fn target_language_edit(content: &str, tokens: &[Token], version: XliffVersion, to: &str) -> Vec<Edit> {
+    let (element, attr) = match version {
+        XliffVersion::V12 => ("file", "target-language"),
+        XliffVersion::V20 => ("xliff", "trgLang"),
+    };
+    tokens
+        .iter()
+        .filter(|t| matches!(t, Token::Start { .. }) && t.local_name() == Some(element))
+        .filter(|t| t.attr(attr).is_none())
+        .map(|t| {
+            let end = t.range().end;
+            let at = end - if content[..end].ends_with("/>") { 2 } else { 1 };
+            Edit::insert(at, format!(" {}=\"{}\"", attr, xml::escape_attr(&registry::locale_tag(to))))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::stub::UppercaseTranslator;
+
+    const V12: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+  <file source-language="en" datatype="plaintext" original="app">
+    <body>
+      <trans-unit id="greeting">
+        <source>Hello <g id="1">world</g> &amp; friends</source>
+      </trans-unit>
+      <trans-unit id="empty">
+        <source>Save</source>
+        <target state="new"/>
+      </trans-unit>
+      <trans-unit id="done">
+        <source>Cancel</source>
+        <target>取消</target>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>
+"#;
+
+    const V20: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xliff version="2.0" xmlns="urn:oasis:names:tc:xliff:document:2.0" srcLang="en">
+  <file id="f1">
+    <unit id="u1">
+      <segment state="initial">
+        <source>Open <pc id="1">file</pc></source>
+      </segment>
+    </unit>
+    <unit id="u2">
+      <segment>
+        <source>Quit</source>
+        <target></target>
+      </segment>
+    </unit>
+  </file>
+</xliff>
+"#;
+
+    #[test]
+    fn detects_version() {
+        assert_eq!(detect_version(V12).unwrap(), XliffVersion::V12);
+        assert_eq!(detect_version(V20).unwrap(), XliffVersion::V20);
+        assert!(detect_version("<root/>").is_err());
+    }
+
+    #[tokio::test]
+    async fn translates_v12_units() {
+        let (output, stats) = translate_xliff(V12, &UppercaseTranslator, "en", "zh").await.unwrap();
+        assert_eq!((stats.translated, stats.skipped), (2, 1));
+        assert!(stats.failures.is_empty());
+
+        assert!(output.contains(r#"original="app" target-language="zh">"#));
+        assert!(output.contains(concat!(
+            "        <source>Hello <g id=\"1\">world</g> &amp; friends</source>\n",
+            "        <target state=\"needs-review-translation\">HELLO <g id=\"1\">WORLD</g> &amp; FRIENDS</target>\n",
+            "        <note from=\"fanyi\">Machine translated by stub via fanyi</note>\n",
+        )));
+        // 空的 <target/> 被替换，而不是再插入一个
+        assert!(output.contains(concat!(
+            "        <source>Save</source>\n",
+            "        <target state=\"needs-review-translation\">SAVE</target>\n",
+            "        <note from=\"fanyi\">Machine translated by stub via fanyi</note>\n",
+            "      </trans-unit>",
+        )));
+        assert_eq!(output.matches("<target").count(), 3);
+        assert!(output.contains("<target>取消</target>"));
+
+        // 再次翻译时所有单元都已有译文
+        let (again, stats) = translate_xliff(&output, &UppercaseTranslator, "en", "zh").await.unwrap();
+        assert_eq!((stats.translated, stats.skipped), (0, 3));
+        assert_eq!(again, output);
+    }
+
+    #[tokio::test]
+    async fn target_language_keeps_region() {
+        let (output, _) = translate_xliff(V12, &UppercaseTranslator, "en", "pt_br").await.unwrap();
+        assert!(output.contains(r#"original="app" target-language="pt-BR">"#));
+        let (output, _) = translate_xliff(V20, &UppercaseTranslator, "en", "en-gb").await.unwrap();
+        assert!(output.contains(r#"srcLang="en" trgLang="en-GB">"#));
+        let (output, _) = translate_xliff(V20, &UppercaseTranslator, "en", "日语").await.unwrap();
+        assert!(output.contains(r#"trgLang="ja">"#));
+    }
+
+    #[tokio::test]
+    async fn translates_v20_units() {
+        let (output, stats) = translate_xliff(V20, &UppercaseTranslator, "en", "ja").await.unwrap();
+        assert_eq!((stats.translated, stats.skipped), (2, 0));
+
+        assert!(output.contains(r#"srcLang="en" trgLang="ja">"#));
+        assert!(output.contains(concat!(
+            "    <unit id=\"u1\">\n",
+            "      <notes><note category=\"provenance\">Machine translated by stub via fanyi</note></notes>\n",
+            "      <segment state=\"translated\" subState=\"fanyi:needs-review\">\n",
+            "        <source>Open <pc id=\"1\">file</pc></source>\n",
+            "        <target>OPEN <pc id=\"1\">FILE</pc></target>\n",
+        )));
+        assert!(output.contains("        <source>Quit</source>\n        <target>QUIT</target>\n      </segment>"));
+        assert_eq!(output.matches("<target>").count(), 2);
+    }
+}
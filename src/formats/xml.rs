@@ -0,0 +1,233 @@
+use std::ops::Range;
+
+/// XML 标记，记录在原文中的字节范围以便原位修改
+#[derive(Debug, Clone)]
+pub enum Token {
+    /// 开始标签或自闭合标签
+    Start {
+        name: String,
+        attrs: Vec<(String, String)>,
+        self_closing: bool,
+        range: Range<usize>,
+    },
+    /// 结束标签
+    End { name: String, range: Range<usize> },
+    /// 文本内容（未反转义）
+    Text { range: Range<usize> },
+    /// 注释、CDATA、处理指令和 DOCTYPE，原样保留
+    Other { range: Range<usize> },
+}
+
+impl Token {
+    pub fn range(&self) -> &Range<usize> {
+        match self {
+            Token::Start { range, .. }
+            | Token::End { range, .. }
+            | Token::Text { range }
+            | Token::Other { range } => range,
+        }
+    }
+
+    /// 标签的本地名（去掉命名空间前缀）
+    pub fn local_name(&self) -> Option<&str> {
+        match self {
+            Token::Start { name, .. } | Token::End { name, .. } => {
+                Some(name.rsplit(':').next().unwrap_or(name))
+            }
+            _ => None,
+        }
+    }
+
+    /// 获取开始标签的属性值（已反转义）
+    pub fn attr(&self, key: &str) -> Option<&str> {
+        match self {
+            Token::Start { attrs, .. } => attrs
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str()),
+            _ => None,
+        }
+    }
+}
+
+/// 将 XML 文本拆分为标记序列
+///
+/// 这是一个宽松的扫描器，只识别标签边界和属性，不校验文档结构；
+/// 各标记的范围首尾相接覆盖整个输入，因此可以逐字节还原文档
+pub fn tokenize(src: &str) -> Vec<Token> {
+    tokenize_with(src, &[])
+}
+
+/// 按 HTML 规则拆分标记，script、style 和 textarea 的内容作为一个整体文本
+pub fn tokenize_html(src: &str) -> Vec<Token> {
+    tokenize_with(src, &["script", "style", "textarea"])
+}
+
+fn tokenize_with(src: &str, raw_text: &[&str]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < src.len() {
+        let rest = &src[pos..];
+        // 不是标签开头的 '<'（例如 "a < b"）按文本处理
+        let is_markup = rest.starts_with('<')
+            && rest[1..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?'));
+        if !is_markup {
+            let skip = if rest.starts_with('<') { 1 } else { 0 };
+            let end = rest[skip..].find('<').map_or(src.len(), |i| pos + skip + i);
+            tokens.push(Token::Text { range: pos..end });
+            pos = end;
+            continue;
+        }
+
+        let special_end = [("<!--", "-->"), ("<![CDATA[", "]]>"), ("<?", "?>")]
+            .iter()
+            .find(|(open, _)| rest.starts_with(open))
+            .map(|(_, close)| rest.find(close).map_or(src.len(), |i| pos + i + close.len()));
+        if let Some(end) = special_end {
+            tokens.push(Token::Other { range: pos..end });
+            pos = end;
+            continue;
+        }
+
+        let end = find_tag_end(rest).map_or(src.len(), |i| pos + i + 1);
+        let inner = &src[pos + 1..end.saturating_sub(1).max(pos + 1)];
+        let range = pos..end;
+        pos = end;
+
+        if inner.starts_with('!') {
+            tokens.push(Token::Other { range });
+        } else if let Some(name) = inner.strip_prefix('/') {
+            tokens.push(Token::End {
+                name: name.trim().to_string(),
+                range,
+            });
+        } else {
+            let self_closing = inner.ends_with('/');
+            let inner = inner.trim_end_matches('/');
+            let name_end = inner
+                .find(|c: char| c.is_whitespace())
+                .unwrap_or(inner.len());
+            let name = inner[..name_end].to_string();
+            let is_raw = !self_closing && raw_text.contains(&name.to_ascii_lowercase().as_str());
+            tokens.push(Token::Start {
+                attrs: parse_attrs(&inner[name_end..]),
+                name: name.clone(),
+                self_closing,
+                range,
+            });
+
+            // 原始文本元素的内容直到对应的结束标签为止，其中的 '<' 不是标签
+            if is_raw {
+                let close = format!("</{}", name.to_ascii_lowercase());
+                let end = src[pos..]
+                    .to_ascii_lowercase()
+                    .find(&close)
+                    .map_or(src.len(), |i| pos + i);
+                if end > pos {
+                    tokens.push(Token::Text { range: pos..end });
+                    pos = end;
+                }
+            }
+        }
+    }
+
+    tokens
+}
+
+/// 找到标签结尾的 '>'，跳过属性值中的 '>'
+fn find_tag_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_attrs(s: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut rest = s.trim_start();
+    while !rest.is_empty() {
+        let Some(eq) = rest.find('=') else { break };
+        let key = rest[..eq].trim().to_string();
+        let after = rest[eq + 1..].trim_start();
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(close) = after[1..].find(quote) else { break };
+        attrs.push((key, unescape(&after[1..close + 1])));
+        rest = after[close + 2..].trim_start();
+    }
+    attrs
+}
+
+/// 反转义 XML 实体
+pub fn unescape(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';').filter(|i| *i <= 10) else {
+            out.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 转义文本内容
+pub fn escape_text(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// 转义属性值（双引号）
+pub fn escape_attr(s: &str) -> String {
+    escape_text(s).replace('"', "&quot;")
+}
+
+/// 获取某个位置所在行的缩进
+pub fn indent_before(src: &str, pos: usize) -> &str {
+    let line_start = src[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let prefix = &src[line_start..pos];
+    let len = prefix.len() - prefix.trim_start().len();
+    &prefix[..len]
+}
@@ -0,0 +1,5 @@
+pub mod registry;
+pub mod validate;
+
+pub use registry::{Language, Provider};
+pub use validate::{validate_source, validate_target};
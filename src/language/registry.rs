@@ -0,0 +1,478 @@
+use std::fmt;
+
+/// 翻译服务提供商
+///
+/// 各提供商对同一语言的代码写法不同（例如日语: 百度为 jp，BCP-47 为 ja），
+/// 语言表中为每个提供商记录其专有代码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    Baidu,
+}
+
+impl Provider {
+    /// 所有已知的提供商
+    pub const ALL: &'static [Provider] = &[Provider::Baidu];
+
+    /// 按配置文件或命令行中的名称查找提供商
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "baidu" | "百度" => Some(Provider::Baidu),
+            _ => None,
+        }
+    }
+
+    /// 提供商在配置文件中使用的名称
+    pub fn id(&self) -> &'static str {
+        match self {
+            Provider::Baidu => "baidu",
+        }
+    }
+
+    /// 提供商的显示名称
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Provider::Baidu => "百度翻译",
+        }
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// 语言表中的一种语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Language {
+    /// ISO 639-1 两字母代码
+    pub iso639_1: Option<&'static str>,
+    /// ISO 639-3 三字母代码
+    pub iso639_3: Option<&'static str>,
+    /// BCP-47 语言标签，作为 fanyi 内部的标准代码
+    pub bcp47: &'static str,
+    /// 中文名称
+    pub name_zh: &'static str,
+    /// 英文名称
+    pub name_en: &'static str,
+    /// 百度翻译使用的代码，None 表示百度不支持
+    baidu: Option<&'static str>,
+}
+
+impl Language {
+    /// 获取指定提供商使用的语言代码
+    pub fn code_for(&self, provider: Provider) -> Option<&'static str> {
+        match provider {
+            Provider::Baidu => self.baidu,
+        }
+    }
+}
+
+/// 自动检测语言的特殊代码，所有提供商通用
+pub const AUTO: &str = "auto";
+
+const fn non_empty(s: &'static str) -> Option<&'static str> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+const fn lang(
+    iso639_1: &'static str,
+    iso639_3: &'static str,
+    bcp47: &'static str,
+    name_zh: &'static str,
+    name_en: &'static str,
+    baidu: &'static str,
+) -> Language {
+    Language {
+        iso639_1: non_empty(iso639_1),
+        iso639_3: non_empty(iso639_3),
+        bcp47,
+        name_zh,
+        name_en,
+        baidu: non_empty(baidu),
+    }
+}
+
+/// 语言表：ISO 639-1, ISO 639-3, BCP-47, 中文名, 英文名, 百度代码
+///
+/// 同一代码对应多条记录时（如 zh 与 zh-Hant），靠前的记录优先
+static LANGUAGES: &[Language] = &[
+    lang("zh", "zho", "zh", "中文", "Chinese", "zh"),
+    lang("", "zho", "zh-Hant", "繁体中文", "Traditional Chinese", "cht"),
+    lang("", "yue", "yue", "粤语", "Cantonese", "yue"),
+    lang("", "lzh", "lzh", "文言文", "Classical Chinese", "wyw"),
+    lang("en", "eng", "en", "英语", "English", "en"),
+    lang("ja", "jpn", "ja", "日语", "Japanese", "jp"),
+    lang("ko", "kor", "ko", "韩语", "Korean", "kor"),
+    lang("fr", "fra", "fr", "法语", "French", "fra"),
+    lang("es", "spa", "es", "西班牙语", "Spanish", "spa"),
+    lang("th", "tha", "th", "泰语", "Thai", "th"),
+    lang("ar", "ara", "ar", "阿拉伯语", "Arabic", "ara"),
+    lang("ru", "rus", "ru", "俄语", "Russian", "ru"),
+    lang("pt", "por", "pt", "葡萄牙语", "Portuguese", "pt"),
+    lang("de", "deu", "de", "德语", "German", "de"),
+    lang("it", "ita", "it", "意大利语", "Italian", "it"),
+    lang("el", "ell", "el", "希腊语", "Greek", "el"),
+    lang("nl", "nld", "nl", "荷兰语", "Dutch", "nl"),
+    lang("pl", "pol", "pl", "波兰语", "Polish", "pl"),
+    lang("bg", "bul", "bg", "保加利亚语", "Bulgarian", "bul"),
+    lang("et", "est", "et", "爱沙尼亚语", "Estonian", "est"),
+    lang("da", "dan", "da", "丹麦语", "Danish", "dan"),
+    lang("fi", "fin", "fi", "芬兰语", "Finnish", "fin"),
+    lang("cs", "ces", "cs", "捷克语", "Czech", "cs"),
+    lang("ro", "ron", "ro", "罗马尼亚语", "Romanian", "rom"),
+    lang("sl", "slv", "sl", "斯洛文尼亚语", "Slovenian", "slo"),
+    lang("sv", "swe", "sv", "瑞典语", "Swedish", "swe"),
+    lang("hu", "hun", "hu", "匈牙利语", "Hungarian", "hu"),
+    lang("vi", "vie", "vi", "越南语", "Vietnamese", "vie"),
+    lang("", "ach", "ach", "阿乔利语", "Acholi", "ach"),
+    lang("af", "afr", "af", "南非荷兰语", "Afrikaans", "afr"),
+    lang("ak", "aka", "ak", "阿肯语", "Akan", "aka"),
+    lang("sq", "sqi", "sq", "阿尔巴尼亚语", "Albanian", "alb"),
+    lang("", "arq", "arq", "阿尔及利亚阿拉伯语", "Algerian Arabic", "arq"),
+    lang("am", "amh", "am", "阿姆哈拉语", "Amharic", "amh"),
+    lang("", "ang", "ang", "古英语", "Old English", "eno"),
+    lang("an", "arg", "an", "阿拉贡语", "Aragonese", "arg"),
+    lang("hy", "hye", "hy", "亚美尼亚语", "Armenian", "arm"),
+    lang("as", "asm", "as", "阿萨姆语", "Assamese", "asm"),
+    lang("", "ast", "ast", "阿斯图里亚斯语", "Asturian", "ast"),
+    lang("ay", "aym", "ay", "艾马拉语", "Aymara", "aym"),
+    lang("az", "aze", "az", "阿塞拜疆语", "Azerbaijani", "aze"),
+    lang("", "bal", "bal", "俾路支语", "Baluchi", "bal"),
+    lang("ba", "bak", "ba", "巴什基尔语", "Bashkir", "bak"),
+    lang("eu", "eus", "eu", "巴斯克语", "Basque", "baq"),
+    lang("be", "bel", "be", "白俄罗斯语", "Belarusian", "bel"),
+    lang("", "bem", "bem", "别姆巴语", "Bemba", "bem"),
+    lang("bn", "ben", "bn", "孟加拉语", "Bengali", "ben"),
+    lang("", "ber", "ber", "柏柏尔语", "Berber", "ber"),
+    lang("", "bho", "bho", "博杰普尔语", "Bhojpuri", "bho"),
+    lang("bi", "bis", "bi", "比斯拉马语", "Bislama", "bis"),
+    lang("bs", "bos", "bs", "波斯尼亚语", "Bosnian", "bos"),
+    lang("br", "bre", "br", "布列塔尼语", "Breton", "bre"),
+    lang("my", "mya", "my", "缅甸语", "Burmese", "bur"),
+    lang("ca", "cat", "ca", "加泰罗尼亚语", "Catalan", "cat"),
+    lang("", "ceb", "ceb", "宿务语", "Cebuano", "ceb"),
+    lang("", "chr", "chr", "切罗基语", "Cherokee", "chr"),
+    lang("ny", "nya", "ny", "齐切瓦语", "Chichewa", "nya"),
+    lang("kw", "cor", "kw", "康沃尔语", "Cornish", "cor"),
+    lang("co", "cos", "co", "科西嘉语", "Corsican", "cos"),
+    lang("cr", "cre", "cr", "克里克语", "Cree", "cre"),
+    lang("", "crh", "crh", "克里米亚鞑靼语", "Crimean Tatar", "cri"),
+    lang("hr", "hrv", "hr", "克罗地亚语", "Croatian", "hrv"),
+    lang("dv", "div", "dv", "迪维希语", "Dhivehi", "div"),
+    lang("eo", "epo", "eo", "世界语", "Esperanto", "epo"),
+    lang("fo", "fao", "fo", "法罗语", "Faroese", "fao"),
+    lang("", "fil", "fil", "菲律宾语", "Filipino", "fil"),
+    lang("", "fur", "fur", "弗留利语", "Friulian", "fri"),
+    lang("ff", "ful", "ff", "富拉尼语", "Fulah", "ful"),
+    lang("gl", "glg", "gl", "加利西亚语", "Galician", "glg"),
+    lang("lg", "lug", "lg", "干达语", "Ganda", "lug"),
+    lang("ka", "kat", "ka", "格鲁吉亚语", "Georgian", "geo"),
+    lang("", "grc", "grc", "古希腊语", "Ancient Greek", "gra"),
+    lang("gn", "grn", "gn", "瓜拉尼语", "Guarani", "grn"),
+    lang("gu", "guj", "gu", "古吉拉特语", "Gujarati", "guj"),
+    lang("ht", "hat", "ht", "海地克里奥尔语", "Haitian Creole", "ht"),
+    lang("", "hak", "hak", "客家语", "Hakka", "hak"),
+    lang("ha", "hau", "ha", "豪萨语", "Hausa", "hau"),
+    lang("", "haw", "haw", "夏威夷语", "Hawaiian", "haw"),
+    lang("he", "heb", "he", "希伯来语", "Hebrew", "heb"),
+    lang("hi", "hin", "hi", "印地语", "Hindi", "hi"),
+    lang("", "hup", "hup", "胡帕语", "Hupa", "hup"),
+    lang("is", "isl", "is", "冰岛语", "Icelandic", "ice"),
+    lang("io", "ido", "io", "伊多语", "Ido", "ido"),
+    lang("ig", "ibo", "ig", "伊博语", "Igbo", "ibo"),
+    lang("id", "ind", "id", "印尼语", "Indonesian", "id"),
+    lang("", "inh", "inh", "印古什语", "Ingush", "ing"),
+    lang("ia", "ina", "ia", "国际语", "Interlingua", "ina"),
+    lang("iu", "iku", "iu", "因纽特语", "Inuktitut", "iku"),
+    lang("ga", "gle", "ga", "爱尔兰语", "Irish", "gle"),
+    lang("jv", "jav", "jv", "爪哇语", "Javanese", "jav"),
+    lang("", "kab", "kab", "卡拜尔语", "Kabyle", "kab"),
+    lang("kl", "kal", "kl", "格陵兰语", "Kalaallisut", "kal"),
+    lang("kn", "kan", "kn", "卡纳达语", "Kannada", "kan"),
+    lang("kr", "kau", "kr", "卡努里语", "Kanuri", "kau"),
+    lang("ks", "kas", "ks", "克什米尔语", "Kashmiri", "kas"),
+    lang("kk", "kaz", "kk", "哈萨克语", "Kazakh", "kaz"),
+    lang("km", "khm", "km", "高棉语", "Khmer", "hkm"),
+    lang("rw", "kin", "rw", "卢旺达语", "Kinyarwanda", "kin"),
+    lang("", "tlh", "tlh", "克林贡语", "Klingon", "kli"),
+    lang("kg", "kon", "kg", "刚果语", "Kongo", "kon"),
+    lang("ku", "kur", "ku", "库尔德语", "Kurdish", "kur"),
+    lang("ky", "kir", "ky", "吉尔吉斯语", "Kyrgyz", "kir"),
+    lang("lo", "lao", "lo", "老挝语", "Lao", "lao"),
+    lang("", "ltg", "ltg", "拉特加莱语", "Latgalian", "lag"),
+    lang("la", "lat", "la", "拉丁语", "Latin", "lat"),
+    lang("lv", "lav", "lv", "拉脱维亚语", "Latvian", "lav"),
+    lang("li", "lim", "li", "林堡语", "Limburgish", "lim"),
+    lang("ln", "lin", "ln", "林加拉语", "Lingala", "lin"),
+    lang("lt", "lit", "lt", "立陶宛语", "Lithuanian", "lit"),
+    lang("", "jbo", "jbo", "逻辑语", "Lojban", "loj"),
+    lang("lb", "ltz", "lb", "卢森堡语", "Luxembourgish", "ltz"),
+    lang("mk", "mkd", "mk", "马其顿语", "Macedonian", "mac"),
+    lang("", "mai", "mai", "迈蒂利语", "Maithili", "mai"),
+    lang("mg", "mlg", "mg", "马达加斯加语", "Malagasy", "mg"),
+    lang("ms", "msa", "ms", "马来语", "Malay", "may"),
+    lang("ml", "mal", "ml", "马拉雅拉姆语", "Malayalam", "mal"),
+    lang("mt", "mlt", "mt", "马耳他语", "Maltese", "mlt"),
+    lang("gv", "glv", "gv", "马恩岛语", "Manx", "glv"),
+    lang("mi", "mri", "mi", "毛利语", "Maori", "mao"),
+    lang("mr", "mar", "mr", "马拉地语", "Marathi", "mar"),
+    lang("mh", "mah", "mh", "马绍尔语", "Marshallese", "mah"),
+    lang("", "frm", "frm", "中古法语", "Middle French", "frm"),
+    lang("mn", "mon", "mn", "蒙古语", "Mongolian", ""),
+    lang("", "nap", "nap", "那不勒斯语", "Neapolitan", "nea"),
+    lang("ne", "nep", "ne", "尼泊尔语", "Nepali", "nep"),
+    lang("", "nso", "nso", "北索托语", "Northern Sotho", "ped"),
+    lang("no", "nor", "no", "挪威语", "Norwegian", "nor"),
+    lang("nb", "nob", "nb", "书面挪威语", "Norwegian Bokmål", "nob"),
+    lang("nn", "nno", "nn", "新挪威语", "Norwegian Nynorsk", "nno"),
+    lang("oc", "oci", "oc", "奥克语", "Occitan", "oci"),
+    lang("or", "ori", "or", "奥里亚语", "Odia", "ori"),
+    lang("oj", "oji", "oj", "奥杰布瓦语", "Ojibwe", "oji"),
+    lang("om", "orm", "om", "奥罗莫语", "Oromo", "orm"),
+    lang("os", "oss", "os", "奥塞梯语", "Ossetian", "oss"),
+    lang("", "pam", "pam", "邦板牙语", "Pampanga", "pam"),
+    lang("", "pap", "pap", "帕皮阿门托语", "Papiamento", "pap"),
+    lang("ps", "pus", "ps", "普什图语", "Pashto", "pus"),
+    lang("fa", "fas", "fa", "波斯语", "Persian", "per"),
+    lang("pa", "pan", "pa", "旁遮普语", "Punjabi", "pan"),
+    lang("qu", "que", "qu", "克丘亚语", "Quechua", "que"),
+    lang("rm", "roh", "rm", "罗曼什语", "Romansh", "roh"),
+    lang("sa", "san", "sa", "梵语", "Sanskrit", "san"),
+    lang("sc", "srd", "sc", "萨丁尼亚语", "Sardinian", "srd"),
+    lang("gd", "gla", "gd", "苏格兰盖尔语", "Scottish Gaelic", "gla"),
+    lang("sr", "srp", "sr", "塞尔维亚语(西里尔)", "Serbian (Cyrillic)", "src"),
+    lang("", "", "sr-Latn", "塞尔维亚语(拉丁)", "Serbian (Latin)", "srp"),
+    lang("sn", "sna", "sn", "绍纳语", "Shona", "sna"),
+    lang("sd", "snd", "sd", "信德语", "Sindhi", "snd"),
+    lang("si", "sin", "si", "僧伽罗语", "Sinhala", "sin"),
+    lang("sk", "slk", "sk", "斯洛伐克语", "Slovak", "sk"),
+    lang("so", "som", "so", "索马里语", "Somali", "som"),
+    lang("st", "sot", "st", "南索托语", "Southern Sotho", "sot"),
+    lang("su", "sun", "su", "巽他语", "Sundanese", "sun"),
+    lang("sw", "swa", "sw", "斯瓦希里语", "Swahili", "swa"),
+    lang("", "syr", "syr", "叙利亚语", "Syriac", "syr"),
+    lang("tl", "tgl", "tl", "他加禄语", "Tagalog", "tgl"),
+    lang("tg", "tgk", "tg", "塔吉克语", "Tajik", "tgk"),
+    lang("ta", "tam", "ta", "泰米尔语", "Tamil", "tam"),
+    lang("tt", "tat", "tt", "鞑靼语", "Tatar", "tat"),
+    lang("te", "tel", "te", "泰卢固语", "Telugu", "tel"),
+    lang("", "tet", "tet", "德顿语", "Tetum", "tet"),
+    lang("bo", "bod", "bo", "藏语", "Tibetan", ""),
+    lang("ti", "tir", "ti", "提格利尼亚语", "Tigrinya", "tir"),
+    lang("ts", "tso", "ts", "聪加语", "Tsonga", "tso"),
+    lang("tr", "tur", "tr", "土耳其语", "Turkish", "tr"),
+    lang("tk", "tuk", "tk", "土库曼语", "Turkmen", "tuk"),
+    lang("tw", "twi", "tw", "契维语", "Twi", "twi"),
+    lang("uk", "ukr", "uk", "乌克兰语", "Ukrainian", "ukr"),
+    lang("", "hsb", "hsb", "上索布语", "Upper Sorbian", "ups"),
+    lang("ur", "urd", "ur", "乌尔都语", "Urdu", "urd"),
+    lang("ug", "uig", "ug", "维吾尔语", "Uyghur", ""),
+    lang("ve", "ven", "ve", "文达语", "Venda", "ven"),
+    lang("wa", "wln", "wa", "瓦隆语", "Walloon", "wln"),
+    lang("cy", "cym", "cy", "威尔士语", "Welsh", "wel"),
+    lang("fy", "fry", "fy", "西弗里斯语", "Western Frisian", "fry"),
+    lang("wo", "wol", "wo", "沃洛夫语", "Wolof", "wol"),
+    lang("xh", "xho", "xh", "科萨语", "Xhosa", "xho"),
+    lang("yi", "yid", "yi", "意第绪语", "Yiddish", "yid"),
+    lang("yo", "yor", "yo", "约鲁巴语", "Yoruba", "yor"),
+    lang("", "zza", "zza", "扎扎其语", "Zazaki", "zaz"),
+    lang("zu", "zul", "zu", "祖鲁语", "Zulu", "zul"),
+];
+
+/// 常用别名，保持与早期版本兼容（例如 jp、kr 曾是命令行中可用的写法）
+static ALIASES: &[(&str, &str)] = &[
+    ("jp", "ja"),
+    ("kr", "ko"),
+    ("cn", "zh"),
+    ("zh-cn", "zh"),
+    ("zh-sg", "zh"),
+    ("zh-hans", "zh"),
+    ("zh-tw", "zh-Hant"),
+    ("zh-hk", "zh-Hant"),
+    ("zh-mo", "zh-Hant"),
+    ("cht", "zh-Hant"),
+    ("繁体", "zh-Hant"),
+    ("简体中文", "zh"),
+    ("汉语", "zh"),
+    ("普通话", "zh"),
+    ("farsi", "fa"),
+    ("tagalog", "tl"),
+    ("serbian", "sr"),
+    ("塞尔维亚语", "sr"),
+];
+
+/// 将用户输入的语言解析为语言表中的条目
+///
+/// 支持 ISO 639-1/639-3 代码、BCP-47 标签（带地区的标签会回退到主语言）、
+/// 中英文名称（"日语"、"日文"、"japanese"）以及各提供商的专有代码
+pub fn resolve(input: &str) -> Option<&'static Language> {
+    let key = input.trim().replace('_', "-").to_lowercase();
+    if key.is_empty() || key == AUTO {
+        return None;
+    }
+
+    if let Some((_, target)) = ALIASES.iter().find(|(alias, _)| *alias == key) {
+        return find_exact(&target.to_lowercase());
+    }
+
+    if let Some(language) = find_exact(&key) {
+        return Some(language);
+    }
+
+    // "日文" 与 "日语" 视为同一语言
+    if let Some(stem) = key.strip_suffix('文') {
+        if let Some(language) = resolve(&format!("{}语", stem)) {
+            return Some(language);
+        }
+    }
+
+    // 带地区或文字的 BCP-47 标签回退到主语言，例如 pt-BR -> pt
+    if let Some((primary, _)) = key.split_once('-') {
+        return resolve(primary);
+    }
+
+    None
+}
+
+/// 在语言表中精确匹配代码或名称（key 需为小写）
+///
+/// 提供商代码先于 ISO 639-3 匹配：百度的拉丁塞尔维亚语代码 srp 与西里尔塞尔维亚语的 ISO 639-3 代码相同
+fn find_exact(key: &str) -> Option<&'static Language> {
+    let matches = |value: Option<&str>| value.is_some_and(|v| v.to_lowercase() == key);
+
+    LANGUAGES
+        .iter()
+        .find(|l| l.bcp47.to_lowercase() == key)
+        .or_else(|| LANGUAGES.iter().find(|l| matches(l.iso639_1)))
+        .or_else(|| {
+            LANGUAGES.iter().find(|l| {
+                Provider::ALL.iter().any(|p| matches(l.code_for(*p)))
+            })
+        })
+        .or_else(|| LANGUAGES.iter().find(|l| matches(l.iso639_3)))
+        .or_else(|| {
+            LANGUAGES
+                .iter()
+                .find(|l| l.name_zh == key || l.name_en.to_lowercase() == key)
+        })
+}
+
+/// 根据提供商返回的代码反查语言
+pub fn from_provider_code(provider: Provider, code: &str) -> Option<&'static Language> {
+    LANGUAGES
+        .iter()
+        .find(|l| l.code_for(provider).is_some_and(|c| c.eq_ignore_ascii_case(code)))
+}
+
+/// 将用户输入的语言转换为指定提供商的代码
+///
+/// "auto" 原样保留；无法识别或该提供商不支持的语言返回 None
+pub fn provider_code(input: &str, provider: Provider) -> Option<&'static str> {
+    if input.trim().eq_ignore_ascii_case(AUTO) {
+        return Some(AUTO);
+    }
+    resolve(input)?.code_for(provider)
+}
+
+/// 将提供商返回的代码转换为 BCP-47 标签，无法识别时原样返回
+pub fn normalize_provider_code(provider: Provider, code: &str) -> String {
+    from_provider_code(provider, code)
+        .map(|l| l.bcp47.to_string())
+        .unwrap_or_else(|| code.to_string())
+}
+
+/// 将用户输入的语言统一为主语言的 BCP-47 标签，无法识别时返回小写的原文
+///
+/// 地区子标签会被丢弃（pt-BR -> pt，zh-TW -> zh-Hant），只适合用来比较两个语言是否相同；
+/// 需要写入文件的语言标签请使用 [`locale_tag`]
+pub fn primary_tag(input: &str) -> String {
+    resolve(input).map_or_else(|| input.trim().to_lowercase(), |l| l.bcp47.to_string())
+}
+
+/// 将用户输入的语言转换为写入资源文件的 BCP-47 标签
+///
+/// 地区和文字子标签原样保留，只统一大小写（pt-br -> pt-BR，zh_hans -> zh-Hans）；
+/// 别名、名称和提供商代码（jp、japanese、日语、srp）转换为语言表中的标签
+pub fn locale_tag(input: &str) -> String {
+    let key = input.trim().replace('_', "-");
+    let subtags: Vec<&str> = key.split('-').collect();
+    let is_tag = subtags.len() > 1
+        && subtags.iter().all(|s| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric()));
+    if !is_tag {
+        return primary_tag(&key);
+    }
+
+    let mut tag = resolve(subtags[0]).map_or_else(|| subtags[0].to_lowercase(), |l| l.bcp47.to_string());
+    for subtag in &subtags[1..] {
+        tag.push('-');
+        match subtag.len() {
+            // 文字子标签首字母大写: Hans、Latn
+            4 if subtag.chars().all(|c| c.is_ascii_alphabetic()) => {
+                tag.push_str(&subtag[..1].to_uppercase());
+                tag.push_str(&subtag[1..].to_lowercase());
+            }
+            // 地区子标签大写: BR、419
+            2 | 3 => tag.push_str(&subtag.to_uppercase()),
+            _ => tag.push_str(&subtag.to_lowercase()),
+        }
+    }
+    tag
+}
+
+/// 获取指定提供商支持的所有语言
+pub fn supported(provider: Provider) -> Vec<&'static Language> {
+    LANGUAGES
+        .iter()
+        .filter(|l| l.code_for(provider).is_some())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn baidu_code_srp_resolves_to_latin_serbian() {
+        assert_eq!(resolve("srp").map(|l| l.bcp47), Some("sr-Latn"));
+        assert_eq!(provider_code("srp", Provider::Baidu), Some("srp"));
+        assert_eq!(provider_code("sr", Provider::Baidu), Some("src"));
+    }
+
+    #[test]
+    fn region_subtags_fall_back_to_primary_language() {
+        assert_eq!(primary_tag("EN-US"), "en");
+        assert_eq!(primary_tag("zh_TW"), "zh-Hant");
+        assert_eq!(primary_tag("日语"), "ja");
+        assert_eq!(primary_tag("jp"), "ja");
+    }
+
+    #[test]
+    fn locale_tag_keeps_region_and_script() {
+        assert_eq!(locale_tag("pt-br"), "pt-BR");
+        assert_eq!(locale_tag("zh_hans"), "zh-Hans");
+        assert_eq!(locale_tag("zh-CN"), "zh-CN");
+        assert_eq!(locale_tag("ZH-HANT-TW"), "zh-Hant-TW");
+        assert_eq!(locale_tag("es-419"), "es-419");
+        assert_eq!(locale_tag("jp-JP"), "ja-JP");
+        // 别名、名称和提供商代码
+        assert_eq!(locale_tag("jp"), "ja");
+        assert_eq!(locale_tag("Japanese"), "ja");
+        assert_eq!(locale_tag("日语"), "ja");
+        assert_eq!(locale_tag("cht"), "zh-Hant");
+        assert_eq!(locale_tag("srp"), "sr-Latn");
+    }
+
+    #[test]
+    fn bare_language_names_resolve() {
+        assert_eq!(resolve("serbian").map(|l| l.bcp47), Some("sr"));
+        assert_eq!(resolve("塞尔维亚语").map(|l| l.bcp47), Some("sr"));
+        assert_eq!(resolve("塞尔维亚文").map(|l| l.bcp47), Some("sr"));
+        assert_eq!(resolve("日文").map(|l| l.bcp47), Some("ja"));
+        assert_eq!(resolve("Serbian (Latin)").map(|l| l.bcp47), Some("sr-Latn"));
+    }
+}
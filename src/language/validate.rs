@@ -0,0 +1,152 @@
+use thiserror::Error;
+
+use crate::language::registry::{self, Language, Provider, AUTO};
+use crate::utils::edit_distance;
+
+/// 最多给出的建议数量
+const MAX_SUGGESTIONS: usize = 3;
+
+/// 语言参数校验错误
+#[derive(Debug, Error)]
+pub enum LanguageError {
+    #[error("未知的语言: {input}{}", format_suggestions(.suggestions))]
+    Unknown {
+        input: String,
+        suggestions: Vec<&'static Language>,
+    },
+    #[error("{provider} 不支持 {name} ({input})")]
+    Unsupported {
+        input: String,
+        name: &'static str,
+        provider: &'static str,
+    },
+    #[error("目标语言不能为 auto")]
+    AutoTarget,
+}
+
+fn format_suggestions(suggestions: &[&'static Language]) -> String {
+    if suggestions.is_empty() {
+        return "，使用 'fanyi languages' 查看支持的语言".to_string();
+    }
+    let names: Vec<String> = suggestions
+        .iter()
+        .map(|l| format!("{} ({}, {})", l.bcp47, l.name_en, l.name_zh))
+        .collect();
+    format!("，您是不是要找: {}?", names.join(" / "))
+}
+
+/// 校验源语言参数，返回其对应的提供商代码（"auto" 原样保留）
+pub fn validate_source(input: &str, provider: Provider) -> Result<&'static str, LanguageError> {
+    if input.trim().eq_ignore_ascii_case(AUTO) {
+        return Ok(AUTO);
+    }
+    validate(input, provider)
+}
+
+/// 校验目标语言参数，返回其对应的提供商代码
+pub fn validate_target(input: &str, provider: Provider) -> Result<&'static str, LanguageError> {
+    if input.trim().eq_ignore_ascii_case(AUTO) {
+        return Err(LanguageError::AutoTarget);
+    }
+    validate(input, provider)
+}
+
+fn validate(input: &str, provider: Provider) -> Result<&'static str, LanguageError> {
+    match registry::resolve(input) {
+        Some(language) => language
+            .code_for(provider)
+            .ok_or(LanguageError::Unsupported {
+                input: input.to_string(),
+                name: language.name_zh,
+                provider: provider.display_name(),
+            }),
+        None => Err(LanguageError::Unknown {
+            input: input.to_string(),
+            suggestions: suggest(input, provider),
+        }),
+    }
+}
+
+/// 按编辑距离查找与输入最接近的语言，只在提供商支持的语言中查找
+pub fn suggest(input: &str, provider: Provider) -> Vec<&'static Language> {
+    let key = input.trim().to_lowercase();
+    // 允许的最大编辑距离随输入长度增加，短代码只容忍一处错误
+    let max_distance = (key.chars().count() / 3).clamp(1, 3);
+
+    let mut scored: Vec<(usize, &'static Language)> = registry::supported(provider)
+        .into_iter()
+        .filter_map(|language| {
+            let candidates = [
+                Some(language.bcp47),
+                language.iso639_1,
+                language.iso639_3,
+                language.code_for(provider),
+                Some(language.name_en),
+                Some(language.name_zh),
+            ];
+            candidates
+                .into_iter()
+                .flatten()
+                .map(|candidate| edit_distance(&key, &candidate.to_lowercase()))
+                .min()
+                .filter(|distance| *distance <= max_distance)
+                .map(|distance| (distance, language))
+        })
+        .collect();
+
+    scored.sort_by_key(|(distance, _)| *distance);
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, language)| language)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggested(input: &str) -> Vec<&'static str> {
+        suggest(input, Provider::Baidu).into_iter().map(|l| l.bcp47).collect()
+    }
+
+    #[test]
+    fn returns_provider_codes() {
+        assert_eq!(validate_source("auto", Provider::Baidu).unwrap(), "auto");
+        assert_eq!(validate_source(" AUTO ", Provider::Baidu).unwrap(), "auto");
+        assert_eq!(validate_source("Japanese", Provider::Baidu).unwrap(), "jp");
+        assert_eq!(validate_target("zh-TW", Provider::Baidu).unwrap(), "cht");
+        assert_eq!(validate_target("韩文", Provider::Baidu).unwrap(), "kor");
+        assert_eq!(validate_target("fr-CA", Provider::Baidu).unwrap(), "fra");
+    }
+
+    #[test]
+    fn rejects_auto_as_target() {
+        let error = validate_target("Auto", Provider::Baidu).unwrap_err();
+        assert!(matches!(error, LanguageError::AutoTarget));
+        assert_eq!(error.to_string(), "目标语言不能为 auto");
+    }
+
+    #[test]
+    fn suggests_close_languages() {
+        assert_eq!(suggested("Japanes").first(), Some(&"ja"));
+        assert_eq!(suggested("frnch").first(), Some(&"fr"));
+        assert_eq!(suggested("日与").first(), Some(&"ja"));
+        assert!(suggested("japanese").len() <= MAX_SUGGESTIONS);
+        assert!(suggested("qqqqqqqqqq").is_empty());
+    }
+
+    #[test]
+    fn explains_unknown_languages() {
+        let error = validate_source("Japanes", Provider::Baidu).unwrap_err();
+        let LanguageError::Unknown { ref input, ref suggestions } = error else {
+            panic!("应为未知语言: {:?}", error);
+        };
+        assert_eq!(input, "Japanes");
+        assert_eq!(suggestions[0].bcp47, "ja");
+        assert!(error.to_string().starts_with("未知的语言: Japanes，您是不是要找: ja (Japanese, 日语)"));
+
+        let error = validate_target("qqqqqqqqqq", Provider::Baidu).unwrap_err();
+        assert_eq!(error.to_string(), "未知的语言: qqqqqqqqqq，使用 'fanyi languages' 查看支持的语言");
+    }
+}
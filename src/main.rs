@@ -1,16 +1,41 @@
 mod config;
 mod cli;
+mod formats;
+mod language;
+mod memory;
+mod rpc;
+mod server;
 mod translator;
 mod ui;
+mod utils;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
 use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use cli::{Cli, Commands};
-use config::{Config, ProxyMode, ProxyConfig};
+use cli::{Cli, Commands, DaemonCommands, I18nCommands, MobileCommands, PoCommands, TmCommands, XliffCommands};
+use config::{Config, Origin, ProxyMode, ProxyConfig};
+use formats::output::OutputMode;
+use formats::code::CodeLanguage;
+use formats::i18n::{self, I18nFormat};
+use formats::mobile::MobileFormat;
+use formats::po::translate_po;
+use formats::subtitle::{translate_subtitles, SubtitleFormat};
+use formats::xliff::{detect_version, translate_xliff};
+use formats::FileFormat;
+use language::{registry, validate_source, validate_target, Provider};
+use memory::{tmx, MemoryMatch, MemoryUnit, TranslationMemory};
 use translator::{BaiduTranslator, Translator};
-use ui::{display_translation, display_error, display_success, display_info, display_warning};
+use translator::cache::CachedTranslator;
+use translator::image::image_mime_type;
+use translator::glossary::{Glossary, GlossaryTranslator, GlossaryViolation};
+use translator::memory::MemoryTranslator;
+use translator::protect::ProtectedTranslator;
+use translator::trait_def::{TranslationNotices, TranslationResult};
+use utils::{diff, encoding};
+use ui::{display_translation, display_image_translation, display_error, display_success, display_info, display_warning};
 
 #[tokio::main]
 async fn main() {
@@ -34,30 +59,134 @@ async fn run() -> Result<()> {
             http_proxy,
             https_proxy,
             show,
+            origin,
         }) => {
-            return handle_config_command(app_id, secret_key, default_from, default_to, 
-                                       proxy_mode, http_proxy, https_proxy, *show);
+            return handle_config_command(&cli, app_id, secret_key, default_from, default_to, 
+                                       proxy_mode, http_proxy, https_proxy, *show, *origin);
         }
-        Some(Commands::Languages) => {
-            return handle_languages_command();
+        Some(Commands::Languages { provider }) => {
+            return handle_languages_command(&cli, provider);
         }
         Some(Commands::ProxyStatus) => {
-            return handle_proxy_status_command();
+            return handle_proxy_status_command(&cli);
+        }
+        Some(Commands::File { paths, output, suffix, in_place, format }) => {
+            let mode = OutputMode::from_args(output, suffix, *in_place);
+            return handle_file_command(&cli, paths, &mode, *format).await;
+        }
+        Some(Commands::Code { paths, dry_run }) => {
+            return handle_code_command(&cli, paths, *dry_run).await;
+        }
+        Some(Commands::Subs { path, output, bilingual }) => {
+            return handle_subs_command(&cli, path, output, *bilingual).await;
+        }
+        Some(Commands::Po { action: PoCommands::Translate { path, output, overwrite } }) => {
+            return handle_po_translate_command(&cli, path, output, *overwrite).await;
+        }
+        Some(Commands::I18n { action: I18nCommands::Translate { path, output } }) => {
+            return handle_i18n_translate_command(&cli, path, output).await;
+        }
+        Some(Commands::I18n { action: I18nCommands::Sync { source, targets, dir } }) => {
+            return handle_i18n_sync_command(&cli, source, targets, dir).await;
+        }
+        Some(Commands::Xliff { action: XliffCommands::Translate { path, output } }) => {
+            return handle_xliff_translate_command(&cli, path, output).await;
+        }
+        Some(Commands::Mobile { action: MobileCommands::Translate { path, output } }) => {
+            return handle_mobile_translate_command(&cli, path, output).await;
+        }
+        Some(Commands::Serve { listen, token, compat }) => {
+            return handle_serve_command(&cli, listen, token, *compat).await;
+        }
+        Some(Commands::Rpc) => {
+            return handle_rpc_command(&cli, false).await;
+        }
+        Some(Commands::Mcp) => {
+            return handle_rpc_command(&cli, true).await;
+        }
+        Some(Commands::Lsp) => {
+            return handle_lsp_command(&cli).await;
+        }
+        Some(Commands::Tm { action }) => {
+            return handle_tm_command(&cli, action);
+        }
+        Some(Commands::Daemon { action }) => {
+            return handle_daemon_command(&cli, action).await;
+        }
+        Some(Commands::Image { path }) => {
+            return handle_image_command(&cli, path).await;
         }
         None => {}
     }
 
-    // 加载配置
-    let mut config = Config::load()?;
-    let enable_colors = config.ui.enable_colors && !cli.no_color;
+    let (config, enable_colors) = prepare_config(&cli)?;
 
-    // 处理命令行代理覆盖
-    if cli.no_proxy {
-        config.proxy.enabled = ProxyMode::Disable;
-    } else if cli.force_proxy {
-        config.proxy.enabled = ProxyMode::Enable;
+    // 获取要翻译的文本
+    let text = get_translation_text(&cli).await?;
+    if text.trim().is_empty() {
+        display_error("要翻译的文本不能为空", enable_colors);
+        std::process::exit(1);
+    }
+
+    // 获取源语言和目标语言
+    let from_lang = cli.get_from_language(&config.translation.default_from);
+    let to_lang = cli.get_to_language(&config.translation.default_to);
+
+    // 在发起请求前校验语言参数
+    check_languages(&from_lang, &to_lang, Provider::Baidu, enable_colors);
+
+    // 守护进程在运行且配置与当前一致时交给它翻译
+    if let Some(result) = translate_via_daemon(&config, &text, &from_lang, &to_lang).await {
+        match result {
+            Ok((result, notices)) => {
+                if cli.json {
+                    println!("{}", serde_json::to_string_pretty(&result)?);
+                } else {
+                    display_translation(&result, enable_colors, config.ui.show_language_detection);
+                }
+                display_notices(&notices, cli.json, enable_colors);
+            }
+            Err(e) => {
+                display_error(&e.to_string(), enable_colors);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
     }
 
+    // 创建翻译器（传递代理配置）
+    let translator = new_translator(&config)?;
+
+    // 执行翻译
+    match translator.translate(&text, &from_lang, &to_lang).await {
+        Ok(result) => {
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else {
+                display_translation(&result, enable_colors, config.ui.show_language_detection);
+            }
+            let notices = translator.notices(&text, &result.target, &from_lang, &to_lang);
+            display_notices(&notices, cli.json, enable_colors);
+        }
+        Err(e) => {
+            display_error(&e.to_string(), enable_colors);
+            display_info("如果是网络错误，请检查代理设置或使用 'fanyi proxy-status' 查看代理状态", enable_colors);
+            std::process::exit(1);
+        }
+    }
+
+    Ok(())
+}
+
+/// 加载配置并应用命令行覆盖，配置不完整时直接退出
+///
+/// 返回配置以及是否启用颜色输出
+fn prepare_config(cli: &Cli) -> Result<(Config, bool)> {
+    // 加载配置
+    let mut config = Config::load(cli.profile.as_deref())?;
+    apply_cli_overrides(&mut config, cli);
+    let enable_colors = config.ui.enable_colors;
+
     // 如果禁用代理，清除代理相关环境变量，防止 reqwest 自动读取
     if config.proxy.enabled == ProxyMode::Disable {
         for key in &["http_proxy", "https_proxy", "HTTP_PROXY", "HTTPS_PROXY"] {
@@ -72,28 +201,846 @@ async fn run() -> Result<()> {
         std::process::exit(1);
     }
 
-    // 获取要翻译的文本
-    let text = get_translation_text(&cli).await?;
-    if text.trim().is_empty() {
-        display_error("要翻译的文本不能为空", enable_colors);
+    Ok((config, enable_colors))
+}
+
+/// 命令行参数是优先级最高的配置来源
+fn apply_cli_overrides(config: &mut Config, cli: &Cli) {
+    if let Some(from) = &cli.from {
+        config.translation.default_from = from.clone();
+        config.origins.insert("translation.default_from".to_string(), Origin::Cli("--from"));
+    }
+    if let Some(to) = &cli.to {
+        config.translation.default_to = to.clone();
+        config.origins.insert("translation.default_to".to_string(), Origin::Cli("--to"));
+    }
+    if let Some(glossary) = &cli.glossary {
+        config.translation.glossary = Some(glossary.display().to_string());
+        config.origins.insert("translation.glossary".to_string(), Origin::Cli("--glossary"));
+    }
+    if cli.no_color {
+        config.ui.enable_colors = false;
+        config.origins.insert("ui.enable_colors".to_string(), Origin::Cli("--no-color"));
+    }
+
+    // 处理命令行代理覆盖
+    if cli.no_proxy {
+        config.proxy.enabled = ProxyMode::Disable;
+        config.origins.insert("proxy.enabled".to_string(), Origin::Cli("--no-proxy"));
+    } else if cli.force_proxy {
+        config.proxy.enabled = ProxyMode::Enable;
+        config.origins.insert("proxy.enabled".to_string(), Origin::Cli("--force-proxy"));
+    }
+}
+
+/// 校验源语言和目标语言是否被提供商支持，不支持时给出建议并退出
+fn check_languages(from: &str, to: &str, provider: Provider, enable_colors: bool) {
+    let result = validate_source(from, provider).and_then(|_| validate_target(to, provider));
+    if let Err(e) = result {
+        display_error(&e.to_string(), enable_colors);
         std::process::exit(1);
     }
+}
+
+/// 文本翻译使用的翻译器：翻译记忆 -> 缓存 -> 占位符保护 -> 术语表 -> 百度翻译
+type TextTranslator =
+    MemoryTranslator<CachedTranslator<ProtectedTranslator<GlossaryTranslator<BaiduTranslator>>>>;
+
+/// 创建用于文本翻译的翻译器
+///
+/// 优先复用翻译记忆中的译文，翻译前后保护占位符、标签、网址等内容并应用术语表
+fn new_translator(config: &Config) -> Result<TextTranslator> {
+    let glossary = match &config.translation.glossary {
+        Some(path) => Some(Glossary::load(Path::new(path))?),
+        None => None,
+    };
+    let memory = if config.memory.enabled {
+        Some(TranslationMemory::load(&config.memory_path()?)?)
+    } else {
+        None
+    };
+    let baidu = BaiduTranslator::new(config.baidu.clone(), &config.proxy);
+    let protected = ProtectedTranslator::new(GlossaryTranslator::new(baidu, glossary));
+    let cached = CachedTranslator::new(protected, config.cache.capacity);
+    Ok(MemoryTranslator::new(cached, memory, config.memory.fuzzy_threshold))
+}
+
+/// 显示译文的翻译记忆和术语表提示
+///
+/// 结果以 JSON 输出时只报告术语表违规
+fn display_notices(notices: &TranslationNotices, json: bool, enable_colors: bool) {
+    if !json {
+        if notices.from_memory {
+            display_info("译文来自翻译记忆", enable_colors);
+        } else {
+            display_fuzzy_matches(&notices.fuzzy_matches, enable_colors);
+        }
+    }
+    display_violations(&notices.violations, json, enable_colors);
+}
+
+/// 显示翻译记忆中相似的句段，供参考
+fn display_fuzzy_matches(matches: &[MemoryMatch], enable_colors: bool) {
+    if matches.is_empty() {
+        return;
+    }
+    display_info("相似的翻译记忆:", enable_colors);
+    for m in matches.iter().take(3) {
+        println!("  [{:.0}%] {}", m.score * 100.0, m.unit.source);
+        println!("        {}", m.unit.target);
+    }
+}
+
+/// 报告没有按术语表翻译的术语
+fn report_glossary_violations(translator: &TextTranslator, stdout_output: bool, enable_colors: bool) {
+    display_violations(&translator.inner().inner().inner().take_violations(), stdout_output, enable_colors);
+}
+
+/// 显示术语表违规
+///
+/// 结果输出到标准输出时警告写到标准错误，避免混入译文
+fn display_violations(violations: &[GlossaryViolation], stdout_output: bool, enable_colors: bool) {
+    for violation in violations {
+        let message = format!(
+            "术语 \"{}\" 应译为 \"{}\"，译文: {}",
+            violation.source_term, violation.target_term, violation.translation
+        );
+        if stdout_output {
+            eprintln!("⚠ {}", message);
+        } else {
+            display_warning(&message, enable_colors);
+        }
+    }
+}
+
+/// 处理file命令
+async fn handle_file_command(
+    cli: &Cli,
+    paths: &[PathBuf],
+    mode: &OutputMode,
+    format: FileFormat,
+) -> Result<()> {
+    let (config, enable_colors) = prepare_config(cli)?;
 
-    // 获取源语言和目标语言
     let from_lang = cli.get_from_language(&config.translation.default_from);
     let to_lang = cli.get_to_language(&config.translation.default_to);
+    check_languages(&from_lang, &to_lang, Provider::Baidu, enable_colors);
+
+    let translator = new_translator(&config)?;
+    let multiple_inputs = paths.len() > 1;
+
+    if let OutputMode::Path(dir) = mode {
+        if multiple_inputs {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("创建输出目录失败: {}", dir.display()))?;
+        }
+    }
+
+    for path in paths {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("读取文件失败: {}", path.display()))?;
+        let (content, encoding) = encoding::decode(&bytes)
+            .with_context(|| format!("解码文件失败: {}", path.display()))?;
+
+        let result = match format.resolve(path) {
+            FileFormat::Markdown => {
+                formats::markdown::translate_markdown(&content, &translator, &from_lang, &to_lang).await
+            }
+            FileFormat::Html => {
+                formats::html::translate_html(&content, &translator, &from_lang, &to_lang).await
+            }
+            _ => formats::text::translate_text(&content, &translator, &from_lang, &to_lang).await,
+        };
+        let translated = match result {
+            Ok(translated) => translated,
+            Err(e) => {
+                display_error(&format!("{}: {}", path.display(), e), enable_colors);
+                std::process::exit(1);
+            }
+        };
+
+        match mode.output_path(path, multiple_inputs)? {
+            Some(output_path) => {
+                let bytes = encoding::encode(&translated, encoding)?;
+                std::fs::write(&output_path, bytes)
+                    .with_context(|| format!("写入文件失败: {}", output_path.display()))?;
+                display_success(
+                    &format!("{} -> {} ({})", path.display(), output_path.display(), encoding.name()),
+                    enable_colors,
+                );
+            }
+            None => print!("{}", translated),
+        }
+    }
+    report_glossary_violations(&translator, matches!(mode, OutputMode::Stdout), enable_colors);
+
+    Ok(())
+}
+
+/// 处理code命令
+async fn handle_code_command(cli: &Cli, paths: &[PathBuf], dry_run: bool) -> Result<()> {
+    let (config, enable_colors) = prepare_config(cli)?;
+
+    let from_lang = cli.get_from_language(&config.translation.default_from);
+    let to_lang = cli.get_to_language(&config.translation.default_to);
+    check_languages(&from_lang, &to_lang, Provider::Baidu, enable_colors);
+
+    let mut files = Vec::new();
+    for path in paths {
+        if !path.is_dir() && CodeLanguage::from_path(path).is_none() {
+            display_error(&format!("不支持的源文件类型: {}", path.display()), enable_colors);
+            std::process::exit(1);
+        }
+        files.extend(formats::code::collect_source_files(path)?);
+    }
+
+    let translator = new_translator(&config)?;
+    let mut total = 0;
+    for path in &files {
+        let Some(language) = CodeLanguage::from_path(path) else { continue };
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("读取文件失败: {}", path.display()))?;
+        let (content, encoding) = encoding::decode(&bytes)
+            .with_context(|| format!("解码文件失败: {}", path.display()))?;
+
+        let (translated, count) =
+            match formats::code::translate_code(&content, language, &translator, &from_lang, &to_lang).await {
+                Ok(result) => result,
+                Err(e) => {
+                    display_error(&format!("{}: {}", path.display(), e), enable_colors);
+                    std::process::exit(1);
+                }
+            };
+        if translated == content {
+            continue;
+        }
+        total += count;
+
+        if dry_run {
+            // 相对路径使用 git 风格的 a/ b/ 前缀，便于 git apply
+            let name = path.display().to_string();
+            let (old_name, new_name) = if path.is_relative() {
+                (format!("a/{}", name), format!("b/{}", name))
+            } else {
+                (name.clone(), name)
+            };
+            print!("{}", diff::unified_diff(&old_name, &new_name, &content, &translated));
+        } else {
+            std::fs::write(path, encoding::encode(&translated, encoding)?)
+                .with_context(|| format!("写入文件失败: {}", path.display()))?;
+            display_success(&format!("{}: 翻译了 {} 行注释", path.display(), count), enable_colors);
+        }
+    }
+
+    report_glossary_violations(&translator, dry_run, enable_colors);
+    if !dry_run {
+        display_info(&format!("共处理 {} 个文件，翻译 {} 行注释", files.len(), total), enable_colors);
+    }
+
+    Ok(())
+}
+
+/// 处理subs命令
+async fn handle_subs_command(
+    cli: &Cli,
+    path: &Path,
+    output: &Option<PathBuf>,
+    bilingual: bool,
+) -> Result<()> {
+    let (config, enable_colors) = prepare_config(cli)?;
+
+    let from_lang = cli.get_from_language(&config.translation.default_from);
+    let to_lang = cli.get_to_language(&config.translation.default_to);
+    check_languages(&from_lang, &to_lang, Provider::Baidu, enable_colors);
+
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("读取文件失败: {}", path.display()))?;
+    let (content, encoding) = encoding::decode(&bytes)
+        .with_context(|| format!("解码文件失败: {}", path.display()))?;
+    let format = SubtitleFormat::detect(&content);
+
+    let translator = new_translator(&config)?;
+    let translated = match translate_subtitles(&content, &translator, &from_lang, &to_lang, bilingual).await {
+        Ok(translated) => translated,
+        Err(e) => {
+            display_error(&e.to_string(), enable_colors);
+            std::process::exit(1);
+        }
+    };
+    report_glossary_violations(&translator, output.is_none(), enable_colors);
+
+    match output {
+        Some(output_path) => {
+            std::fs::write(output_path, encoding::encode(&translated, encoding)?)
+                .with_context(|| format!("写入文件失败: {}", output_path.display()))?;
+            display_success(
+                &format!("{} 字幕已翻译: {} -> {}", format.name(), path.display(), output_path.display()),
+                enable_colors,
+            );
+        }
+        None => print!("{}", translated),
+    }
+
+    Ok(())
+}
+
+/// 处理po translate命令
+async fn handle_po_translate_command(
+    cli: &Cli,
+    path: &Path,
+    output: &Option<PathBuf>,
+    overwrite: bool,
+) -> Result<()> {
+    let (config, enable_colors) = prepare_config(cli)?;
+
+    let from_lang = cli.get_from_language(&config.translation.default_from);
+    let to_lang = cli.get_to_language(&config.translation.default_to);
+    check_languages(&from_lang, &to_lang, Provider::Baidu, enable_colors);
+
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("读取文件失败: {}", path.display()))?;
+    let (content, encoding) = encoding::decode(&bytes)
+        .with_context(|| format!("解码文件失败: {}", path.display()))?;
+
+    let translator = new_translator(&config)?;
+    let (translated, stats) = match translate_po(&content, &translator, &from_lang, &to_lang, overwrite).await {
+        Ok(result) => result,
+        Err(e) => {
+            display_error(&e.to_string(), enable_colors);
+            std::process::exit(1);
+        }
+    };
+    report_glossary_violations(&translator, false, enable_colors);
+
+    let output_path = output.as_deref().unwrap_or(path);
+    std::fs::write(output_path, encoding::encode(&translated, encoding)?)
+        .with_context(|| format!("写入文件失败: {}", output_path.display()))?;
+
+    display_success(
+        &format!(
+            "已翻译 {} 条，跳过已有译文 {} 条 -> {}",
+            stats.translated,
+            stats.skipped,
+            output_path.display()
+        ),
+        enable_colors,
+    );
+    if stats.failed > 0 {
+        display_warning(&format!("{} 条译文中的格式占位符被破坏，保持未翻译", stats.failed), enable_colors);
+    }
+    if stats.translated > 0 {
+        display_info("机器翻译的条目已标记为 fuzzy，请人工校对", enable_colors);
+    }
+
+    Ok(())
+}
+
+/// 处理i18n translate命令
+async fn handle_i18n_translate_command(cli: &Cli, path: &Path, output: &Option<PathBuf>) -> Result<()> {
+    let (config, enable_colors) = prepare_config(cli)?;
+
+    let Some(format) = I18nFormat::from_path(path) else {
+        display_error("仅支持 .json、.yml 和 .yaml 资源文件", enable_colors);
+        std::process::exit(1);
+    };
+
+    let from_lang = cli.get_from_language(&config.translation.default_from);
+    let to_lang = cli.get_to_language(&config.translation.default_to);
+    check_languages(&from_lang, &to_lang, Provider::Baidu, enable_colors);
+
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("读取文件失败: {}", path.display()))?;
+    let (content, encoding) = encoding::decode(&bytes)
+        .with_context(|| format!("解码文件失败: {}", path.display()))?;
+
+    let translator = new_translator(&config)?;
+    let outcome = match i18n::translate_file(&content, format, &translator, &from_lang, &to_lang).await {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            display_error(&e.to_string(), enable_colors);
+            std::process::exit(1);
+        }
+    };
+
+    for failure in &outcome.failures {
+        let message = format!("{}: {}，已保留原文", failure.key_path, failure.reason);
+        if output.is_some() {
+            display_warning(&message, enable_colors);
+        } else {
+            // 译文输出到标准输出时，警告写到标准错误以免混入结果
+            eprintln!("⚠ {}", message);
+        }
+    }
+    report_glossary_violations(&translator, output.is_none(), enable_colors);
+
+    match output {
+        Some(output_path) => {
+            std::fs::write(output_path, encoding::encode(&outcome.content, encoding)?)
+                .with_context(|| format!("写入文件失败: {}", output_path.display()))?;
+            display_success(
+                &format!("已翻译 {} 个字符串 -> {}", outcome.translated, output_path.display()),
+                enable_colors,
+            );
+        }
+        None => print!("{}", outcome.content),
+    }
+
+    Ok(())
+}
+
+/// 处理i18n sync命令
+async fn handle_i18n_sync_command(cli: &Cli, source: &str, targets: &[String], dir: &Path) -> Result<()> {
+    let (config, enable_colors) = prepare_config(cli)?;
+
+    for target in targets {
+        check_languages(source, target, Provider::Baidu, enable_colors);
+    }
+
+    let translator = new_translator(&config)?;
+    let reports = match i18n::sync::sync_locales(dir, source, targets, &translator).await {
+        Ok(reports) => reports,
+        Err(e) => {
+            display_error(&e.to_string(), enable_colors);
+            std::process::exit(1);
+        }
+    };
+
+    for report in reports {
+        display_success(
+            &format!(
+                "{} ({}): 新增 {}，更新 {}，删除 {}，未变化 {}",
+                report.target,
+                report.path.display(),
+                report.added.len(),
+                report.changed.len(),
+                report.removed.len(),
+                report.unchanged
+            ),
+            enable_colors,
+        );
+        for key in &report.added {
+            println!("  + {}", key);
+        }
+        for key in &report.changed {
+            println!("  ~ {}", key);
+        }
+        for key in &report.removed {
+            println!("  - {}", key);
+        }
+        for failure in &report.failures {
+            display_warning(&format!("{}: {}，未更新", failure.key_path, failure.reason), enable_colors);
+        }
+    }
+    report_glossary_violations(&translator, false, enable_colors);
+
+    Ok(())
+}
+
+/// 处理xliff translate命令
+async fn handle_xliff_translate_command(cli: &Cli, path: &Path, output: &Option<PathBuf>) -> Result<()> {
+    let (config, enable_colors) = prepare_config(cli)?;
+
+    let from_lang = cli.get_from_language(&config.translation.default_from);
+    let to_lang = cli.get_to_language(&config.translation.default_to);
+    check_languages(&from_lang, &to_lang, Provider::Baidu, enable_colors);
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("读取文件失败: {}", path.display()))?;
+
+    let translator = new_translator(&config)?;
+    let (translated, stats) = match translate_xliff(&content, &translator, &from_lang, &to_lang).await {
+        Ok(result) => result,
+        Err(e) => {
+            display_error(&e.to_string(), enable_colors);
+            std::process::exit(1);
+        }
+    };
+
+    for (unit_id, reason) in &stats.failures {
+        display_warning(&format!("单元 {}: {}，未写入译文", unit_id, reason), enable_colors);
+    }
+    report_glossary_violations(&translator, false, enable_colors);
+
+    let output_path = output.as_deref().unwrap_or(path);
+    std::fs::write(output_path, translated)
+        .with_context(|| format!("写入文件失败: {}", output_path.display()))?;
+
+    display_success(
+        &format!(
+            "XLIFF {}: 已翻译 {} 个单元，跳过已有译文 {} 个 -> {}",
+            detect_version(&content)?.name(),
+            stats.translated,
+            stats.skipped,
+            output_path.display()
+        ),
+        enable_colors,
+    );
+
+    Ok(())
+}
+
+/// 处理mobile translate命令
+async fn handle_mobile_translate_command(cli: &Cli, path: &Path, output: &Option<PathBuf>) -> Result<()> {
+    let (config, enable_colors) = prepare_config(cli)?;
+
+    let Some(format) = MobileFormat::from_path(path) else {
+        display_error("仅支持 strings.xml、.strings 和 .xcstrings 文件", enable_colors);
+        std::process::exit(1);
+    };
+
+    let from_lang = cli.get_from_language(&config.translation.default_from);
+    let to_lang = cli.get_to_language(&config.translation.default_to);
+    check_languages(&from_lang, &to_lang, Provider::Baidu, enable_colors);
+
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("读取文件失败: {}", path.display()))?;
+    let (content, encoding) = encoding::decode(&bytes)
+        .with_context(|| format!("解码文件失败: {}", path.display()))?;
+
+    let translator = new_translator(&config)?;
+    let (translated, stats) =
+        match formats::mobile::translate(&content, format, &translator, &from_lang, &to_lang).await {
+            Ok(result) => result,
+            Err(e) => {
+                display_error(&e.to_string(), enable_colors);
+                std::process::exit(1);
+            }
+        };
+
+    // 字符串目录包含所有语言，默认写回原文件；其他格式每种语言一个文件
+    let output_path = match (output, format) {
+        (Some(path), _) => Some(path.as_path()),
+        (None, MobileFormat::XcStrings) => Some(path),
+        (None, _) => None,
+    };
+
+    for (name, reason) in &stats.failures {
+        let message = format!("{}: {}，已保留原文", name, reason);
+        if output_path.is_some() {
+            display_warning(&message, enable_colors);
+        } else {
+            eprintln!("⚠ {}", message);
+        }
+    }
+    report_glossary_violations(&translator, output_path.is_none(), enable_colors);
+
+    match output_path {
+        Some(output_path) => {
+            std::fs::write(output_path, encoding::encode(&translated, encoding)?)
+                .with_context(|| format!("写入文件失败: {}", output_path.display()))?;
+            display_success(
+                &format!(
+                    "{}: 已翻译 {} 条，跳过 {} 条 -> {}",
+                    format.name(),
+                    stats.translated,
+                    stats.skipped,
+                    output_path.display()
+                ),
+                enable_colors,
+            );
+        }
+        None => print!("{}", translated),
+    }
+
+    Ok(())
+}
+
+/// 处理serve命令
+async fn handle_serve_command(
+    cli: &Cli,
+    listen: &Option<String>,
+    token: &Option<String>,
+    compat: server::Compat,
+) -> Result<()> {
+    let (config, _) = prepare_config(cli)?;
+    let listen = listen.clone().unwrap_or_else(|| config.server.listen.clone());
+    let state = server::ServerState {
+        translator: new_translator(&config)?,
+        token: token.clone().or_else(|| config.server.token.clone()),
+        default_from: cli.get_from_language(&config.translation.default_from),
+        default_to: cli.get_to_language(&config.translation.default_to),
+    };
+    server::serve(&listen, compat, state).await
+}
+
+/// 处理rpc和mcp命令
+///
+/// 整个会话共用一个翻译器和 HTTP 连接，提示信息只写到标准错误
+async fn handle_rpc_command(cli: &Cli, mcp: bool) -> Result<()> {
+    let (config, _) = prepare_config(cli)?;
+    let handler = rpc::methods::TranslatorHandler::new(
+        new_translator(&config)?,
+        cli.get_from_language(&config.translation.default_from),
+        cli.get_to_language(&config.translation.default_to),
+    );
+    if mcp {
+        rpc::serve_stdio(Arc::new(rpc::mcp::McpHandler::new(handler)), rpc::Framing::Lines).await
+    } else {
+        rpc::serve_stdio(Arc::new(handler), rpc::Framing::Lines).await
+    }
+}
+
+/// 处理lsp命令
+///
+/// 注释可能使用任何语言，未指定 --from 时自动检测源语言
+async fn handle_lsp_command(cli: &Cli) -> Result<()> {
+    let (config, _) = prepare_config(cli)?;
+    let handler = rpc::lsp::LspHandler::new(
+        new_translator(&config)?,
+        cli.get_from_language("auto"),
+        cli.get_to_language(&config.translation.default_to),
+    );
+    rpc::serve_stdio(Arc::new(handler), rpc::Framing::Headers).await
+}
+
+/// 通过守护进程翻译
+///
+/// 守护进程没有运行，或者它启动时的配置与当前不同（例如在有 .fanyi.toml 的项目中）时返回 None
+#[cfg(unix)]
+async fn translate_via_daemon(
+    config: &Config,
+    text: &str,
+    from: &str,
+    to: &str,
+) -> Option<Result<(TranslationResult, TranslationNotices)>> {
+    let mut client = rpc::daemon::DaemonClient::connect(config.profile.as_deref()).await?;
+    let status = client.call("status", serde_json::Value::Null).await.ok()?;
+    if status["config"].as_str() != Some(config.fingerprint().ok()?.as_str()) {
+        return None;
+    }
+    Some(client.translate(text, from, to).await)
+}
+
+#[cfg(not(unix))]
+async fn translate_via_daemon(
+    _config: &Config,
+    _text: &str,
+    _from: &str,
+    _to: &str,
+) -> Option<Result<(TranslationResult, TranslationNotices)>> {
+    None
+}
+
+/// 处理daemon命令
+#[cfg(unix)]
+async fn handle_daemon_command(cli: &Cli, action: &DaemonCommands) -> Result<()> {
+    use rpc::daemon::{self, DaemonClient};
+
+    let enable_colors = Config::load(cli.profile.as_deref()).map(|c| c.ui.enable_colors).unwrap_or(true) && !cli.no_color;
+    let profile = Config::selected_profile(cli.profile.as_deref());
+    let profile = profile.as_deref();
+    match action {
+        DaemonCommands::Run => {
+            let (config, _) = prepare_config(cli)?;
+            let handler = rpc::methods::TranslatorHandler::new(
+                new_translator(&config)?,
+                cli.get_from_language(&config.translation.default_from),
+                cli.get_to_language(&config.translation.default_to),
+            );
+            daemon::run(handler, profile, config.fingerprint()?).await
+        }
+        DaemonCommands::Start => {
+            if let Some(mut client) = DaemonClient::connect(profile).await {
+                let status = client.call("status", serde_json::Value::Null).await?;
+                display_info(&format!("守护进程已在运行 (pid {})", status["pid"]), enable_colors);
+                return Ok(());
+            }
+            // 提前检查配置，避免后台进程启动后才因为缺少密钥退出
+            prepare_config(cli)?;
+            spawn_daemon(cli, profile)?;
+
+            for _ in 0..50 {
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                if DaemonClient::connect(profile).await.is_some() {
+                    display_success(&format!("守护进程已启动: {}", daemon::socket_path(profile)?.display()), enable_colors);
+                    return Ok(());
+                }
+            }
+            anyhow::bail!("守护进程启动失败，请查看日志: {}", daemon::log_path()?.display())
+        }
+        DaemonCommands::Stop => match DaemonClient::connect(profile).await {
+            Some(mut client) => {
+                client.call("shutdown", serde_json::Value::Null).await?;
+                display_success("守护进程已停止", enable_colors);
+                Ok(())
+            }
+            None => {
+                display_info("守护进程没有运行", enable_colors);
+                Ok(())
+            }
+        },
+        DaemonCommands::Status => match DaemonClient::connect(profile).await {
+            Some(mut client) => {
+                let status = client.call("status", serde_json::Value::Null).await?;
+                if cli.json {
+                    println!("{}", serde_json::to_string_pretty(&status)?);
+                } else {
+                    display_success("守护进程正在运行", enable_colors);
+                    println!("  套接字: {}", daemon::socket_path(profile)?.display());
+                    println!("  进程号: {}", status["pid"]);
+                    println!("  运行时间: {} 秒", status["uptime"]);
+                    println!("  已处理请求: {}", status["requests"]);
+                }
+                Ok(())
+            }
+            None => {
+                display_info("守护进程没有运行", enable_colors);
+                Ok(())
+            }
+        },
+    }
+}
+
+#[cfg(not(unix))]
+async fn handle_daemon_command(_cli: &Cli, _action: &DaemonCommands) -> Result<()> {
+    anyhow::bail!("守护进程只支持 Unix 系统")
+}
+
+/// 以独立进程组在后台运行 `fanyi daemon run`，日志写到运行时目录下的 daemon.log
+#[cfg(unix)]
+fn spawn_daemon(cli: &Cli, profile: Option<&str>) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+    use std::process::{Command, Stdio};
+
+    let log = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(rpc::daemon::log_path()?)
+        .context("打开守护进程日志失败")?;
+    let mut command = Command::new(std::env::current_exe().context("无法获取程序路径")?);
+    if cli.no_proxy {
+        command.arg("--no-proxy");
+    } else if cli.force_proxy {
+        command.arg("--force-proxy");
+    }
+    if let Some(glossary) = &cli.glossary {
+        command.arg("--glossary").arg(glossary);
+    }
+    if let Some(profile) = profile {
+        command.args(["--profile", profile]);
+    }
+    command
+        .args(["daemon", "run"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(log)
+        .process_group(0)
+        .spawn()
+        .context("启动守护进程失败")?;
+    Ok(())
+}
+
+/// 处理tm命令
+fn handle_tm_command(cli: &Cli, action: &TmCommands) -> Result<()> {
+    let config = Config::load(cli.profile.as_deref())?;
+    let enable_colors = config.ui.enable_colors && !cli.no_color;
+    let mut memory = TranslationMemory::load(&config.memory_path()?)?;
+
+    match action {
+        TmCommands::Add { source, target } => {
+            let from = cli.get_from_language(&config.translation.default_from);
+            let to = cli.get_to_language(&config.translation.default_to);
+            if from == "auto" {
+                display_error("添加翻译记忆时必须用 --from 指定源语言", enable_colors);
+                std::process::exit(1);
+            }
+            for lang in [&from, &to] {
+                if registry::resolve(lang).is_none() {
+                    display_error(&format!("无法识别的语言: {}", lang), enable_colors);
+                    std::process::exit(1);
+                }
+            }
+
+            let added = memory.add(MemoryUnit {
+                source_lang: from,
+                target_lang: to,
+                source: source.trim().to_string(),
+                target: target.trim().to_string(),
+                created: None,
+            });
+            memory.save()?;
+            display_success(if added { "已添加到翻译记忆" } else { "已更新翻译记忆中的译文" }, enable_colors);
+        }
+        TmCommands::Search { text, threshold } => {
+            let from = cli.get_from_language("auto");
+            let to = cli.get_to_language(&config.translation.default_to);
+            let threshold = threshold.unwrap_or(config.memory.fuzzy_threshold);
+            let matches = memory.search(text, &from, &to, threshold);
+
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&matches)?);
+            } else if matches.is_empty() {
+                display_info("没有找到相似的翻译记忆", enable_colors);
+            } else {
+                for m in &matches {
+                    println!("[{:.0}%] {} -> {}", m.score * 100.0, m.unit.source_lang, m.unit.target_lang);
+                    println!("  {}", m.unit.source);
+                    println!("  {}", m.unit.target);
+                }
+            }
+        }
+        TmCommands::Import { path } => {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("读取文件失败: {}", path.display()))?;
+            let units = tmx::parse(&content)
+                .map_err(|e| anyhow::anyhow!("解析 TMX 文件失败: {}: {}", path.display(), e))?;
+
+            let total = units.len();
+            let added = units.into_iter().filter(|unit| memory.add(unit.clone())).count();
+            memory.save()?;
+            display_success(
+                &format!("已导入 {} 条句段 (新增 {} 条，更新 {} 条)", total, added, total - added),
+                enable_colors,
+            );
+        }
+        TmCommands::Export { path } => {
+            std::fs::write(path, tmx::render(memory.units()))
+                .with_context(|| format!("写入文件失败: {}", path.display()))?;
+            display_success(
+                &format!("已导出 {} 条句段 -> {}", memory.units().len(), path.display()),
+                enable_colors,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// 处理image命令
+async fn handle_image_command(cli: &Cli, path: &Path) -> Result<()> {
+    let (config, enable_colors) = prepare_config(cli)?;
+
+    let Some(mime_type) = image_mime_type(path) else {
+        display_error("仅支持 png 和 jpg 格式的图片", enable_colors);
+        std::process::exit(1);
+    };
+
+    let image = std::fs::read(path)
+        .with_context(|| format!("读取图片失败: {}", path.display()))?;
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "image".to_string());
+
+    let from_lang = cli.get_from_language(&config.translation.default_from);
+    let to_lang = cli.get_to_language(&config.translation.default_to);
+    check_languages(&from_lang, &to_lang, Provider::Baidu, enable_colors);
 
-    // 创建翻译器（传递代理配置）
     let translator = BaiduTranslator::new(config.baidu.clone(), &config.proxy);
+    if !cli.json {
+        display_info(&format!("正在通过{}识别并翻译图片...", translator.name()), enable_colors);
+    }
 
-    // 执行翻译
-    match translator.translate(&text, &from_lang, &to_lang).await {
+    match translator.translate_image(image, &file_name, mime_type, &from_lang, &to_lang).await {
         Ok(result) => {
-            display_translation(&result, enable_colors, config.ui.show_language_detection);
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else {
+                display_image_translation(&result, enable_colors);
+            }
         }
         Err(e) => {
             display_error(&e.to_string(), enable_colors);
-            display_info("如果是网络错误，请检查代理设置或使用 'fanyi proxy-status' 查看代理状态", enable_colors);
             std::process::exit(1);
         }
     }
@@ -122,7 +1069,9 @@ async fn get_translation_text(cli: &Cli) -> Result<String> {
 }
 
 /// 处理配置命令
+#[allow(clippy::too_many_arguments)]
 fn handle_config_command(
+    cli: &Cli,
     app_id: &Option<String>,
     secret_key: &Option<String>,
     default_from: &Option<String>,
@@ -131,13 +1080,32 @@ fn handle_config_command(
     http_proxy: &Option<String>,
     https_proxy: &Option<String>,
     show: bool,
+    origin: bool,
 ) -> Result<()> {
-    let mut config = Config::load().unwrap_or_default();
-    let enable_colors = config.ui.enable_colors;
+    let mut config = if show {
+        let mut config = Config::load(cli.profile.as_deref())?;
+        apply_cli_overrides(&mut config, cli);
+        config
+    } else {
+        // 修改只写入用户配置文件；指定的配置档案不存在时新建
+        let base = Config::load_base().unwrap_or_default();
+        match Config::selected_profile(cli.profile.as_deref()) {
+            Some(name) => base.into_profile(&name)?,
+            None => base,
+        }
+    };
+    let enable_colors = config.ui.enable_colors && !cli.no_color;
+
+    if origin {
+        return display_config_origins(&config, enable_colors);
+    }
 
     if show {
         // 显示当前配置
-        display_info("当前配置:", enable_colors);
+        match &config.profile {
+            Some(name) => display_info(&format!("当前配置 (配置档案: {}):", name), enable_colors),
+            None => display_info("当前配置:", enable_colors),
+        }
         println!("  百度翻译APP ID: {}", 
             if config.baidu.app_id.is_empty() { 
                 "未设置".to_string() 
@@ -154,6 +1122,12 @@ fn handle_config_command(
         );
         println!("  默认源语言: {}", config.translation.default_from);
         println!("  默认目标语言: {}", config.translation.default_to);
+        println!("  术语表: {}", config.translation.glossary.as_deref().unwrap_or("未设置"));
+        println!("  百度术语干预: {}", if config.baidu.need_intervention { "启用" } else { "禁用" });
+        println!("  翻译记忆: {}", if config.memory.enabled { "启用" } else { "禁用" });
+        if let Ok(path) = config.memory_path() {
+            println!("  翻译记忆文件: {}", path.display());
+        }
         println!("  颜色输出: {}", if config.ui.enable_colors { "启用" } else { "禁用" });
         
         // 显示代理配置
@@ -164,6 +1138,10 @@ fn handle_config_command(
         println!("  HTTPS代理: {}", 
             config.proxy.https_proxy.as_deref().unwrap_or("未设置")
         );
+        if !config.profiles.is_empty() {
+            let names: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+            println!("  配置档案: {}", names.join(", "));
+        }
         
         return Ok(());
     }
@@ -213,7 +1191,10 @@ fn handle_config_command(
 
     if updated {
         config.save()?;
-        display_success("配置已更新", enable_colors);
+        match &config.profile {
+            Some(name) => display_success(&format!("配置档案 {} 已更新", name), enable_colors),
+            None => display_success("配置已更新", enable_colors),
+        }
         
         // 提示配置文件位置
         if let Ok(config_path) = Config::config_file_path() {
@@ -227,25 +1208,77 @@ fn handle_config_command(
     Ok(())
 }
 
+/// 列出每个生效的配置项及其来源
+fn display_config_origins(config: &Config, enable_colors: bool) -> Result<()> {
+    match &config.profile {
+        Some(name) => display_info(&format!("生效的配置 (配置档案: {}):", name), enable_colors),
+        None => display_info("生效的配置:", enable_colors),
+    }
+    let entries = config.entries()?;
+    let rendered: Vec<(String, String, Origin)> = entries
+        .into_iter()
+        .map(|(key, value, origin)| {
+            let secret = ["baidu.app_id", "baidu.secret_key", "server.token"].contains(&key.as_str());
+            let value = match value {
+                toml::Value::String(s) if secret && !s.is_empty() => format!("\"{}\"", mask_string(&s, 4)),
+                value => value.to_string(),
+            };
+            (key, value, origin)
+        })
+        .collect();
+
+    let width = rendered.iter().map(|(key, value, _)| key.len() + value.chars().count()).max().unwrap_or(0);
+    for (key, value, origin) in rendered {
+        let padding = width - key.len() - value.chars().count();
+        println!("  {} = {}{}  # {}", key, value, " ".repeat(padding), origin);
+    }
+    Ok(())
+}
+
 /// 处理languages命令
-fn handle_languages_command() -> Result<()> {
-    let translator = BaiduTranslator::new(config::BaiduConfig::default(), &ProxyConfig::default());
+fn handle_languages_command(cli: &Cli, provider: &Option<String>) -> Result<()> {
+    let config = Config::load(cli.profile.as_deref()).unwrap_or_default();
+    let provider_name = provider.as_deref().unwrap_or(&config.translation.provider);
+
+    let Some(provider) = Provider::parse(provider_name) else {
+        let known: Vec<&str> = Provider::ALL.iter().map(|p| p.id()).collect();
+        display_error(
+            &format!("未知的翻译服务: {} (可选: {})", provider_name, known.join(", ")),
+            config.ui.enable_colors,
+        );
+        std::process::exit(1);
+    };
+
+    let translator: Box<dyn Translator> = match provider {
+        Provider::Baidu => Box::new(BaiduTranslator::new(
+            config::BaiduConfig::default(),
+            &ProxyConfig::default(),
+        )),
+    };
+    let provider = translator.provider();
     let languages = translator.supported_languages();
-    
-    println!("支持的语言列表:");
-    println!("{:<8} {}", "代码", "语言");
-    println!("{}", "-".repeat(20));
-    
-    for (code, name) in languages {
-        println!("{:<8} {}", code, name);
+
+    println!("{} 支持的语言列表 (共 {} 种):", provider.display_name(), languages.len());
+    println!("{:<8} {:<10} {:<14} English", "代码", "BCP-47", "语言");
+    println!("{}", "-".repeat(56));
+    println!("{:<8} {:<10} {:<14} Auto detect", "auto", "auto", "自动检测");
+
+    for lang in languages {
+        println!(
+            "{:<8} {:<10} {:<14} {}",
+            lang.code_for(provider).unwrap_or_default(),
+            lang.bcp47,
+            lang.name_zh,
+            lang.name_en
+        );
     }
 
     Ok(())
 }
 
 /// 处理代理状态命令
-fn handle_proxy_status_command() -> Result<()> {
-    let config = Config::load().unwrap_or_default();
+fn handle_proxy_status_command(cli: &Cli) -> Result<()> {
+    let config = Config::load(cli.profile.as_deref()).unwrap_or_default();
     
     println!("=== 代理状态 ===");
     println!("配置的代理模式: {:?}", config.proxy.enabled);
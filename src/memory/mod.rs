@@ -0,0 +1,157 @@
+pub mod tmx;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::language::registry;
+use crate::utils::edit_distance;
+
+/// 翻译记忆中的一条句段对
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryUnit {
+    /// 源语言 (BCP-47)
+    pub source_lang: String,
+    /// 目标语言 (BCP-47)
+    pub target_lang: String,
+    pub source: String,
+    pub target: String,
+    /// 创建时间，TMX 格式 (YYYYMMDDThhmmssZ)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+}
+
+/// 模糊匹配结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryMatch {
+    #[serde(flatten)]
+    pub unit: MemoryUnit,
+    /// 相似度，0.0 ~ 1.0，1.0 为完全匹配
+    pub score: f64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MemoryFile {
+    units: Vec<MemoryUnit>,
+}
+
+/// 翻译记忆库：保存经过审校的原文和译文
+pub struct TranslationMemory {
+    path: PathBuf,
+    units: Vec<MemoryUnit>,
+}
+
+/// 比较前统一空白，首尾空白和连续空白不影响匹配
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl TranslationMemory {
+    /// 默认的记忆库位置
+    pub fn default_path() -> Result<PathBuf> {
+        let dir = dirs::data_dir().context("无法获取数据目录")?.join("fanyi");
+        Ok(dir.join("memory.json"))
+    }
+
+    /// 加载记忆库，文件不存在时为空
+    pub fn load(path: &Path) -> Result<Self> {
+        let units = if path.exists() {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("读取翻译记忆失败: {}", path.display()))?;
+            serde_json::from_str::<MemoryFile>(&content)
+                .with_context(|| format!("解析翻译记忆失败: {}", path.display()))?
+                .units
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            units,
+        })
+    }
+
+    /// 保存记忆库
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).context("创建翻译记忆目录失败")?;
+        }
+        let file = MemoryFile {
+            units: self.units.clone(),
+        };
+        fs::write(&self.path, serde_json::to_string_pretty(&file)?)
+            .with_context(|| format!("写入翻译记忆失败: {}", self.path.display()))
+    }
+
+    pub fn units(&self) -> &[MemoryUnit] {
+        &self.units
+    }
+
+    /// 添加句段对，已有相同原文时更新译文；返回是否为新增
+    pub fn add(&mut self, mut unit: MemoryUnit) -> bool {
+        unit.source_lang = registry::primary_tag(&unit.source_lang);
+        unit.target_lang = registry::primary_tag(&unit.target_lang);
+        unit.created.get_or_insert_with(tmx::timestamp_now);
+
+        let key = normalize(&unit.source);
+        let existing = self.units.iter_mut().find(|u| {
+            u.source_lang == unit.source_lang && u.target_lang == unit.target_lang && normalize(&u.source) == key
+        });
+        match existing {
+            Some(existing) => {
+                *existing = unit;
+                false
+            }
+            None => {
+                self.units.push(unit);
+                true
+            }
+        }
+    }
+
+    /// 查找完全匹配的译文，源语言为 auto 时匹配任意源语言
+    pub fn exact(&self, text: &str, from: &str, to: &str) -> Option<&MemoryUnit> {
+        let key = normalize(text);
+        if key.is_empty() {
+            return None;
+        }
+        self.candidates(from, to).find(|u| normalize(&u.source) == key)
+    }
+
+    /// 按编辑距离查找相似度不低于阈值的句段，按相似度从高到低排序
+    pub fn search(&self, text: &str, from: &str, to: &str, threshold: f64) -> Vec<MemoryMatch> {
+        let key = normalize(text);
+        let key_len = key.chars().count();
+        if key_len == 0 {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<MemoryMatch> = self
+            .candidates(from, to)
+            .filter_map(|unit| {
+                let source = normalize(&unit.source);
+                let longest = key_len.max(source.chars().count());
+                // 长度相差过大时不可能达到阈值，跳过编辑距离计算
+                let length_gap = key_len.abs_diff(source.chars().count());
+                if (1.0 - length_gap as f64 / longest as f64) < threshold {
+                    return None;
+                }
+                let score = 1.0 - edit_distance(&key, &source) as f64 / longest as f64;
+                (score >= threshold).then(|| MemoryMatch {
+                    unit: unit.clone(),
+                    score,
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches
+    }
+
+    fn candidates<'a>(&'a self, from: &str, to: &str) -> impl Iterator<Item = &'a MemoryUnit> {
+        let to = registry::primary_tag(to);
+        let from = (from != "auto").then(|| registry::primary_tag(from));
+        self.units.iter().filter(move |u| {
+            u.target_lang == to && from.as_ref().is_none_or(|f| *f == u.source_lang)
+        })
+    }
+}
@@ -0,0 +1,242 @@
+use anyhow::{bail, Result};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::MemoryUnit;
+use crate::formats::xml::{self, Token};
+
+/// 正在解析的 <tu>
+struct PendingUnit {
+    srclang: Option<String>,
+    created: Option<String>,
+    /// 各个 <tuv> 的 (语言, 文本)
+    variants: Vec<(String, String)>,
+}
+
+/// 解析 TMX 1.4 文件
+///
+/// 每个 <tu> 中以 srclang 对应的 <tuv> 为原文（未指定时取第一个），与其余每个
+/// <tuv> 组成一条句段对；<bpt>、<ept>、<ph>、<it> 中的原始格式代码不计入文本
+pub fn parse(content: &str) -> Result<Vec<MemoryUnit>> {
+    let tokens = xml::tokenize(content);
+    if !tokens.iter().any(|t| matches!(t, Token::Start { .. }) && t.local_name() == Some("tmx")) {
+        bail!("不是有效的 TMX 文件");
+    }
+
+    let mut units = Vec::new();
+    let mut header_srclang: Option<String> = None;
+    let mut tu: Option<PendingUnit> = None;
+    let mut lang: Option<String> = None;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        match (token, token.local_name()) {
+            (Token::Start { .. }, Some("header")) => {
+                header_srclang = token.attr("srclang").filter(|l| *l != "*all*").map(str::to_string);
+            }
+            (Token::Start { self_closing: false, .. }, Some("tu")) => {
+                tu = Some(PendingUnit {
+                    srclang: token.attr("srclang").map(str::to_string),
+                    created: token.attr("creationdate").map(str::to_string),
+                    variants: Vec::new(),
+                });
+            }
+            (Token::Start { .. }, Some("tuv")) => {
+                lang = token.attr("xml:lang").or_else(|| token.attr("lang")).map(str::to_string);
+            }
+            (Token::Start { self_closing: false, .. }, Some("seg")) => {
+                let (text, end) = read_segment(content, &tokens, i);
+                if let (Some(tu), Some(lang)) = (tu.as_mut(), &lang) {
+                    tu.variants.push((lang.clone(), text));
+                }
+                i = end;
+            }
+            (Token::End { .. }, Some("tu")) => {
+                if let Some(PendingUnit { srclang, created, variants }) = tu.take() {
+                    let srclang = srclang.or_else(|| header_srclang.clone());
+                    let source_index = srclang
+                        .and_then(|s| variants.iter().position(|(l, _)| l.eq_ignore_ascii_case(&s)))
+                        .unwrap_or(0);
+                    if let Some((source_lang, source)) = variants.get(source_index) {
+                        for (index, (target_lang, target)) in variants.iter().enumerate() {
+                            if index != source_index && !source.trim().is_empty() && !target.trim().is_empty() {
+                                units.push(MemoryUnit {
+                                    source_lang: source_lang.clone(),
+                                    target_lang: target_lang.clone(),
+                                    source: source.clone(),
+                                    target: target.clone(),
+                                    created: created.clone(),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    Ok(units)
+}
+
+/// 读取 <seg> 的文本，返回文本和 </seg> 的下标
+fn read_segment(content: &str, tokens: &[Token], start: usize) -> (String, usize) {
+    let mut text = String::new();
+    // 处于原始格式代码元素中的层数
+    let mut code_depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(start + 1) {
+        match (token, token.local_name()) {
+            (Token::End { .. }, Some("seg")) => return (text, i),
+            (Token::Start { self_closing: false, .. }, Some("bpt" | "ept" | "ph" | "it")) => code_depth += 1,
+            (Token::End { .. }, Some("bpt" | "ept" | "ph" | "it")) => code_depth -= 1,
+            (Token::Text { range }, _) if code_depth == 0 => {
+                text.push_str(&xml::unescape(&content[range.clone()]));
+            }
+            (Token::Other { range }, _) if code_depth == 0 => {
+                // CDATA 中的文本
+                let raw = &content[range.clone()];
+                if let Some(inner) = raw.strip_prefix("<![CDATA[").and_then(|r| r.strip_suffix("]]>")) {
+                    text.push_str(inner);
+                }
+            }
+            _ => {}
+        }
+    }
+    (text, tokens.len())
+}
+
+/// 生成 TMX 1.4 文件
+pub fn render(units: &[MemoryUnit]) -> String {
+    // 所有句段的源语言相同时写入 header，否则为 *all*
+    let srclang = match units.first() {
+        Some(first) if units.iter().all(|u| u.source_lang == first.source_lang) => first.source_lang.as_str(),
+        _ => "*all*",
+    };
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<tmx version=\"1.4\">\n");
+    out.push_str(&format!(
+        "  <header creationtool=\"fanyi\" creationtoolversion=\"{}\" datatype=\"plaintext\" segtype=\"sentence\" adminlang=\"en\" srclang=\"{}\" o-tmf=\"fanyi\" creationdate=\"{}\"/>\n",
+        env!("CARGO_PKG_VERSION"),
+        xml::escape_attr(srclang),
+        timestamp_now()
+    ));
+    out.push_str("  <body>\n");
+    for unit in units {
+        let created = unit
+            .created
+            .as_ref()
+            .map(|c| format!(" creationdate=\"{}\"", xml::escape_attr(c)))
+            .unwrap_or_default();
+        out.push_str(&format!("    <tu srclang=\"{}\"{}>\n", xml::escape_attr(&unit.source_lang), created));
+        for (lang, text) in [(&unit.source_lang, &unit.source), (&unit.target_lang, &unit.target)] {
+            out.push_str(&format!(
+                "      <tuv xml:lang=\"{}\"><seg>{}</seg></tuv>\n",
+                xml::escape_attr(lang),
+                xml::escape_text(text)
+            ));
+        }
+        out.push_str("    </tu>\n");
+    }
+    out.push_str("  </body>\n</tmx>\n");
+    out
+}
+
+/// 当前 UTC 时间，TMX 的日期格式 YYYYMMDDThhmmssZ
+pub fn timestamp_now() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (days, rem) = (secs / 86400, secs % 86400);
+
+    // 由 1970-01-01 起的天数换算公历日期
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_units_and_skips_inline_codes() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tmx version="1.4">
+  <header srclang="en-US" datatype="plaintext"/>
+  <body>
+    <tu creationdate="20240101T000000Z">
+      <tuv xml:lang="zh-CN"><seg>打开<bpt i="1">&lt;b&gt;</bpt>文件<ept i="1">&lt;/b&gt;</ept></seg></tuv>
+      <tuv xml:lang="en-US"><seg>Open <bpt i="1">&lt;b&gt;</bpt>file<ept i="1">&lt;/b&gt;</ept> &amp; save</seg></tuv>
+      <tuv xml:lang="ja"><seg><![CDATA[開く]]></seg></tuv>
+    </tu>
+    <tu srclang="fr">
+      <tuv lang="fr"><seg>Bonjour</seg></tuv>
+      <tuv lang="de"><seg></seg></tuv>
+    </tu>
+  </body>
+</tmx>
+"#;
+        let units = parse(content).unwrap();
+        let found: Vec<(&str, &str, &str, &str)> = units
+            .iter()
+            .map(|u| (u.source_lang.as_str(), u.target_lang.as_str(), u.source.as_str(), u.target.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("en-US", "zh-CN", "Open file & save", "打开文件"),
+                ("en-US", "ja", "Open file & save", "開く"),
+            ]
+        );
+        assert_eq!(units[0].created.as_deref(), Some("20240101T000000Z"));
+        assert!(parse("<xliff/>").is_err());
+    }
+
+    #[test]
+    fn render_round_trips() {
+        let units = vec![MemoryUnit {
+            source_lang: "en".to_string(),
+            target_lang: "zh-Hans".to_string(),
+            source: "Fish & <chips>".to_string(),
+            target: "炸鱼薯条".to_string(),
+            created: Some("20240101T000000Z".to_string()),
+        }];
+        let rendered = render(&units);
+        assert!(rendered.contains(r#"srclang="en" o-tmf="fanyi""#));
+
+        let parsed = parse(&rendered).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].source, "Fish & <chips>");
+        assert_eq!(parsed[0].target, "炸鱼薯条");
+        assert_eq!(parsed[0].target_lang, "zh-Hans");
+        assert_eq!(parsed[0].created, units[0].created);
+    }
+
+    #[test]
+    fn timestamp_has_tmx_format() {
+        let now = timestamp_now();
+        assert_eq!(now.len(), 16);
+        assert_eq!(&now[8..9], "T");
+        assert!(now.ends_with('Z'));
+    }
+}
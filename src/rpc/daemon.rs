@@ -0,0 +1,316 @@
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::os::unix::fs::PermissionsExt;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::Notify;
+
+use super::methods::TranslatorHandler;
+use super::{serve, Framing, Handler, RpcError};
+use crate::translator::trait_def::{TranslationNotices, TranslationResult};
+use crate::translator::Translator;
+
+/// 守护进程使用的目录，只有当前用户可以访问
+pub fn runtime_dir() -> Result<PathBuf> {
+    let dir = dirs::runtime_dir()
+        .or_else(dirs::cache_dir)
+        .context("无法获取运行时目录")?
+        .join("fanyi");
+    std::fs::create_dir_all(&dir).context("创建运行时目录失败")?;
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700)).context("设置运行时目录权限失败")?;
+    Ok(dir)
+}
+
+/// 守护进程的套接字，每个配置档案各自运行一个守护进程
+pub fn socket_path(profile: Option<&str>) -> Result<PathBuf> {
+    let name = match profile {
+        Some(profile) => format!("daemon-{}.sock", profile),
+        None => "daemon.sock".to_string(),
+    };
+    Ok(runtime_dir()?.join(name))
+}
+
+pub fn log_path() -> Result<PathBuf> {
+    Ok(runtime_dir()?.join("daemon.log"))
+}
+
+/// 守护进程提供 `fanyi rpc` 的全部方法，另外支持 status 和 shutdown
+struct DaemonHandler<T> {
+    methods: TranslatorHandler<T>,
+    /// 守护进程启动时的配置摘要
+    fingerprint: String,
+    started: Instant,
+    requests: AtomicU64,
+    shutdown: Arc<Notify>,
+}
+
+impl<T> DaemonHandler<T> {
+    fn new(methods: TranslatorHandler<T>, fingerprint: String, shutdown: Arc<Notify>) -> Self {
+        Self {
+            methods,
+            fingerprint,
+            started: Instant::now(),
+            requests: AtomicU64::new(0),
+            shutdown,
+        }
+    }
+}
+
+#[async_trait]
+impl<T: Translator + 'static> Handler for DaemonHandler<T> {
+    async fn handle(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "status" => Ok(json!({
+                "pid": std::process::id(),
+                "config": self.fingerprint,
+                "uptime": self.started.elapsed().as_secs(),
+                "requests": self.requests.load(Ordering::Relaxed),
+            })),
+            "shutdown" => {
+                self.shutdown.notify_one();
+                Ok(Value::Null)
+            }
+            _ => {
+                self.requests.fetch_add(1, Ordering::Relaxed);
+                self.methods.handle(method, params).await
+            }
+        }
+    }
+}
+
+/// 在 Unix 域套接字上运行守护进程，所有连接共用一个翻译器、HTTP 连接和缓存
+pub async fn run<T: Translator + 'static>(
+    methods: TranslatorHandler<T>,
+    profile: Option<&str>,
+    fingerprint: String,
+) -> Result<()> {
+    let path = socket_path(profile)?;
+    if DaemonClient::connect(profile).await.is_some() {
+        bail!("守护进程已在运行: {}", path.display());
+    }
+    // 上次异常退出留下的套接字文件
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).with_context(|| format!("监听 {} 失败", path.display()))?;
+
+    let shutdown = Arc::new(Notify::new());
+    let handler = Arc::new(DaemonHandler::new(methods, fingerprint, shutdown.clone()));
+    eprintln!("守护进程已启动: {} (pid {})", path.display(), std::process::id());
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, _) = accepted.context("接受连接失败")?;
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    let (reader, writer) = stream.into_split();
+                    if let Err(e) = serve(handler, Framing::Lines, reader, writer).await {
+                        eprintln!("连接异常结束: {}", e);
+                    }
+                });
+            }
+            _ = shutdown.notified() => break,
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    let _ = std::fs::remove_file(&path);
+    eprintln!("守护进程已退出");
+    Ok(())
+}
+
+/// 连接到守护进程的客户端
+pub struct DaemonClient {
+    reader: BufReader<tokio::net::unix::OwnedReadHalf>,
+    writer: tokio::net::unix::OwnedWriteHalf,
+    next_id: u64,
+}
+
+impl DaemonClient {
+    /// 连接到正在运行的守护进程，没有运行时返回 None
+    pub async fn connect(profile: Option<&str>) -> Option<Self> {
+        Self::connect_to(&socket_path(profile).ok()?).await
+    }
+
+    /// 连接到指定的套接字
+    async fn connect_to(path: &Path) -> Option<Self> {
+        let stream = UnixStream::connect(path).await.ok()?;
+        let (reader, writer) = stream.into_split();
+        Some(Self {
+            reader: BufReader::new(reader),
+            writer,
+            next_id: 1,
+        })
+    }
+
+    /// 调用方法并等待结果
+    pub async fn call(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }).to_string();
+        request.push('\n');
+        self.writer.write_all(request.as_bytes()).await.context("发送请求到守护进程失败")?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line).await.context("读取守护进程响应失败")? == 0 {
+            bail!("守护进程关闭了连接");
+        }
+        let mut response: Value = serde_json::from_str(&line).context("解析守护进程响应失败")?;
+        if let Some(message) = response.pointer("/error/message").and_then(|m| m.as_str()) {
+            bail!("{}", message);
+        }
+        Ok(response["result"].take())
+    }
+
+    /// 翻译单段文本，同时取回翻译记忆和术语表的提示
+    pub async fn translate(&mut self, text: &str, from: &str, to: &str) -> Result<(TranslationResult, TranslationNotices)> {
+        #[derive(Deserialize)]
+        struct Response {
+            #[serde(flatten)]
+            result: TranslationResult,
+            #[serde(default)]
+            notices: TranslationNotices,
+        }
+
+        let params = json!({ "text": text, "from": from, "to": to, "notices": true });
+        let response: Response =
+            serde_json::from_value(self.call("translate", params).await?).context("解析守护进程响应失败")?;
+        Ok((response.result, response.notices))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::Provider;
+    use crate::memory::{MemoryUnit, TranslationMemory};
+    use crate::translator::glossary::{Glossary, GlossaryTranslator};
+    use crate::translator::memory::MemoryTranslator;
+    use tokio::io::{duplex, split, AsyncBufReadExt};
+
+    /// 不管原文是什么都返回固定译文，会破坏术语的占位标记
+    struct FixedTranslator;
+
+    #[async_trait]
+    impl Translator for FixedTranslator {
+        async fn translate(&self, text: &str, from: &str, to: &str) -> Result<TranslationResult> {
+            Ok(TranslationResult {
+                source: text.to_string(),
+                target: "固定译文".to_string(),
+                from: from.to_string(),
+                to: to.to_string(),
+                detected_language: None,
+            })
+        }
+
+        fn provider(&self) -> Provider {
+            Provider::Baidu
+        }
+
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+    }
+
+    fn handler() -> DaemonHandler<MemoryTranslator<GlossaryTranslator<FixedTranslator>>> {
+        let glossary = Glossary::from_csv("en,zh\nbucket,存储桶\n").unwrap();
+        let mut memory = TranslationMemory::load(Path::new("/nonexistent/fanyi-memory.json")).unwrap();
+        memory.add(MemoryUnit {
+            source_lang: "en".to_string(),
+            target_lang: "zh".to_string(),
+            source: "Delete the old files".to_string(),
+            target: "删除旧文件".to_string(),
+            created: None,
+        });
+        let translator = MemoryTranslator::new(GlossaryTranslator::new(FixedTranslator, Some(glossary)), Some(memory), 0.6);
+        let methods = TranslatorHandler::new(translator, "auto".to_string(), "zh".to_string());
+        DaemonHandler::new(methods, "abc".to_string(), Arc::new(Notify::new()))
+    }
+
+    #[tokio::test]
+    async fn status_reports_fingerprint_and_counts_requests() {
+        let handler = handler();
+        handler.handle("translate", json!({ "text": "hi", "from": "en" })).await.unwrap();
+        let status = handler.handle("status", Value::Null).await.unwrap();
+        assert_eq!(status["config"], "abc");
+        assert_eq!(status["requests"], 1);
+    }
+
+    #[tokio::test]
+    async fn shutdown_notifies_the_accept_loop() {
+        let handler = handler();
+        let notified = handler.shutdown.clone();
+        let waiter = tokio::spawn(async move { notified.notified().await });
+        tokio::task::yield_now().await;
+        handler.handle("shutdown", Value::Null).await.unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter).await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn translate_returns_notices_only_when_requested() {
+        let (client, server) = duplex(4096);
+        let (reader, writer) = split(server);
+        tokio::spawn(serve(Arc::new(handler()), Framing::Lines, reader, writer));
+        let (reader, mut writer) = split(client);
+        let mut lines = BufReader::new(reader).lines();
+
+        writer
+            .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"translate\",\"params\":{\"text\":\"Empty the bucket\",\"from\":\"en\"}}\n")
+            .await
+            .unwrap();
+        let response: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(response["result"]["target"], "固定译文");
+        assert!(response["result"].get("notices").is_none());
+
+        writer
+            .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"translate\",\"params\":{\"text\":\"Empty the bucket\",\"from\":\"en\",\"notices\":true}}\n")
+            .await
+            .unwrap();
+        let response: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        let violations = &response["result"]["notices"]["violations"];
+        assert_eq!(violations[0]["source_term"], "bucket");
+        assert_eq!(violations[0]["target_term"], "存储桶");
+        assert_eq!(response["result"]["notices"]["from_memory"], false);
+    }
+
+    #[tokio::test]
+    async fn client_receives_memory_and_glossary_notices() {
+        let path = std::env::temp_dir().join(format!("fanyi-daemon-test-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        let handler = Arc::new(handler());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, writer) = stream.into_split();
+            serve(handler, Framing::Lines, reader, writer).await
+        });
+
+        let mut client = DaemonClient::connect_to(&path).await.unwrap();
+        let status = client.call("status", Value::Null).await.unwrap();
+        assert_eq!(status["config"], "abc");
+
+        let (result, notices) = client.translate("Delete the old files", "en", "zh").await.unwrap();
+        assert_eq!(result.target, "删除旧文件");
+        assert!(notices.from_memory);
+        assert!(notices.fuzzy_matches.is_empty());
+
+        let (result, notices) = client.translate("Delete the old file", "en", "zh").await.unwrap();
+        assert_eq!(result.target, "固定译文");
+        assert!(!notices.from_memory);
+        assert_eq!(notices.fuzzy_matches[0].unit.target, "删除旧文件");
+        assert!(notices.violations.is_empty());
+
+        let (_, notices) = client.translate("Empty the bucket", "en", "zh").await.unwrap();
+        assert_eq!(notices.violations.len(), 1);
+        assert_eq!(notices.violations[0].translation, "固定译文");
+
+        let error = client.call("nonexistent", Value::Null).await.unwrap_err();
+        assert!(error.to_string().contains("nonexistent"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,379 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use super::{parse_params, Handler, RpcError};
+use crate::formats::code::{self, CodeLanguage};
+use crate::language::registry;
+use crate::translator::Translator;
+
+/// 最小的 LSP 服务：悬停翻译光标处的注释或字符串，代码操作翻译选中的文本
+///
+/// 只支持全量同步文档内容，位置按 LSP 默认的 UTF-16 列号计算
+pub struct LspHandler<T> {
+    translator: T,
+    /// 源语言，可以被初始化参数覆盖
+    from: Mutex<String>,
+    /// 目标语言，可以被初始化参数覆盖
+    to: Mutex<String>,
+    documents: Mutex<HashMap<String, Document>>,
+    /// 客户端支持 codeAction/resolve 时延迟到选中操作后再翻译
+    resolve_edits: AtomicBool,
+    shutdown: AtomicBool,
+}
+
+struct Document {
+    text: String,
+    language: Option<CodeLanguage>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+struct Position {
+    line: usize,
+    character: usize,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+struct Range {
+    start: Position,
+    end: Position,
+}
+
+#[derive(Deserialize)]
+struct TextDocumentIdentifier {
+    uri: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HoverParams {
+    text_document: TextDocumentIdentifier,
+    position: Position,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CodeActionParams {
+    text_document: TextDocumentIdentifier,
+    range: Range,
+}
+
+/// LSP 位置转换为字节偏移，超出范围时取行尾或文末
+fn offset_of(text: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(i) => line_start += i + 1,
+            None => return text.len(),
+        }
+    }
+    let line_end = text[line_start..].find('\n').map_or(text.len(), |i| line_start + i);
+
+    let mut units = 0;
+    for (i, c) in text[line_start..line_end].char_indices() {
+        if units >= position.character {
+            return line_start + i;
+        }
+        units += c.len_utf16();
+    }
+    line_end
+}
+
+/// 字节偏移转换为 LSP 位置
+fn position_of(text: &str, offset: usize) -> Value {
+    let before = &text[..offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let character: usize = before[line_start..].chars().map(char::len_utf16).sum();
+    json!({ "line": line, "character": character })
+}
+
+impl<T: Translator> LspHandler<T> {
+    pub fn new(translator: T, from: String, to: String) -> Self {
+        Self {
+            translator,
+            from: Mutex::new(from),
+            to: Mutex::new(to),
+            documents: Mutex::new(HashMap::new()),
+            resolve_edits: AtomicBool::new(false),
+            shutdown: AtomicBool::new(false),
+        }
+    }
+
+    fn languages(&self) -> (String, String) {
+        (self.from.lock().unwrap().clone(), self.to.lock().unwrap().clone())
+    }
+
+    fn initialize(&self, params: Value) -> Value {
+        let resolve_edits = params
+            .pointer("/capabilities/textDocument/codeAction/resolveSupport/properties")
+            .and_then(|p| p.as_array())
+            .is_some_and(|p| p.iter().any(|v| v == "edit"));
+        self.resolve_edits.store(resolve_edits, Ordering::Relaxed);
+
+        // 初始化参数 {"from": "auto", "to": "zh"} 可以覆盖命令行中的语言
+        if let Some(options) = params.get("initializationOptions") {
+            if let Some(from) = options.get("from").and_then(|v| v.as_str()) {
+                *self.from.lock().unwrap() = from.to_string();
+            }
+            if let Some(to) = options.get("to").and_then(|v| v.as_str()) {
+                *self.to.lock().unwrap() = to.to_string();
+            }
+        }
+
+        json!({
+            "capabilities": {
+                "textDocumentSync": { "openClose": true, "change": 1 },
+                "hoverProvider": true,
+                "codeActionProvider": { "codeActionKinds": ["refactor.rewrite"], "resolveProvider": true },
+            },
+            "serverInfo": { "name": "fanyi", "version": env!("CARGO_PKG_VERSION") },
+        })
+    }
+
+    /// 当前文档内容的副本，避免在等待翻译时持有锁
+    fn document(&self, uri: &str) -> Option<(String, Option<CodeLanguage>)> {
+        let documents = self.documents.lock().unwrap();
+        documents.get(uri).map(|d| (d.text.clone(), d.language))
+    }
+
+    async fn hover(&self, params: HoverParams) -> Result<Value, RpcError> {
+        let Some((text, Some(language))) = self.document(&params.text_document.uri) else {
+            return Ok(Value::Null);
+        };
+        let offset = offset_of(&text, params.position);
+        let Some((range, source)) = code::text_at(&text, language, offset) else {
+            return Ok(Value::Null);
+        };
+
+        let (from, to) = self.languages();
+        let result = self.translator.translate(&source, &from, &to).await.map_err(RpcError::internal)?;
+        let detected = result.detected_language.as_deref().unwrap_or(&from);
+        Ok(json!({
+            "contents": {
+                "kind": "markdown",
+                "value": format!(
+                    "{}\n\n---\n*{} · {} → {}*",
+                    result.target,
+                    self.translator.name(),
+                    detected.to_uppercase(),
+                    to.to_uppercase()
+                ),
+            },
+            "range": { "start": position_of(&text, range.start), "end": position_of(&text, range.end) },
+        }))
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> Result<Value, RpcError> {
+        let start = params.range.start;
+        let end = params.range.end;
+        if (start.line, start.character) == (end.line, end.character) {
+            return Ok(json!([]));
+        }
+
+        let (_, to) = self.languages();
+        let name = registry::resolve(&to).map_or(to.as_str(), |l| l.name_en);
+        let action = json!({
+            "title": format!("Translate selection to {}", name),
+            "kind": "refactor.rewrite",
+            "data": {
+                "uri": params.text_document.uri,
+                "range": { "start": { "line": start.line, "character": start.character },
+                           "end": { "line": end.line, "character": end.character } },
+            },
+        });
+
+        if self.resolve_edits.load(Ordering::Relaxed) {
+            Ok(json!([action]))
+        } else {
+            Ok(json!([self.resolve(action).await?]))
+        }
+    }
+
+    /// 翻译代码操作对应的选区，补全操作的 edit
+    async fn resolve(&self, mut action: Value) -> Result<Value, RpcError> {
+        let data = action.get("data").cloned().unwrap_or_default();
+        let uri = data.get("uri").and_then(|u| u.as_str()).unwrap_or_default().to_string();
+        let range: Range = parse_params(data.get("range").cloned().unwrap_or_default())?;
+        let Some((text, _)) = self.document(&uri) else {
+            return Err(RpcError::invalid_params(format!("文档未打开: {}", uri)));
+        };
+
+        let (start, end) = (offset_of(&text, range.start), offset_of(&text, range.end));
+        let selection = &text[start.min(end)..end.max(start)];
+        if selection.trim().is_empty() {
+            return Ok(action);
+        }
+
+        let (from, to) = self.languages();
+        let result = self.translator.translate(selection.trim(), &from, &to).await.map_err(RpcError::internal)?;
+        // 保留选区首尾的空白
+        let leading = &selection[..selection.len() - selection.trim_start().len()];
+        let trailing = &selection[selection.trim_end().len()..];
+        let new_text = format!("{}{}{}", leading, result.target, trailing);
+
+        action["edit"] = json!({
+            "changes": { uri: [{ "range": data["range"], "newText": new_text }] },
+        });
+        Ok(action)
+    }
+
+    fn open(&self, params: &Value) {
+        let Some(document) = params.get("textDocument") else { return };
+        let uri = document.get("uri").and_then(|u| u.as_str()).unwrap_or_default();
+        let text = document.get("text").and_then(|t| t.as_str()).unwrap_or_default();
+        let language = document
+            .get("languageId")
+            .and_then(|l| l.as_str())
+            .and_then(CodeLanguage::from_language_id)
+            .or_else(|| CodeLanguage::from_path(Path::new(uri)));
+        self.documents.lock().unwrap().insert(
+            uri.to_string(),
+            Document {
+                text: text.to_string(),
+                language,
+            },
+        );
+    }
+
+    /// 全量同步：取最后一次变更的完整内容
+    fn change(&self, params: &Value) {
+        let uri = params.pointer("/textDocument/uri").and_then(|u| u.as_str()).unwrap_or_default();
+        let text = params
+            .get("contentChanges")
+            .and_then(|c| c.as_array())
+            .and_then(|c| c.last())
+            .and_then(|c| c.get("text"))
+            .and_then(|t| t.as_str());
+        if let (Some(document), Some(text)) = (self.documents.lock().unwrap().get_mut(uri), text) {
+            document.text = text.to_string();
+        }
+    }
+}
+
+#[async_trait]
+impl<T: Translator + 'static> Handler for LspHandler<T> {
+    async fn handle(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => Ok(self.initialize(params)),
+            "shutdown" => {
+                self.shutdown.store(true, Ordering::Relaxed);
+                Ok(Value::Null)
+            }
+            "textDocument/hover" => self.hover(parse_params(params)?).await,
+            "textDocument/codeAction" => self.code_action(parse_params(params)?).await,
+            "codeAction/resolve" => self.resolve(params).await,
+            _ => Err(RpcError::method_not_found(method)),
+        }
+    }
+
+    async fn notify(&self, method: &str, params: Value) {
+        match method {
+            "textDocument/didOpen" => self.open(&params),
+            "textDocument/didChange" => self.change(&params),
+            "textDocument/didClose" => {
+                if let Some(uri) = params.pointer("/textDocument/uri").and_then(|u| u.as_str()) {
+                    self.documents.lock().unwrap().remove(uri);
+                }
+            }
+            // 收到 shutdown 后退出码为 0，否则为 1
+            "exit" => std::process::exit(if self.shutdown.load(Ordering::Relaxed) { 0 } else { 1 }),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::stub::UppercaseTranslator;
+
+    const URI: &str = "file:///src/main.rs";
+
+    fn position(line: usize, character: usize) -> Position {
+        Position { line, character }
+    }
+
+    async fn open(text: &str, capabilities: Value) -> LspHandler<UppercaseTranslator> {
+        let handler = LspHandler::new(UppercaseTranslator, "auto".to_string(), "zh".to_string());
+        handler.handle("initialize", json!({ "capabilities": capabilities })).await.unwrap();
+        let document = json!({ "textDocument": { "uri": URI, "languageId": "rust", "version": 1, "text": text } });
+        handler.notify("textDocument/didOpen", document).await;
+        handler
+    }
+
+    #[test]
+    fn converts_utf16_positions() {
+        // 😀 占两个 UTF-16 单元、四个字节，中文字符占一个单元、三个字节
+        let text = "a😀b\n中文\nlast";
+        assert_eq!(offset_of(text, position(0, 1)), 1);
+        assert_eq!(offset_of(text, position(0, 3)), 5);
+        assert_eq!(offset_of(text, position(0, 10)), 6);
+        assert_eq!(offset_of(text, position(1, 1)), 10);
+        assert_eq!(offset_of(text, position(2, 0)), 14);
+        assert_eq!(offset_of(text, position(5, 0)), text.len());
+
+        assert_eq!(position_of(text, 5), json!({ "line": 0, "character": 3 }));
+        assert_eq!(position_of(text, 10), json!({ "line": 1, "character": 1 }));
+        assert_eq!(position_of(text, text.len()), json!({ "line": 2, "character": 4 }));
+    }
+
+    #[tokio::test]
+    async fn hovers_over_comments() {
+        let text = "fn main() {\n    // 😀 hello\n    let x = 1;\n}\n";
+        let handler = open(text, json!({})).await;
+
+        let params = json!({ "textDocument": { "uri": URI }, "position": { "line": 1, "character": 10 } });
+        let hover = handler.handle("textDocument/hover", params).await.unwrap();
+        let value = hover["contents"]["value"].as_str().unwrap();
+        assert!(value.starts_with("😀 HELLO\n"), "{}", value);
+        assert!(value.ends_with("*stub · EN → ZH*"), "{}", value);
+        assert_eq!(hover["range"]["start"], json!({ "line": 1, "character": 4 }));
+        assert_eq!(hover["range"]["end"], json!({ "line": 1, "character": 15 }));
+
+        let params = json!({ "textDocument": { "uri": URI }, "position": { "line": 2, "character": 6 } });
+        assert_eq!(handler.handle("textDocument/hover", params).await.unwrap(), Value::Null);
+        let params = json!({ "textDocument": { "uri": "file:///other.rs" }, "position": { "line": 0, "character": 0 } });
+        assert_eq!(handler.handle("textDocument/hover", params).await.unwrap(), Value::Null);
+    }
+
+    #[tokio::test]
+    async fn translates_the_selection_keeping_surrounding_whitespace() {
+        let handler = open("let s = \"多 hello \";\n", json!({})).await;
+        let range = json!({ "start": { "line": 0, "character": 10 }, "end": { "line": 0, "character": 17 } });
+        let params = json!({ "textDocument": { "uri": URI }, "range": range, "context": { "diagnostics": [] } });
+        let actions = handler.handle("textDocument/codeAction", params).await.unwrap();
+        assert_eq!(actions[0]["title"], "Translate selection to Chinese");
+        assert_eq!(actions[0]["edit"]["changes"][URI], json!([{ "range": range, "newText": " HELLO " }]));
+
+        // 空选区没有操作
+        let empty = json!({ "start": { "line": 0, "character": 3 }, "end": { "line": 0, "character": 3 } });
+        let params = json!({ "textDocument": { "uri": URI }, "range": empty });
+        assert_eq!(handler.handle("textDocument/codeAction", params).await.unwrap(), json!([]));
+    }
+
+    #[tokio::test]
+    async fn resolves_edits_lazily_when_supported() {
+        let capabilities = json!({
+            "textDocument": { "codeAction": { "resolveSupport": { "properties": ["edit"] } } },
+        });
+        let handler = open("// old\n", capabilities).await;
+        let change = json!({ "textDocument": { "uri": URI, "version": 2 }, "contentChanges": [{ "text": "// new text\n" }] });
+        handler.notify("textDocument/didChange", change).await;
+
+        let range = json!({ "start": { "line": 0, "character": 3 }, "end": { "line": 0, "character": 11 } });
+        let params = json!({ "textDocument": { "uri": URI }, "range": range });
+        let actions = handler.handle("textDocument/codeAction", params).await.unwrap();
+        assert!(actions[0].get("edit").is_none());
+
+        let action = handler.handle("codeAction/resolve", actions[0].clone()).await.unwrap();
+        assert_eq!(action["edit"]["changes"][URI][0]["newText"], "NEW TEXT");
+
+        handler.notify("textDocument/didClose", json!({ "textDocument": { "uri": URI } })).await;
+        assert!(handler.handle("codeAction/resolve", actions[0].clone()).await.is_err());
+    }
+}
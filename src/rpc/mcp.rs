@@ -0,0 +1,259 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::methods::TranslatorHandler;
+use super::{parse_params, Handler, RpcError, INTERNAL_ERROR, INVALID_PARAMS};
+use crate::translator::Translator;
+
+/// 支持的 MCP 协议版本，客户端请求其他版本时使用最新的版本
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Model Context Protocol 服务，将翻译器的功能作为工具提供给 AI 助手
+pub struct McpHandler<T> {
+    methods: TranslatorHandler<T>,
+}
+
+/// 工具名称、说明、对应的 `fanyi rpc` 方法以及参数的 JSON Schema
+struct Tool {
+    name: &'static str,
+    description: &'static str,
+    method: &'static str,
+    schema: fn() -> Value,
+}
+
+const TOOLS: &[Tool] = &[
+    Tool {
+        name: "translate",
+        description: "Translate text between languages. Pass an array to translate several segments in one request.",
+        method: "translate",
+        schema: translate_schema,
+    },
+    Tool {
+        name: "detect_language",
+        description: "Detect the language of a text and return its BCP-47 tag.",
+        method: "detect",
+        schema: detect_schema,
+    },
+    Tool {
+        name: "list_languages",
+        description: "List the languages supported by the configured translation provider.",
+        method: "languages",
+        schema: languages_schema,
+    },
+    Tool {
+        name: "dictionary_lookup",
+        description: "Look up a single word: translation, phonetics and definitions grouped by part of speech.",
+        method: "lookup",
+        schema: lookup_schema,
+    },
+];
+
+fn language_property(description: &str) -> Value {
+    json!({
+        "type": "string",
+        "description": format!("{} Accepts ISO 639 codes, BCP-47 tags or language names (see list_languages).", description),
+    })
+}
+
+fn translate_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "text": {
+                "description": "Text to translate, or an array of texts.",
+                "oneOf": [
+                    { "type": "string" },
+                    { "type": "array", "items": { "type": "string" } },
+                ],
+            },
+            "from": language_property("Source language, \"auto\" to detect. Defaults to the configured language."),
+            "to": language_property("Target language. Defaults to the configured language."),
+        },
+        "required": ["text"],
+    })
+}
+
+fn detect_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "text": { "type": "string", "description": "Text whose language should be detected." },
+        },
+        "required": ["text"],
+    })
+}
+
+fn languages_schema() -> Value {
+    json!({ "type": "object", "properties": {} })
+}
+
+fn lookup_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "word": { "type": "string", "description": "Word to look up." },
+            "from": language_property("Language of the word. Defaults to the configured language."),
+            "to": language_property("Language of the translation. Defaults to the configured language."),
+        },
+        "required": ["word"],
+    })
+}
+
+#[derive(Deserialize)]
+struct InitializeParams {
+    #[serde(rename = "protocolVersion")]
+    protocol_version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CallParams {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+impl<T: Translator + 'static> McpHandler<T> {
+    pub fn new(methods: TranslatorHandler<T>) -> Self {
+        Self { methods }
+    }
+
+    fn initialize(&self, params: InitializeParams) -> Value {
+        let version = params
+            .protocol_version
+            .filter(|v| PROTOCOL_VERSIONS.contains(&v.as_str()))
+            .unwrap_or_else(|| PROTOCOL_VERSIONS[0].to_string());
+        json!({
+            "protocolVersion": version,
+            "capabilities": { "tools": { "listChanged": false } },
+            "serverInfo": { "name": "fanyi", "version": env!("CARGO_PKG_VERSION") },
+        })
+    }
+
+    fn list_tools(&self) -> Value {
+        let tools: Vec<Value> = TOOLS
+            .iter()
+            .map(|tool| {
+                json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "inputSchema": (tool.schema)(),
+                })
+            })
+            .collect();
+        json!({ "tools": tools })
+    }
+
+    /// 调用工具；参数错误和翻译失败作为工具的错误结果返回，供助手据此调整
+    async fn call_tool(&self, params: CallParams) -> Result<Value, RpcError> {
+        let Some(tool) = TOOLS.iter().find(|t| t.name == params.name) else {
+            return Err(RpcError::invalid_params(format!("未知的工具: {}", params.name)));
+        };
+
+        match self.methods.handle(tool.method, params.arguments).await {
+            Ok(result) => Ok(json!({
+                "content": [{ "type": "text", "text": serde_json::to_string_pretty(&result).unwrap_or_default() }],
+                "structuredContent": if result.is_object() { result } else { json!({ "result": result }) },
+                "isError": false,
+            })),
+            Err(error) if error.code == INVALID_PARAMS || error.code == INTERNAL_ERROR => Ok(json!({
+                "content": [{ "type": "text", "text": error.message }],
+                "isError": true,
+            })),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+#[async_trait]
+impl<T: Translator + 'static> Handler for McpHandler<T> {
+    async fn handle(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => Ok(self.initialize(parse_params(params)?)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(self.list_tools()),
+            "tools/call" => self.call_tool(parse_params(params)?).await,
+            _ => Err(RpcError::method_not_found(method)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::{serve, Framing, METHOD_NOT_FOUND};
+    use crate::translator::stub::UppercaseTranslator;
+    use std::sync::Arc;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    fn handler() -> McpHandler<UppercaseTranslator> {
+        McpHandler::new(TranslatorHandler::new(UppercaseTranslator, "auto".to_string(), "zh".to_string()))
+    }
+
+    #[tokio::test]
+    async fn negotiates_the_protocol_version() {
+        let handler = handler();
+        let result = handler.handle("initialize", json!({ "protocolVersion": "2025-03-26" })).await.unwrap();
+        assert_eq!(result["protocolVersion"], "2025-03-26");
+        assert_eq!(result["serverInfo"]["name"], "fanyi");
+        let result = handler.handle("initialize", json!({ "protocolVersion": "1999-01-01" })).await.unwrap();
+        assert_eq!(result["protocolVersion"], PROTOCOL_VERSIONS[0]);
+    }
+
+    #[tokio::test]
+    async fn lists_tools_with_schemas() {
+        let result = handler().handle("tools/list", Value::Null).await.unwrap();
+        let names: Vec<&str> = result["tools"].as_array().unwrap().iter().map(|t| t["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["translate", "detect_language", "list_languages", "dictionary_lookup"]);
+        assert_eq!(result["tools"][0]["inputSchema"]["required"], json!(["text"]));
+    }
+
+    #[tokio::test]
+    async fn calls_tools() {
+        let handler = handler();
+        let params = json!({ "name": "translate", "arguments": { "text": "hello", "from": "en" } });
+        let result = handler.handle("tools/call", params).await.unwrap();
+        assert_eq!(result["isError"], false);
+        assert_eq!(result["structuredContent"]["target"], "HELLO");
+        let text: Value = serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(text, result["structuredContent"]);
+
+        // 非对象的结果包装在 result 字段中
+        let result = handler.handle("tools/call", json!({ "name": "list_languages" })).await.unwrap();
+        assert!(result["structuredContent"]["result"].is_array());
+    }
+
+    #[tokio::test]
+    async fn reports_tool_errors_in_the_result() {
+        let handler = handler();
+        let params = json!({ "name": "translate", "arguments": { "text": "hello", "to": "notalang" } });
+        let result = handler.handle("tools/call", params).await.unwrap();
+        assert_eq!(result["isError"], true);
+        assert!(result["content"][0]["text"].as_str().unwrap().starts_with("参数错误"));
+
+        let error = handler.handle("tools/call", json!({ "name": "speak" })).await.unwrap_err();
+        assert_eq!(error.code, INVALID_PARAMS);
+        let error = handler.handle("resources/list", Value::Null).await.unwrap_err();
+        assert_eq!(error.code, METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn serves_a_session_over_lines() {
+        let (client, server) = tokio::io::duplex(4096);
+        let (reader, writer) = tokio::io::split(server);
+        tokio::spawn(serve(Arc::new(handler()), Framing::Lines, reader, writer));
+        let (reader, mut writer) = tokio::io::split(client);
+        let mut lines = BufReader::new(reader).lines();
+
+        let call = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": { "name": "detect_language", "arguments": { "text": "hello" } },
+        });
+        writer.write_all(format!("{}\n", call).as_bytes()).await.unwrap();
+        let response: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["structuredContent"], json!({ "language": "en" }));
+    }
+}
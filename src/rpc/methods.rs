@@ -0,0 +1,194 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{parse_params, Handler, RpcError};
+use crate::language::{validate_source, validate_target};
+use crate::translator::Translator;
+
+/// `fanyi rpc` 提供的方法: translate、detect、languages、lookup
+pub struct TranslatorHandler<T> {
+    translator: T,
+    default_from: String,
+    default_to: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Texts {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct TranslateParams {
+    text: Texts,
+    from: Option<String>,
+    to: Option<String>,
+    /// 单段文本时在结果中附带翻译记忆和术语表的提示
+    #[serde(default)]
+    notices: bool,
+}
+
+#[derive(Deserialize)]
+struct DetectParams {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct LookupParams {
+    word: String,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+impl<T: Translator> TranslatorHandler<T> {
+    pub fn new(translator: T, default_from: String, default_to: String) -> Self {
+        Self {
+            translator,
+            default_from,
+            default_to,
+        }
+    }
+
+    /// 补全默认语言并校验，返回 (源语言, 目标语言)
+    fn languages_of(&self, from: Option<String>, to: Option<String>) -> Result<(String, String), RpcError> {
+        let from = from.unwrap_or_else(|| self.default_from.clone());
+        let to = to.unwrap_or_else(|| self.default_to.clone());
+        let provider = self.translator.provider();
+        validate_source(&from, provider)
+            .and_then(|_| validate_target(&to, provider))
+            .map_err(RpcError::invalid_params)?;
+        Ok((from, to))
+    }
+
+    async fn translate(&self, params: TranslateParams) -> Result<Value, RpcError> {
+        let (from, to) = self.languages_of(params.from, params.to)?;
+        match params.text {
+            Texts::One(text) => {
+                if text.trim().is_empty() {
+                    return Err(RpcError::invalid_params("要翻译的文本不能为空"));
+                }
+                let result = self.translator.translate(&text, &from, &to).await.map_err(RpcError::internal)?;
+                let mut value = json!(result);
+                if params.notices {
+                    value["notices"] = json!(self.translator.notices(&text, &result.target, &from, &to));
+                }
+                Ok(value)
+            }
+            Texts::Many(texts) => {
+                let translations = self
+                    .translator
+                    .translate_batch(&texts, &from, &to)
+                    .await
+                    .map_err(RpcError::internal)?;
+                Ok(json!({ "from": from, "to": to, "translations": translations }))
+            }
+        }
+    }
+
+    fn languages(&self) -> Value {
+        let provider = self.translator.provider();
+        self.translator
+            .supported_languages()
+            .into_iter()
+            .map(|lang| {
+                json!({
+                    "code": lang.bcp47,
+                    "provider_code": lang.code_for(provider),
+                    "name": lang.name_zh,
+                    "name_en": lang.name_en,
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl<T: Translator + 'static> Handler for TranslatorHandler<T> {
+    async fn handle(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "translate" => self.translate(parse_params(params)?).await,
+            "detect" => {
+                let params: DetectParams = parse_params(params)?;
+                if params.text.trim().is_empty() {
+                    return Err(RpcError::invalid_params("要检测的文本不能为空"));
+                }
+                let language = self.translator.detect(&params.text).await.map_err(RpcError::internal)?;
+                Ok(json!({ "language": language }))
+            }
+            "languages" => Ok(self.languages()),
+            "lookup" => {
+                let params: LookupParams = parse_params(params)?;
+                let (from, to) = self.languages_of(params.from, params.to)?;
+                let entry = self.translator.lookup(&params.word, &from, &to).await.map_err(RpcError::internal)?;
+                Ok(json!(entry))
+            }
+            _ => Err(RpcError::method_not_found(method)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::{INVALID_PARAMS, METHOD_NOT_FOUND};
+    use crate::translator::stub::UppercaseTranslator;
+
+    fn handler() -> TranslatorHandler<UppercaseTranslator> {
+        TranslatorHandler::new(UppercaseTranslator, "auto".to_string(), "zh".to_string())
+    }
+
+    #[tokio::test]
+    async fn translates_one_text_or_a_batch() {
+        let handler = handler();
+        let result = handler.handle("translate", json!({ "text": "hello", "to": "ja" })).await.unwrap();
+        assert_eq!(result["target"], "HELLO");
+        assert_eq!(result["from"], "auto");
+        assert_eq!(result["to"], "ja");
+        assert!(result.get("notices").is_none());
+
+        let result = handler.handle("translate", json!({ "text": ["a", " ", "b"], "from": "en" })).await.unwrap();
+        assert_eq!(result, json!({ "from": "en", "to": "zh", "translations": ["A", " ", "B"] }));
+    }
+
+    #[tokio::test]
+    async fn attaches_notices_on_request() {
+        let result = handler().handle("translate", json!({ "text": "hello", "notices": true })).await.unwrap();
+        assert_eq!(result["notices"], json!({ "from_memory": false, "fuzzy_matches": [], "violations": [] }));
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_params() {
+        let handler = handler();
+        for params in [
+            json!({ "text": "  " }),
+            json!({ "text": "hello", "to": "notalang" }),
+            json!({ "text": "hello", "to": "auto" }),
+            json!({ "words": "hello" }),
+        ] {
+            let error = handler.handle("translate", params.clone()).await.unwrap_err();
+            assert_eq!(error.code, INVALID_PARAMS, "{}", params);
+        }
+        let error = handler.handle("detect", json!({ "text": "" })).await.unwrap_err();
+        assert_eq!(error.code, INVALID_PARAMS);
+        let error = handler.handle("speak", json!({})).await.unwrap_err();
+        assert_eq!(error.code, METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn detects_looks_up_and_lists_languages() {
+        let handler = handler();
+        let result = handler.handle("detect", json!({ "text": "hello" })).await.unwrap();
+        assert_eq!(result, json!({ "language": "en" }));
+
+        let result = handler.handle("lookup", json!({ "word": "cat", "from": "en" })).await.unwrap();
+        assert_eq!(result["word"], "cat");
+        assert_eq!(result["translation"], "CAT");
+
+        let languages = handler.handle("languages", Value::Null).await.unwrap();
+        let languages = languages.as_array().unwrap();
+        let hant = languages.iter().find(|lang| lang["code"] == "zh-Hant").unwrap();
+        assert_eq!(hant["provider_code"], "cht");
+    }
+}
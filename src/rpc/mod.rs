@@ -0,0 +1,342 @@
+#[cfg(unix)]
+pub mod daemon;
+pub mod lsp;
+pub mod mcp;
+pub mod methods;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinSet;
+
+/// JSON-RPC 2.0 错误码
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+/// 请求被客户端取消（与 LSP 的 RequestCancelled 相同）
+pub const REQUEST_CANCELLED: i64 = -32800;
+
+/// 消息的分帧方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// 每行一条消息 (fanyi rpc、MCP)
+    Lines,
+    /// 每条消息前带有 Content-Length 头部 (LSP)
+    Headers,
+}
+
+/// JSON-RPC 错误
+#[derive(Debug)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(METHOD_NOT_FOUND, format!("未知的方法: {}", method))
+    }
+
+    pub fn invalid_params(error: impl std::fmt::Display) -> Self {
+        Self::new(INVALID_PARAMS, format!("参数错误: {}", error))
+    }
+
+    /// 翻译服务等内部错误
+    pub fn internal(error: anyhow::Error) -> Self {
+        Self::new(INTERNAL_ERROR, error.to_string())
+    }
+}
+
+/// 处理 JSON-RPC 请求和通知
+#[async_trait]
+pub trait Handler: Send + Sync + 'static {
+    /// 处理带 id 的请求，返回结果或错误
+    async fn handle(&self, method: &str, params: Value) -> Result<Value, RpcError>;
+
+    /// 处理不需要响应的通知，默认忽略
+    async fn notify(&self, _method: &str, _params: Value) {}
+}
+
+/// 将参数反序列化为指定类型，缺少参数时按空对象处理
+pub fn parse_params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(RpcError::invalid_params)
+}
+
+fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": error.code, "message": error.message },
+        }),
+    }
+}
+
+/// 读取下一条消息，输入结束时返回 None
+async fn read_message<R: AsyncBufRead + Unpin>(reader: &mut R, framing: Framing) -> Result<Option<String>> {
+    let mut line = String::new();
+    match framing {
+        Framing::Lines => loop {
+            line.clear();
+            if reader.read_line(&mut line).await? == 0 {
+                return Ok(None);
+            }
+            if !line.trim().is_empty() {
+                return Ok(Some(line));
+            }
+        },
+        Framing::Headers => {
+            let mut length = None;
+            loop {
+                line.clear();
+                if reader.read_line(&mut line).await? == 0 {
+                    return Ok(None);
+                }
+                let header = line.trim();
+                if header.is_empty() {
+                    // 头部与消息之间的空行，没有头部时继续读取
+                    if length.is_some() {
+                        break;
+                    }
+                    continue;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.trim().eq_ignore_ascii_case("Content-Length") {
+                        length = Some(value.trim().parse::<usize>().context("无效的 Content-Length")?);
+                    }
+                }
+            }
+            let mut body = vec![0; length.unwrap_or_default()];
+            reader.read_exact(&mut body).await?;
+            Ok(Some(String::from_utf8(body).context("消息不是有效的 UTF-8")?))
+        }
+    }
+}
+
+/// 按分帧方式编码消息
+fn encode_message(message: &Value, framing: Framing) -> String {
+    let body = message.to_string();
+    match framing {
+        Framing::Lines => body + "\n",
+        Framing::Headers => format!("Content-Length: {}\r\n\r\n{}", body.len(), body),
+    }
+}
+
+/// 正在处理的请求，键为序列化后的 id
+type Inflight = Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>;
+
+/// 在标准输入输出上运行 JSON-RPC 2.0 服务，直到标准输入关闭
+pub async fn serve_stdio<H: Handler>(handler: Arc<H>, framing: Framing) -> Result<()> {
+    serve(handler, framing, tokio::io::stdin(), tokio::io::stdout()).await
+}
+
+/// 在一个连接上运行 JSON-RPC 2.0 服务，直到输入结束
+///
+/// 每个请求在单独的任务中处理，响应按完成顺序写出；通知按收到的顺序依次处理。
+/// 收到 `$/cancelRequest` 或 `notifications/cancelled` 通知时取消对应的请求
+pub async fn serve<H, R, W>(handler: Arc<H>, framing: Framing, input: R, mut output: W) -> Result<()>
+where
+    H: Handler,
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let (sender, mut receiver) = mpsc::unbounded_channel::<Value>();
+    let writer = tokio::spawn(async move {
+        while let Some(message) = receiver.recv().await {
+            output.write_all(encode_message(&message, framing).as_bytes()).await?;
+            output.flush().await?;
+        }
+        Ok::<_, std::io::Error>(())
+    });
+
+    let inflight: Inflight = Arc::default();
+    let mut tasks = JoinSet::new();
+    let mut reader = BufReader::new(input);
+
+    while let Some(line) = read_message(&mut reader, framing).await.context("读取输入失败")? {
+        // 回收已完成的请求，长时间运行的会话中 JoinSet 不会无限增长
+        while tasks.try_join_next().is_some() {}
+
+        let message: Value = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                let error = RpcError::new(PARSE_ERROR, format!("无效的 JSON: {}", e));
+                let _ = sender.send(response(Value::Null, Err(error)));
+                continue;
+            }
+        };
+
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(|m| m.as_str()).map(str::to_string) else {
+            // 不处理客户端发来的响应
+            if message.get("result").is_none() && message.get("error").is_none() {
+                let error = RpcError::new(INVALID_REQUEST, "缺少 method");
+                let _ = sender.send(response(id.unwrap_or(Value::Null), Err(error)));
+            }
+            continue;
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        match id {
+            None if method == "$/cancelRequest" || method == "notifications/cancelled" => {
+                let target = params.get("id").or_else(|| params.get("requestId"));
+                if let Some(cancel) = target.and_then(|id| inflight.lock().unwrap().remove(&id.to_string())) {
+                    let _ = cancel.send(());
+                }
+            }
+            None => handler.notify(&method, params).await,
+            Some(id) => {
+                let (cancel, cancelled) = oneshot::channel();
+                inflight.lock().unwrap().insert(id.to_string(), cancel);
+
+                let handler = handler.clone();
+                let sender = sender.clone();
+                let inflight = inflight.clone();
+                tasks.spawn(async move {
+                    let result = tokio::select! {
+                        result = handler.handle(&method, params) => result,
+                        _ = cancelled => Err(RpcError::new(REQUEST_CANCELLED, "请求已取消")),
+                    };
+                    inflight.lock().unwrap().remove(&id.to_string());
+                    let _ = sender.send(response(id, result));
+                });
+            }
+        }
+    }
+
+    // 标准输入关闭后等待进行中的请求完成
+    while tasks.join_next().await.is_some() {}
+    drop(sender);
+    writer.await.context("输出任务异常退出")?.context("写入输出失败")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::io::{duplex, split, DuplexStream, ReadHalf, WriteHalf};
+
+    type ClientReader = BufReader<ReadHalf<DuplexStream>>;
+    type ClientWriter = WriteHalf<DuplexStream>;
+
+    /// echo 原样返回参数，sleep 一直等待直到被取消
+    struct TestHandler;
+
+    #[async_trait]
+    impl Handler for TestHandler {
+        async fn handle(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+            match method {
+                "echo" => Ok(params),
+                "sleep" => {
+                    tokio::time::sleep(Duration::from_secs(3600)).await;
+                    Ok(Value::Null)
+                }
+                _ => Err(RpcError::method_not_found(method)),
+            }
+        }
+    }
+
+    fn start(framing: Framing) -> (ClientReader, ClientWriter, tokio::task::JoinHandle<Result<()>>) {
+        let (client, server) = duplex(4096);
+        let (reader, writer) = split(server);
+        let session = tokio::spawn(serve(Arc::new(TestHandler), framing, reader, writer));
+        let (reader, writer) = split(client);
+        (BufReader::new(reader), writer, session)
+    }
+
+    async fn next_line(reader: &mut ClientReader) -> Value {
+        let mut line = String::new();
+        tokio::time::timeout(Duration::from_secs(5), reader.read_line(&mut line)).await.unwrap().unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    /// 请求并发处理，逐条发送并等待响应以固定顺序
+    async fn exchange(reader: &mut ClientReader, writer: &mut ClientWriter, request: &str) -> Value {
+        writer.write_all(request.as_bytes()).await.unwrap();
+        next_line(reader).await
+    }
+
+    #[tokio::test]
+    async fn answers_requests_and_reports_errors() {
+        let (mut reader, mut writer, _) = start(Framing::Lines);
+
+        let request = "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"echo\",\"params\":{\"a\":1}}\n\n";
+        let response = exchange(&mut reader, &mut writer, request).await;
+        assert_eq!(response, json!({ "jsonrpc": "2.0", "id": 1, "result": { "a": 1 } }));
+
+        let response = exchange(&mut reader, &mut writer, "not json\n").await;
+        assert_eq!(response["id"], Value::Null);
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+
+        let request = "{\"jsonrpc\":\"2.0\",\"id\":\"x\",\"method\":\"nope\"}\n";
+        let response = exchange(&mut reader, &mut writer, request).await;
+        assert_eq!(response["id"], "x");
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = exchange(&mut reader, &mut writer, "{\"jsonrpc\":\"2.0\",\"id\":2}\n").await;
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn cancels_inflight_requests() {
+        let (mut reader, mut writer, _) = start(Framing::Lines);
+
+        writer.write_all(b"{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"sleep\"}\n").await.unwrap();
+        let cancel = "{\"jsonrpc\":\"2.0\",\"method\":\"$/cancelRequest\",\"params\":{\"id\":7}}\n";
+        let response = exchange(&mut reader, &mut writer, cancel).await;
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["error"]["code"], REQUEST_CANCELLED);
+
+        // MCP 的取消通知使用 requestId
+        writer.write_all(b"{\"jsonrpc\":\"2.0\",\"id\":\"s\",\"method\":\"sleep\"}\n").await.unwrap();
+        let cancel = "{\"jsonrpc\":\"2.0\",\"method\":\"notifications/cancelled\",\"params\":{\"requestId\":\"s\"}}\n";
+        let response = exchange(&mut reader, &mut writer, cancel).await;
+        assert_eq!(response["id"], "s");
+        assert_eq!(response["error"]["code"], REQUEST_CANCELLED);
+    }
+
+    #[tokio::test]
+    async fn reads_and_writes_content_length_frames() {
+        let (mut reader, mut writer, _) = start(Framing::Headers);
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"echo","params":"你好"}"#;
+        let request = format!("Content-Length: {}\r\nContent-Type: application/vscode-jsonrpc\r\n\r\n{}", body.len(), body);
+        writer.write_all(request.as_bytes()).await.unwrap();
+
+        let mut header = String::new();
+        reader.read_line(&mut header).await.unwrap();
+        let length: usize = header.strip_prefix("Content-Length: ").unwrap().trim_end().parse().unwrap();
+        let mut blank = String::new();
+        reader.read_line(&mut blank).await.unwrap();
+        assert_eq!(blank, "\r\n");
+
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).await.unwrap();
+        let response: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(response, json!({ "jsonrpc": "2.0", "id": 1, "result": "你好" }));
+    }
+
+    #[tokio::test]
+    async fn finishes_pending_requests_after_input_closes() {
+        let (mut reader, mut writer, session) = start(Framing::Lines);
+        writer.write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"echo\"}\n").await.unwrap();
+        writer.shutdown().await.unwrap();
+
+        assert_eq!(next_line(&mut reader).await["id"], 1);
+        session.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,185 @@
+use axum::extract::rejection::JsonRejection;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use super::{check_bearer, ApiError, ServerState};
+use crate::translator::Translator;
+
+/// fanyi 自身的接口: POST /translate、POST /detect、GET /languages
+pub fn routes<T: Translator + 'static>(state: Arc<ServerState<T>>) -> Router {
+    Router::new()
+        .route("/translate", post(translate::<T>))
+        .route("/detect", post(detect::<T>))
+        .route("/languages", get(languages::<T>))
+        .with_state(state)
+}
+
+/// 单段文本或多段文本
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Texts {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct TranslateRequest {
+    text: Texts,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DetectRequest {
+    text: String,
+}
+
+#[derive(Serialize)]
+struct LanguageInfo {
+    /// BCP-47 标签
+    code: &'static str,
+    /// 服务商使用的代码
+    provider_code: &'static str,
+    name: &'static str,
+    name_en: &'static str,
+}
+
+/// 单段文本返回翻译结果，多段文本返回 {"translations": [...]}
+async fn translate<T: Translator>(
+    State(state): State<Arc<ServerState<T>>>,
+    headers: HeaderMap,
+    request: Result<Json<TranslateRequest>, JsonRejection>,
+) -> Result<Json<Value>, ApiError> {
+    check_bearer(&state, &headers)?;
+    let Json(request) = request.map_err(|e| ApiError::bad_request(e.body_text()))?;
+    let from = request.from.unwrap_or_else(|| state.default_from.clone());
+    let to = request.to.unwrap_or_else(|| state.default_to.clone());
+    state.check_languages(&from, &to)?;
+
+    match request.text {
+        Texts::One(text) => {
+            if text.trim().is_empty() {
+                return Err(ApiError::bad_request("要翻译的文本不能为空"));
+            }
+            let result = state.translator.translate(&text, &from, &to).await.map_err(ApiError::upstream)?;
+            Ok(Json(json!(result)))
+        }
+        Texts::Many(texts) => {
+            let translations = state
+                .translator
+                .translate_batch(&texts, &from, &to)
+                .await
+                .map_err(ApiError::upstream)?;
+            Ok(Json(json!({ "from": from, "to": to, "translations": translations })))
+        }
+    }
+}
+
+async fn detect<T: Translator>(
+    State(state): State<Arc<ServerState<T>>>,
+    headers: HeaderMap,
+    request: Result<Json<DetectRequest>, JsonRejection>,
+) -> Result<Json<Value>, ApiError> {
+    check_bearer(&state, &headers)?;
+    let Json(request) = request.map_err(|e| ApiError::bad_request(e.body_text()))?;
+    if request.text.trim().is_empty() {
+        return Err(ApiError::bad_request("要检测的文本不能为空"));
+    }
+    let language = state.translator.detect(&request.text).await.map_err(ApiError::upstream)?;
+    Ok(Json(json!({ "language": language })))
+}
+
+async fn languages<T: Translator>(
+    State(state): State<Arc<ServerState<T>>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<LanguageInfo>>, ApiError> {
+    check_bearer(&state, &headers)?;
+    let provider = state.translator.provider();
+    let languages = state
+        .translator
+        .supported_languages()
+        .into_iter()
+        .map(|lang| LanguageInfo {
+            code: lang.bcp47,
+            provider_code: lang.code_for(provider).unwrap_or_default(),
+            name: lang.name_zh,
+            name_en: lang.name_en,
+        })
+        .collect();
+    Ok(Json(languages))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::server::tests::{client, spawn};
+    use crate::server::Compat;
+    use serde_json::{json, Value};
+
+    #[tokio::test]
+    async fn translates_single_and_batched_texts() {
+        let base = spawn(Compat::Fanyi, None).await;
+        let response: Value = client()
+            .post(format!("{}/translate", base))
+            .json(&json!({ "text": "hello", "from": "en" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(response["target"], "HELLO");
+        assert_eq!(response["to"], "zh");
+
+        let response: Value = client()
+            .post(format!("{}/translate", base))
+            .json(&json!({ "text": ["a", "b"], "to": "ja" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(response, json!({ "from": "auto", "to": "ja", "translations": ["A", "B"] }));
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_requests_and_missing_tokens() {
+        let base = spawn(Compat::Fanyi, Some("secret")).await;
+        let unauthorized = client()
+            .post(format!("{}/translate", base))
+            .json(&json!({ "text": "hello" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unauthorized.status(), 401);
+
+        let request = |body: Value| client().post(format!("{}/translate", base)).bearer_auth("secret").json(&body);
+        assert_eq!(request(json!({ "text": "hello", "to": "klingonese" })).send().await.unwrap().status(), 400);
+        assert_eq!(request(json!({ "text": " " })).send().await.unwrap().status(), 400);
+        assert_eq!(request(json!({ "text": "hello" })).send().await.unwrap().status(), 200);
+    }
+
+    #[tokio::test]
+    async fn detects_and_lists_languages() {
+        let base = spawn(Compat::Fanyi, None).await;
+        let response: Value = client()
+            .post(format!("{}/detect", base))
+            .json(&json!({ "text": "hello" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(response, json!({ "language": "en" }));
+
+        let languages: Vec<Value> = client().get(format!("{}/languages", base)).send().await.unwrap().json().await.unwrap();
+        let japanese = languages.iter().find(|l| l["code"] == "ja").unwrap();
+        assert_eq!(japanese["provider_code"], "jp");
+    }
+}
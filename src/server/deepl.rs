@@ -0,0 +1,266 @@
+use axum::body::Bytes;
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+use super::{parse_body, ApiError, ServerState};
+use crate::language::{registry, Language};
+use crate::translator::Translator;
+
+/// DeepL v2 兼容接口
+///
+/// 参数可以是 JSON 或表单（`text` 可重复出现），访问令牌通过
+/// `Authorization: DeepL-Auth-Key <token>` 请求头或 `auth_key` 参数传递
+pub fn routes<T: Translator + 'static>(state: Arc<ServerState<T>>) -> Router {
+    Router::new()
+        .route("/v2/translate", post(translate::<T>))
+        .route("/v2/languages", get(languages::<T>).post(languages::<T>))
+        .with_state(state)
+}
+
+/// DeepL 的错误响应为 {"message": "..."}
+struct DeepLError(ApiError);
+
+impl From<ApiError> for DeepLError {
+    fn from(error: ApiError) -> Self {
+        Self(error)
+    }
+}
+
+impl IntoResponse for DeepLError {
+    fn into_response(self) -> Response {
+        (self.0.status, Json(json!({ "message": self.0.message }))).into_response()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Texts {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct TranslateRequest {
+    text: Texts,
+    source_lang: Option<String>,
+    target_lang: String,
+    auth_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LanguagesQuery {
+    /// source 或 target，默认为 source
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    auth_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Translation {
+    detected_source_language: String,
+    text: String,
+}
+
+#[derive(Serialize)]
+struct LanguageInfo {
+    language: String,
+    name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    supports_formality: Option<bool>,
+}
+
+/// DeepL 的语言代码：大写的 ISO 639-1 代码，繁体中文为 ZH-HANT
+///
+/// DeepL 的代码（EN-US、PT-BR、ZH-HANS 等）可以直接由语言表解析，无需反向转换
+fn code_of(lang: &Language) -> String {
+    match lang.bcp47 {
+        "zh-Hant" => "ZH-HANT".to_string(),
+        _ => lang.iso639_1.unwrap_or(lang.bcp47).to_uppercase(),
+    }
+}
+
+/// 将语言表能识别的语言转换为 DeepL 代码，无法识别时原样转为大写
+fn deepl_code(input: &str) -> String {
+    registry::resolve(input).map_or_else(|| input.to_uppercase(), code_of)
+}
+
+fn check_auth_key<T>(state: &ServerState<T>, headers: &HeaderMap, auth_key: Option<&str>) -> Result<(), DeepLError> {
+    let Some(token) = &state.token else {
+        return Ok(());
+    };
+    let header_key = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("DeepL-Auth-Key ").or_else(|| v.strip_prefix("Bearer ")));
+    if header_key.or(auth_key) == Some(token.as_str()) {
+        Ok(())
+    } else {
+        Err(ApiError::forbidden("Authorization failed. Please supply a valid auth_key parameter.").into())
+    }
+}
+
+async fn translate<T: Translator>(
+    State(state): State<Arc<ServerState<T>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<serde_json::Value>, DeepLError> {
+    let request: TranslateRequest = serde_json::from_value(parse_body(&headers, &body)?)
+        .map_err(|e| ApiError::bad_request(format!("Bad request. Reason: {}", e)))?;
+    check_auth_key(&state, &headers, request.auth_key.as_deref())?;
+
+    let from = request.source_lang.unwrap_or_else(|| "auto".to_string());
+    let to = request.target_lang;
+    state.check_languages(&from, &to)?;
+
+    let texts = match request.text {
+        Texts::One(text) => vec![text],
+        Texts::Many(texts) => texts,
+    };
+    if texts.is_empty() {
+        return Err(ApiError::bad_request("Bad request. Reason: Parameter 'text' not specified.").into());
+    }
+
+    let mut translations = Vec::with_capacity(texts.len());
+    if from == "auto" {
+        // 逐条翻译以得到每条的检测结果
+        for text in &texts {
+            if text.trim().is_empty() {
+                translations.push(Translation {
+                    detected_source_language: String::new(),
+                    text: text.clone(),
+                });
+                continue;
+            }
+            let result = state.translator.translate(text, &from, &to).await.map_err(ApiError::upstream)?;
+            translations.push(Translation {
+                detected_source_language: result.detected_language.as_deref().map(deepl_code).unwrap_or_default(),
+                text: result.target,
+            });
+        }
+    } else {
+        let source = deepl_code(&from);
+        let targets = state
+            .translator
+            .translate_batch(&texts, &from, &to)
+            .await
+            .map_err(ApiError::upstream)?;
+        translations.extend(targets.into_iter().map(|text| Translation {
+            detected_source_language: source.clone(),
+            text,
+        }));
+    }
+
+    Ok(Json(json!({ "translations": translations })))
+}
+
+async fn languages<T: Translator>(
+    State(state): State<Arc<ServerState<T>>>,
+    headers: HeaderMap,
+    Query(query): Query<LanguagesQuery>,
+) -> Result<Json<Vec<LanguageInfo>>, DeepLError> {
+    check_auth_key(&state, &headers, query.auth_key.as_deref())?;
+    let is_target = query.kind.as_deref().is_some_and(|k| k.eq_ignore_ascii_case("target"));
+
+    let languages = state
+        .translator
+        .supported_languages()
+        .into_iter()
+        .map(|lang| LanguageInfo {
+            language: code_of(lang),
+            name: lang.name_en,
+            supports_formality: is_target.then_some(false),
+        })
+        .collect();
+    Ok(Json(languages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::tests::{client, spawn};
+    use crate::server::Compat;
+    use serde_json::Value;
+
+    #[test]
+    fn maps_languages_to_deepl_codes() {
+        assert_eq!(deepl_code("zh-Hant"), "ZH-HANT");
+        assert_eq!(deepl_code("zh-tw"), "ZH-HANT");
+        assert_eq!(deepl_code("en-US"), "EN");
+        assert_eq!(deepl_code("jp"), "JA");
+        assert_eq!(deepl_code("xx"), "XX");
+    }
+
+    #[tokio::test]
+    async fn translates_repeated_form_texts_with_auth_header() {
+        let base = spawn(Compat::Deepl, Some("secret")).await;
+        let response: Value = client()
+            .post(format!("{}/v2/translate", base))
+            .header("Authorization", "DeepL-Auth-Key secret")
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body("text=hello&text=world&source_lang=EN&target_lang=ZH-HANT")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(
+            response,
+            json!({ "translations": [
+                { "detected_source_language": "EN", "text": "HELLO" },
+                { "detected_source_language": "EN", "text": "WORLD" },
+            ] })
+        );
+    }
+
+    #[tokio::test]
+    async fn detects_sources_and_accepts_auth_key_param() {
+        let base = spawn(Compat::Deepl, Some("secret")).await;
+        let response: Value = client()
+            .post(format!("{}/v2/translate", base))
+            .json(&json!({ "text": ["hi", ""], "target_lang": "DE", "auth_key": "secret" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(response["translations"][0], json!({ "detected_source_language": "EN", "text": "HI" }));
+        assert_eq!(response["translations"][1], json!({ "detected_source_language": "", "text": "" }));
+
+        let response = client()
+            .post(format!("{}/v2/translate", base))
+            .header("Authorization", "DeepL-Auth-Key wrong")
+            .json(&json!({ "text": "hi", "target_lang": "DE" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 403);
+        let error: Value = response.json().await.unwrap();
+        assert!(error["message"].as_str().unwrap().starts_with("Authorization failed"));
+    }
+
+    #[tokio::test]
+    async fn lists_target_languages() {
+        let base = spawn(Compat::Deepl, None).await;
+        let languages: Vec<Value> = client()
+            .get(format!("{}/v2/languages?type=target", base))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let traditional = languages.iter().find(|l| l["language"] == "ZH-HANT").unwrap();
+        assert_eq!(traditional["supports_formality"], false);
+
+        let sources: Vec<Value> = client().get(format!("{}/v2/languages", base)).send().await.unwrap().json().await.unwrap();
+        assert!(sources.iter().all(|l| l.get("supports_formality").is_none()));
+    }
+}
@@ -0,0 +1,267 @@
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use super::{check_bearer, parse_body, ApiError, ServerState};
+use crate::language::{registry, Language};
+use crate::translator::Translator;
+
+/// 服务商不返回检测置信度，统一报告此值
+const DETECTED_CONFIDENCE: f64 = 90.0;
+
+/// LibreTranslate 兼容接口
+///
+/// 请求参数可以是 JSON 或表单，访问令牌通过 `api_key` 参数传递
+pub fn routes<T: Translator + 'static>(state: Arc<ServerState<T>>) -> Router {
+    Router::new()
+        .route("/translate", post(translate::<T>))
+        .route("/detect", post(detect::<T>))
+        .route("/languages", get(languages::<T>))
+        .with_state(state)
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Query {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct TranslateRequest {
+    q: Query,
+    #[serde(default = "auto")]
+    source: String,
+    target: String,
+    api_key: Option<String>,
+    /// 请求的候选译文数量，可以是数字或表单中的字符串
+    alternatives: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct DetectRequest {
+    q: String,
+    api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Detection {
+    confidence: f64,
+    language: String,
+}
+
+#[derive(Serialize)]
+struct LanguageInfo {
+    code: &'static str,
+    name: &'static str,
+    targets: Vec<&'static str>,
+}
+
+fn auto() -> String {
+    "auto".to_string()
+}
+
+/// LibreTranslate 的语言代码：繁体中文为 zt，其余优先使用 ISO 639-1
+fn code_of(lang: &Language) -> &'static str {
+    match lang.bcp47 {
+        "zh-Hant" => "zt",
+        _ => lang.iso639_1.unwrap_or(lang.bcp47),
+    }
+}
+
+/// 将 LibreTranslate 的语言代码转换为语言表可以识别的写法
+fn from_code(code: &str) -> String {
+    match code.trim() {
+        "zt" => "zh-Hant".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// 将检测到的 BCP-47 标签转换为 LibreTranslate 的语言代码
+fn detection(tag: &str) -> Detection {
+    Detection {
+        confidence: DETECTED_CONFIDENCE,
+        language: registry::resolve(tag).map_or_else(|| tag.to_string(), |l| code_of(l).to_string()),
+    }
+}
+
+/// LibreTranslate 通过 api_key 参数传递令牌，同时兼容 Bearer 请求头
+fn check_api_key<T>(state: &ServerState<T>, headers: &HeaderMap, api_key: Option<&str>) -> Result<(), ApiError> {
+    match (&state.token, api_key) {
+        (Some(token), Some(key)) if key == token => Ok(()),
+        (Some(_), _) => check_bearer(state, headers).map_err(|_| ApiError::forbidden("Invalid API key")),
+        (None, _) => Ok(()),
+    }
+}
+
+async fn translate<T: Translator>(
+    State(state): State<Arc<ServerState<T>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<Value>, ApiError> {
+    let request: TranslateRequest =
+        serde_json::from_value(parse_body(&headers, &body)?).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    check_api_key(&state, &headers, request.api_key.as_deref())?;
+
+    let (from, to) = (from_code(&request.source), from_code(&request.target));
+    state.check_languages(&from, &to)?;
+
+    let mut response = match request.q {
+        Query::One(text) => {
+            if text.trim().is_empty() {
+                return Err(ApiError::bad_request("Invalid request: missing q parameter"));
+            }
+            let result = state.translator.translate(&text, &from, &to).await.map_err(ApiError::upstream)?;
+            let mut response = json!({ "translatedText": result.target });
+            if let Some(tag) = result.detected_language {
+                response["detectedLanguage"] = json!(detection(&tag));
+            }
+            response
+        }
+        // 自动检测时逐条翻译以得到每条的检测结果，否则批量提交
+        Query::Many(texts) if from == "auto" => {
+            let mut translations = Vec::with_capacity(texts.len());
+            let mut detections = Vec::with_capacity(texts.len());
+            for text in &texts {
+                if text.trim().is_empty() {
+                    translations.push(text.clone());
+                    detections.push(None);
+                    continue;
+                }
+                let result = state.translator.translate(text, &from, &to).await.map_err(ApiError::upstream)?;
+                translations.push(result.target);
+                detections.push(result.detected_language.as_deref().map(detection));
+            }
+            json!({ "translatedText": translations, "detectedLanguage": detections })
+        }
+        Query::Many(texts) => {
+            let translations = state
+                .translator
+                .translate_batch(&texts, &from, &to)
+                .await
+                .map_err(ApiError::upstream)?;
+            json!({ "translatedText": translations })
+        }
+    };
+
+    // 服务商只返回一个译文，请求候选译文时返回空列表
+    if request.alternatives.is_some() {
+        response["alternatives"] = json!([]);
+    }
+    Ok(Json(response))
+}
+
+async fn detect<T: Translator>(
+    State(state): State<Arc<ServerState<T>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<Vec<Detection>>, ApiError> {
+    let request: DetectRequest =
+        serde_json::from_value(parse_body(&headers, &body)?).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    check_api_key(&state, &headers, request.api_key.as_deref())?;
+    if request.q.trim().is_empty() {
+        return Err(ApiError::bad_request("Invalid request: missing q parameter"));
+    }
+
+    let tag = state.translator.detect(&request.q).await.map_err(ApiError::upstream)?;
+    Ok(Json(vec![detection(&tag)]))
+}
+
+/// 每种语言都可以翻译为其他任意支持的语言
+async fn languages<T: Translator>(State(state): State<Arc<ServerState<T>>>) -> Json<Vec<LanguageInfo>> {
+    let languages = state.translator.supported_languages();
+    let codes: Vec<&'static str> = languages.iter().map(|l| code_of(l)).collect();
+    Json(
+        languages
+            .iter()
+            .map(|lang| LanguageInfo {
+                code: code_of(lang),
+                name: lang.name_en,
+                targets: codes.iter().copied().filter(|c| *c != code_of(lang)).collect(),
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::tests::{client, spawn};
+    use crate::server::Compat;
+
+    #[test]
+    fn maps_traditional_chinese_to_zt() {
+        assert_eq!(from_code("zt"), "zh-Hant");
+        assert_eq!(from_code(" en "), "en");
+        assert_eq!(code_of(registry::resolve("zh-Hant").unwrap()), "zt");
+        assert_eq!(code_of(registry::resolve("ja").unwrap()), "ja");
+        assert_eq!(detection("zh-Hant").language, "zt");
+    }
+
+    #[tokio::test]
+    async fn accepts_form_and_json_with_api_key() {
+        let base = spawn(Compat::Libretranslate, Some("secret")).await;
+        let response: Value = client()
+            .post(format!("{}/translate", base))
+            .form(&[("q", "hello"), ("source", "en"), ("target", "zt"), ("api_key", "secret"), ("alternatives", "3")])
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(response, json!({ "translatedText": "HELLO", "alternatives": [] }));
+
+        let response: Value = client()
+            .post(format!("{}/translate", base))
+            .body("q=one&q=two&target=de&api_key=secret")
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(response["translatedText"], json!(["ONE", "TWO"]));
+        assert_eq!(response["detectedLanguage"][0], json!({ "confidence": 90.0, "language": "en" }));
+
+        let response: Value = client()
+            .post(format!("{}/translate", base))
+            .json(&json!({ "q": ["a", "b"], "source": "en", "target": "zh", "api_key": "secret" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(response, json!({ "translatedText": ["A", "B"] }));
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_api_keys_but_accepts_bearer() {
+        let base = spawn(Compat::Libretranslate, Some("secret")).await;
+        let body = json!({ "q": "hello", "target": "zh", "api_key": "wrong" });
+        let response = client().post(format!("{}/translate", base)).json(&body).send().await.unwrap();
+        assert_eq!(response.status(), 403);
+
+        let body = json!({ "q": "hello" });
+        let response = client().post(format!("{}/detect", base)).bearer_auth("secret").json(&body).send().await.unwrap();
+        let detections: Value = response.json().await.unwrap();
+        assert_eq!(detections, json!([{ "confidence": 90.0, "language": "en" }]));
+    }
+
+    #[tokio::test]
+    async fn lists_languages_with_targets() {
+        let base = spawn(Compat::Libretranslate, None).await;
+        let languages: Vec<Value> = client().get(format!("{}/languages", base)).send().await.unwrap().json().await.unwrap();
+        let traditional = languages.iter().find(|l| l["code"] == "zt").unwrap();
+        let targets = traditional["targets"].as_array().unwrap();
+        assert!(targets.contains(&json!("en")));
+        assert!(!targets.contains(&json!("zt")));
+    }
+}
@@ -0,0 +1,228 @@
+mod api;
+mod deepl;
+mod libretranslate;
+
+use anyhow::{Context, Result};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use serde_json::{json, Map, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use crate::language::{validate_source, validate_target};
+use crate::translator::Translator;
+
+/// 服务对外提供的接口风格
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Compat {
+    /// fanyi 自身的接口
+    Fanyi,
+    /// LibreTranslate 兼容接口 (/translate、/detect、/languages)
+    Libretranslate,
+    /// DeepL v2 兼容接口 (/v2/translate、/v2/languages)
+    Deepl,
+}
+
+/// 服务共享的状态
+pub struct ServerState<T> {
+    pub translator: T,
+    /// 客户端需要提供的访问令牌，为空时不校验
+    pub token: Option<String>,
+    /// 请求未指定语言时使用的默认值
+    pub default_from: String,
+    pub default_to: String,
+}
+
+impl<T: Translator> ServerState<T> {
+    /// 校验请求中的语言参数，返回错误时给出可直接返回给客户端的说明
+    fn check_languages(&self, from: &str, to: &str) -> Result<(), ApiError> {
+        let provider = self.translator.provider();
+        validate_source(from, provider)
+            .and_then(|_| validate_target(to, provider))
+            .map(|_| ())
+            .map_err(|e| ApiError::bad_request(e.to_string()))
+    }
+}
+
+/// 返回给客户端的错误
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.into(),
+        }
+    }
+
+    fn forbidden(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::FORBIDDEN,
+            message: message.into(),
+        }
+    }
+
+    fn unauthorized() -> Self {
+        Self {
+            status: StatusCode::UNAUTHORIZED,
+            message: "访问令牌无效".to_string(),
+        }
+    }
+
+    /// 翻译服务返回的错误
+    fn upstream(error: anyhow::Error) -> Self {
+        Self {
+            status: StatusCode::BAD_GATEWAY,
+            message: error.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
+
+/// 校验 `Authorization: Bearer <token>` 请求头
+fn check_bearer<T>(state: &ServerState<T>, headers: &HeaderMap) -> Result<(), ApiError> {
+    let Some(token) = &state.token else {
+        return Ok(());
+    };
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    if provided == Some(token.as_str()) {
+        Ok(())
+    } else {
+        Err(ApiError::unauthorized())
+    }
+}
+
+/// 解析 JSON 或 application/x-www-form-urlencoded 请求体
+///
+/// 表单统一转换为 JSON 对象，重复出现的参数合并为数组
+fn parse_body(headers: &HeaderMap, body: &[u8]) -> Result<Value, ApiError> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if !content_type.starts_with("application/x-www-form-urlencoded") {
+        return serde_json::from_slice(body).map_err(|e| ApiError::bad_request(format!("请求体不是有效的 JSON: {}", e)));
+    }
+
+    let mut object = Map::new();
+    for (key, value) in form_urlencoded::parse(body) {
+        let value = Value::String(value.into_owned());
+        match object.get_mut(key.as_ref()) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+            None => {
+                object.insert(key.into_owned(), value);
+            }
+        }
+    }
+    Ok(Value::Object(object))
+}
+
+/// 按接口风格创建路由
+fn router<T: Translator + 'static>(compat: Compat, state: Arc<ServerState<T>>) -> Router {
+    match compat {
+        Compat::Fanyi => api::routes(state),
+        Compat::Libretranslate => libretranslate::routes(state),
+        Compat::Deepl => deepl::routes(state),
+    }
+}
+
+/// 启动 HTTP 服务，直到收到 Ctrl-C
+pub async fn serve<T: Translator + 'static>(listen: &str, compat: Compat, state: ServerState<T>) -> Result<()> {
+    let addr: SocketAddr = listen
+        .parse()
+        .with_context(|| format!("无效的监听地址: {} (例如 127.0.0.1:8787)", listen))?;
+    let server = axum::Server::try_bind(&addr)
+        .with_context(|| format!("监听 {} 失败", addr))?
+        .serve(router(compat, Arc::new(state)).into_make_service());
+    eprintln!("翻译服务已启动: http://{}", server.local_addr());
+
+    server
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+        .context("HTTP 服务异常退出")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::stub::UppercaseTranslator;
+
+    /// 在随机端口上启动使用测试翻译器的服务，返回服务地址
+    pub(super) async fn spawn(compat: Compat, token: Option<&str>) -> String {
+        let state = ServerState {
+            translator: UppercaseTranslator,
+            token: token.map(str::to_string),
+            default_from: "auto".to_string(),
+            default_to: "zh".to_string(),
+        };
+        let server = axum::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0)))
+            .serve(router(compat, Arc::new(state)).into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        format!("http://{}", addr)
+    }
+
+    /// 不使用系统代理的 HTTP 客户端
+    pub(super) fn client() -> reqwest::Client {
+        reqwest::Client::builder().no_proxy().build().unwrap()
+    }
+
+    fn headers(content_type: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, content_type.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn form_bodies_merge_repeated_params() {
+        let body = parse_body(
+            &headers("application/x-www-form-urlencoded; charset=utf-8"),
+            b"text=one&text=two+words&text=%E4%B8%89&target_lang=DE",
+        )
+        .ok()
+        .unwrap();
+        assert_eq!(body, json!({ "text": ["one", "two words", "三"], "target_lang": "DE" }));
+    }
+
+    #[test]
+    fn json_bodies_are_parsed_as_is() {
+        let body = parse_body(&headers("application/json"), br#"{"text": ["a"], "to": "en"}"#).ok().unwrap();
+        assert_eq!(body, json!({ "text": ["a"], "to": "en" }));
+
+        let error = parse_body(&HeaderMap::new(), b"text=a").err().unwrap();
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn bearer_token_is_checked_only_when_configured() {
+        let state = |token: Option<&str>| ServerState {
+            translator: UppercaseTranslator,
+            token: token.map(str::to_string),
+            default_from: "auto".to_string(),
+            default_to: "zh".to_string(),
+        };
+        let mut headers = HeaderMap::new();
+        assert!(check_bearer(&state(None), &headers).is_ok());
+        assert!(check_bearer(&state(Some("secret")), &headers).is_err());
+
+        headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert!(check_bearer(&state(Some("secret")), &headers).is_ok());
+        headers.insert(header::AUTHORIZATION, "Bearer other".parse().unwrap());
+        assert_eq!(check_bearer(&state(Some("secret")), &headers).err().unwrap().status, StatusCode::UNAUTHORIZED);
+    }
+}
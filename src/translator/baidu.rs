@@ -5,13 +5,26 @@ use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::config::{BaiduConfig, ProxyConfig};
-use crate::translator::trait_def::{Translator, TranslationResult};
+use crate::language::{registry, Provider};
+use crate::translator::image::{BoundingBox, ImageTextBlock, ImageTranslationResult};
+use crate::translator::rate_limit::RateLimiter;
+use crate::translator::trait_def::{Definition, DictionaryEntry, Phonetic, Translator, TranslationResult};
+
+/// 图片翻译接口要求的设备标识，API调用固定为 APICUID
+const PICTURE_CUID: &str = "APICUID";
+/// 图片翻译接口要求的mac参数，API调用固定为 mac
+const PICTURE_MAC: &str = "mac";
+/// 图片翻译接口允许的最大图片大小 (4MB)
+const PICTURE_MAX_BYTES: usize = 4 * 1024 * 1024;
+/// 批量翻译时单次请求的最大字节数，百度建议单次请求不超过6000字节
+const BATCH_MAX_BYTES: usize = 6000;
 
 /// 百度翻译API响应结构
 #[derive(Debug, Deserialize)]
 struct BaiduResponse {
+    #[serde(default)]
     from: String,
-    to: String,
+    #[serde(default)]
     trans_result: Vec<TransResult>,
     #[serde(default)]
     error_code: Option<String>,
@@ -23,12 +36,137 @@ struct BaiduResponse {
 struct TransResult {
     src: String,
     dst: String,
+    /// 词典资源 (请求参数 dict=1 时返回)，通常为 JSON 字符串
+    #[serde(default)]
+    dict: Option<serde_json::Value>,
+}
+
+/// 百度图片翻译API响应结构
+#[derive(Debug, Deserialize)]
+struct BaiduPictureResponse {
+    #[serde(default)]
+    error_code: Option<String>,
+    #[serde(default)]
+    error_msg: Option<String>,
+    #[serde(default)]
+    data: Option<PictureData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PictureData {
+    from: String,
+    to: String,
+    #[serde(default)]
+    content: Vec<PictureContent>,
+    #[serde(default, rename = "sumSrc")]
+    sum_src: String,
+    #[serde(default, rename = "sumDst")]
+    sum_dst: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PictureContent {
+    src: String,
+    dst: String,
+    #[serde(default)]
+    rect: String,
+    #[serde(default, rename = "lineCount")]
+    line_count: u32,
+}
+
+/// 批量翻译时拆分出的一行
+enum BatchLine<'a> {
+    /// 空白行，原样保留
+    Verbatim(&'a str),
+    /// 需要翻译的行，保留首尾空白
+    Query {
+        prefix: &'a str,
+        suffix: &'a str,
+        index: usize,
+    },
+}
+
+/// 将多行查询按字节数上限分组，每组拼接后不超过 `max_bytes`（单行超长时独占一组）
+fn chunk_queries<'a>(queries: &[&'a str], max_bytes: usize) -> Vec<Vec<&'a str>> {
+    let mut chunks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut current_bytes = 0;
+
+    for query in queries {
+        let added = query.len() + usize::from(!current.is_empty());
+        if !current.is_empty() && current_bytes + added > max_bytes {
+            chunks.push(std::mem::take(&mut current));
+            current_bytes = 0;
+        }
+        current_bytes += query.len() + usize::from(!current.is_empty());
+        current.push(query);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// 解析百度词典资源中的音标和释义
+///
+/// 英文单词的释义为字符串列表，中文词语的释义为包含 text 的对象
+fn parse_dictionary(dict: &serde_json::Value) -> (Vec<Phonetic>, Vec<Definition>) {
+    let mut phonetics = Vec::new();
+    let mut definitions = Vec::new();
+    let symbols = dict
+        .pointer("/word_result/simple_means/symbols")
+        .and_then(|s| s.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    for symbol in symbols {
+        for (key, accent) in [("ph_en", "uk"), ("ph_am", "us"), ("word_symbol", "pinyin")] {
+            if let Some(text) = symbol.get(key).and_then(|v| v.as_str()).filter(|t| !t.is_empty()) {
+                phonetics.push(Phonetic {
+                    accent: accent.to_string(),
+                    text: text.to_string(),
+                });
+            }
+        }
+
+        let parts = symbol.get("parts").and_then(|p| p.as_array()).map(Vec::as_slice).unwrap_or_default();
+        for part in parts {
+            let part_of_speech = part
+                .get("part")
+                .or_else(|| part.get("part_name"))
+                .and_then(|p| p.as_str())
+                .unwrap_or_default();
+            let meanings: Vec<String> = part
+                .get("means")
+                .and_then(|m| m.as_array())
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|mean| {
+                    mean.as_str()
+                        .or_else(|| mean.get("text").and_then(|t| t.as_str()))
+                        .or_else(|| mean.get("word_mean").and_then(|t| t.as_str()))
+                })
+                .map(str::to_string)
+                .collect();
+            if !meanings.is_empty() {
+                definitions.push(Definition {
+                    part_of_speech: part_of_speech.to_string(),
+                    meanings,
+                });
+            }
+        }
+    }
+
+    (phonetics, definitions)
 }
 
 /// 百度翻译器
 pub struct BaiduTranslator {
     config: BaiduConfig,
     client: reqwest::Client,
+    rate_limiter: RateLimiter,
 }
 
 impl BaiduTranslator {
@@ -62,7 +200,9 @@ impl BaiduTranslator {
             .build()
             .expect("创建HTTP客户端失败");
         
-        Self { config, client }
+        let rate_limiter = RateLimiter::new(config.qps);
+
+        Self { config, client, rate_limiter }
     }
 
     /// 生成百度翻译API签名
@@ -71,6 +211,16 @@ impl BaiduTranslator {
         format!("{:x}", md5::compute(sign_str.as_bytes()))
     }
 
+    /// 生成图片翻译API签名: md5(appid + md5(image) + salt + cuid + mac + secret_key)
+    fn generate_picture_sign(&self, image: &[u8], salt: &str) -> String {
+        let image_md5 = format!("{:x}", md5::compute(image));
+        let sign_str = format!(
+            "{}{}{}{}{}{}",
+            self.config.app_id, image_md5, salt, PICTURE_CUID, PICTURE_MAC, self.config.secret_key
+        );
+        format!("{:x}", md5::compute(sign_str.as_bytes()))
+    }
+
     /// 生成随机salt
     fn generate_salt() -> String {
         SystemTime::now()
@@ -80,20 +230,11 @@ impl BaiduTranslator {
             .to_string()
     }
 
-    /// 语言代码映射，将常用语言代码转换为百度API支持的格式
+    /// 语言代码映射，通过语言表将用户输入转换为百度API使用的代码
     fn map_language_code(&self, lang: &str) -> String {
-        match lang.to_lowercase().as_str() {
-            "zh" | "zh-cn" | "chinese" => "zh".to_string(),
-            "en" | "english" => "en".to_string(),
-            "ja" | "jp" | "japanese" => "jp".to_string(),
-            "ko" | "kr" | "korean" => "kor".to_string(),
-            "fr" | "french" => "fra".to_string(),
-            "es" | "spanish" => "spa".to_string(),
-            "de" | "german" => "de".to_string(),
-            "ru" | "russian" => "ru".to_string(),
-            "auto" => "auto".to_string(),
-            _ => lang.to_string(),
-        }
+        registry::provider_code(lang, Provider::Baidu)
+            .map(str::to_string)
+            .unwrap_or_else(|| lang.to_string())
     }
 
     /// 处理百度API错误
@@ -113,18 +254,30 @@ impl BaiduTranslator {
             "58001" => "译文语言方向不支持".to_string(),
             "58002" => "服务当前已关闭".to_string(),
             "90107" => "认证未通过或未生效".to_string(),
+            "69001" => "上传图片数据有误".to_string(),
+            "69002" => "图片识别超时".to_string(),
+            "69003" => "内容识别失败".to_string(),
+            "69004" => "识别内容为空".to_string(),
+            "69005" => "图片大小超限（超过4M）".to_string(),
+            "69006" => "图片尺寸不符合标准".to_string(),
+            "69007" => "图片格式不支持（仅支持png/jpg）".to_string(),
             _ => format!("未知错误: {}", error_code),
         }
     }
-}
 
-#[async_trait]
-impl Translator for BaiduTranslator {
-    async fn translate(&self, text: &str, from: &str, to: &str) -> Result<TranslationResult> {
-        if text.trim().is_empty() {
-            anyhow::bail!("翻译文本不能为空");
-        }
+    /// 发送文本翻译请求并检查API错误
+    async fn send_request(&self, text: &str, from: &str, to: &str) -> Result<BaiduResponse> {
+        self.send_request_with(text, from, to, &[]).await
+    }
 
+    /// 发送带有额外参数的文本翻译请求
+    async fn send_request_with(
+        &self,
+        text: &str,
+        from: &str,
+        to: &str,
+        extra: &[(&'static str, &str)],
+    ) -> Result<BaiduResponse> {
         let from_lang = self.map_language_code(from);
         let to_lang = self.map_language_code(to);
         let salt = Self::generate_salt();
@@ -138,6 +291,12 @@ impl Translator for BaiduTranslator {
         params.insert("appid", &self.config.app_id);
         params.insert("salt", &salt);
         params.insert("sign", &sign);
+        if self.config.need_intervention {
+            params.insert("needIntervention", "1");
+        }
+        params.extend(extra.iter().copied());
+
+        self.rate_limiter.acquire().await;
 
         // 发送请求
         let response = self
@@ -166,63 +325,331 @@ impl Translator for BaiduTranslator {
 
         // 检查API错误
         if let Some(error_code) = &baidu_response.error_code {
-            let error_msg = self.handle_api_error(error_code);
+            let error_msg = self.describe_api_error(error_code, baidu_response.error_msg.as_deref());
             anyhow::bail!("百度翻译API错误 ({}): {}", error_code, error_msg);
         }
 
+        Ok(baidu_response)
+    }
+
+    /// 翻译图片中的文字
+    ///
+    /// 通过百度图片翻译接口上传图片，返回识别出的各文字块及其译文
+    pub async fn translate_image(
+        &self,
+        image: Vec<u8>,
+        file_name: &str,
+        mime_type: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<ImageTranslationResult> {
+        if image.is_empty() {
+            anyhow::bail!("图片内容为空");
+        }
+        if image.len() > PICTURE_MAX_BYTES {
+            anyhow::bail!("图片大小超过4M限制");
+        }
+
+        let from_lang = self.map_language_code(from);
+        let to_lang = self.map_language_code(to);
+        let salt = Self::generate_salt();
+        let sign = self.generate_picture_sign(&image, &salt);
+
+        self.rate_limiter.acquire().await;
+
+        let image_part = reqwest::multipart::Part::bytes(image)
+            .file_name(file_name.to_string())
+            .mime_str(mime_type)
+            .context("设置图片类型失败")?;
+
+        let form = reqwest::multipart::Form::new()
+            .part("image", image_part)
+            .text("from", from_lang)
+            .text("to", to_lang)
+            .text("appid", self.config.app_id.clone())
+            .text("salt", salt)
+            .text("cuid", PICTURE_CUID)
+            .text("mac", PICTURE_MAC)
+            .text("version", "3")
+            .text("sign", sign);
+
+        let response = self
+            .client
+            .post(&self.config.picture_api_url)
+            .multipart(form)
+            .send()
+            .await
+            .with_context(|| {
+                format!(
+                    "发送图片翻译请求失败。URL: {}，请检查网络连接、代理设置和API配置",
+                    self.config.picture_api_url
+                )
+            })?;
+
+        let status = response.status();
+        let body = response.text().await.context("读取响应失败")?;
+
+        if !status.is_success() {
+            anyhow::bail!("HTTP请求失败: {} - {}", status, body);
+        }
+
+        let picture_response: BaiduPictureResponse = serde_json::from_str(&body)
+            .with_context(|| format!("解析API响应失败，响应内容: {}", body))?;
+
+        // 图片翻译接口成功时也会返回 error_code "0"
+        if let Some(error_code) = picture_response.error_code.as_deref().filter(|c| *c != "0") {
+            let error_msg = self.describe_api_error(error_code, picture_response.error_msg.as_deref());
+            anyhow::bail!("百度图片翻译API错误 ({}): {}", error_code, error_msg);
+        }
+
+        let data = picture_response.data.context("图片翻译结果为空")?;
+
+        let blocks = data
+            .content
+            .into_iter()
+            .map(|content| ImageTextBlock {
+                source: content.src,
+                target: content.dst,
+                bounding_box: BoundingBox::parse(&content.rect),
+                line_count: content.line_count,
+            })
+            .collect();
+
+        Ok(ImageTranslationResult {
+            from: registry::normalize_provider_code(Provider::Baidu, &data.from),
+            to: registry::normalize_provider_code(Provider::Baidu, &data.to),
+            blocks,
+            source: data.sum_src,
+            target: data.sum_dst,
+        })
+    }
+
+    /// 生成错误描述，未知错误码时附带API返回的原始错误信息
+    fn describe_api_error(&self, error_code: &str, error_msg: Option<&str>) -> String {
+        let description = self.handle_api_error(error_code);
+        match error_msg {
+            Some(msg) if description.starts_with("未知错误") && !msg.is_empty() => {
+                format!("{} ({})", description, msg)
+            }
+            _ => description,
+        }
+    }
+}
+
+#[async_trait]
+impl Translator for BaiduTranslator {
+    async fn translate(&self, text: &str, from: &str, to: &str) -> Result<TranslationResult> {
+        if text.trim().is_empty() {
+            anyhow::bail!("翻译文本不能为空");
+        }
+
+        let baidu_response = self.send_request(text, from, to).await?;
+
         if baidu_response.trans_result.is_empty() {
             anyhow::bail!("翻译结果为空");
         }
 
-        // 提取翻译结果
-        let trans_result = &baidu_response.trans_result[0];
+        // 百度按行返回翻译结果，需要全部拼接起来
+        let source = baidu_response
+            .trans_result
+            .iter()
+            .map(|r| r.src.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let target = baidu_response
+            .trans_result
+            .iter()
+            .map(|r| r.dst.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+
         let detected_language = if from == "auto" && baidu_response.from != from {
-            Some(baidu_response.from.clone())
+            Some(registry::normalize_provider_code(Provider::Baidu, &baidu_response.from))
         } else {
             None
         };
 
         Ok(TranslationResult {
-            source: trans_result.src.clone(),
-            target: trans_result.dst.clone(),
+            source,
+            target,
             from: from.to_string(),
             to: to.to_string(),
             detected_language,
         })
     }
 
-    fn supported_languages(&self) -> Vec<(&'static str, &'static str)> {
-        vec![
-            ("auto", "自动检测"),
-            ("zh", "中文"),
-            ("en", "英语"),
-            ("jp", "日语"),
-            ("kor", "韩语"),
-            ("fra", "法语"),
-            ("spa", "西班牙语"),
-            ("de", "德语"),
-            ("ru", "俄语"),
-            ("th", "泰语"),
-            ("ara", "阿拉伯语"),
-            ("it", "意大利语"),
-            ("pt", "葡萄牙语"),
-            ("el", "希腊语"),
-            ("nl", "荷兰语"),
-            ("pl", "波兰语"),
-            ("bul", "保加利亚语"),
-            ("est", "爱沙尼亚语"),
-            ("dan", "丹麦语"),
-            ("fin", "芬兰语"),
-            ("cs", "捷克语"),
-            ("rom", "罗马尼亚语"),
-            ("slo", "斯洛文尼亚语"),
-            ("swe", "瑞典语"),
-            ("hu", "匈牙利语"),
-            ("vie", "越南语"),
-        ]
+    async fn translate_batch(&self, texts: &[String], from: &str, to: &str) -> Result<Vec<String>> {
+        // 将所有文本拆成行，只提交非空行；百度对每一行分别返回一条结果
+        let mut lines: Vec<BatchLine> = Vec::new();
+        let mut queries: Vec<&str> = Vec::new();
+        let mut line_counts = Vec::with_capacity(texts.len());
+
+        for text in texts {
+            let text_lines: Vec<&str> = text.split('\n').collect();
+            line_counts.push(text_lines.len());
+            for line in text_lines {
+                let core = line.trim();
+                if core.is_empty() {
+                    lines.push(BatchLine::Verbatim(line));
+                } else {
+                    let start = line.len() - line.trim_start().len();
+                    lines.push(BatchLine::Query {
+                        prefix: &line[..start],
+                        suffix: &line[start + core.len()..],
+                        index: queries.len(),
+                    });
+                    queries.push(core);
+                }
+            }
+        }
+
+        let mut translated: Vec<String> = Vec::with_capacity(queries.len());
+        for chunk in chunk_queries(&queries, BATCH_MAX_BYTES) {
+            let query = chunk.join("\n");
+            let response = self.send_request(&query, from, to).await?;
+            if response.trans_result.len() != chunk.len() {
+                anyhow::bail!(
+                    "批量翻译结果数量不匹配: 提交 {} 行，返回 {} 行",
+                    chunk.len(),
+                    response.trans_result.len()
+                );
+            }
+            translated.extend(response.trans_result.into_iter().map(|r| r.dst));
+        }
+
+        // 按原有的行结构重新组装译文
+        let mut lines = lines.into_iter().map(|line| match line {
+            BatchLine::Verbatim(text) => text.to_string(),
+            BatchLine::Query { prefix, suffix, index } => {
+                format!("{}{}{}", prefix, translated[index], suffix)
+            }
+        });
+
+        Ok(line_counts
+            .into_iter()
+            .map(|count| lines.by_ref().take(count).collect::<Vec<_>>().join("\n"))
+            .collect())
+    }
+
+    async fn lookup(&self, word: &str, from: &str, to: &str) -> Result<DictionaryEntry> {
+        let word = word.trim();
+        if word.is_empty() {
+            anyhow::bail!("查询的单词不能为空");
+        }
+
+        let response = self.send_request_with(word, from, to, &[("dict", "1")]).await?;
+        let Some(result) = response.trans_result.into_iter().next() else {
+            anyhow::bail!("翻译结果为空");
+        };
+
+        // 词典资源仅对单词返回，且需要开通对应的服务，没有时只返回译文
+        let dict = match result.dict {
+            Some(serde_json::Value::String(dict)) => serde_json::from_str(&dict).ok(),
+            other => other,
+        };
+        let (phonetics, definitions) = dict.map(|dict| parse_dictionary(&dict)).unwrap_or_default();
+
+        Ok(DictionaryEntry {
+            word: word.to_string(),
+            translation: result.dst,
+            phonetics,
+            definitions,
+        })
+    }
+
+    fn provider(&self) -> Provider {
+        Provider::Baidu
     }
 
     fn name(&self) -> &'static str {
         "百度翻译"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProxyMode;
+    use axum::extract::State;
+    use axum::routing::post;
+    use axum::{Form, Json, Router};
+    use serde_json::{json, Value};
+    use std::net::SocketAddr;
+    use std::sync::{Arc, Mutex};
+
+    /// 模拟百度接口：每行译为大写，内容为 "skip" 的行不返回结果；记录收到的每个 q
+    async fn fake_api(
+        State(queries): State<Arc<Mutex<Vec<String>>>>,
+        Form(params): Form<HashMap<String, String>>,
+    ) -> Json<Value> {
+        let query = params["q"].clone();
+        queries.lock().unwrap().push(query.clone());
+        let results: Vec<Value> = query
+            .split('\n')
+            .filter(|line| *line != "skip")
+            .map(|line| json!({ "src": line, "dst": line.to_uppercase() }))
+            .collect();
+        Json(json!({ "from": params["from"], "to": params["to"], "trans_result": results }))
+    }
+
+    async fn translator() -> (BaiduTranslator, Arc<Mutex<Vec<String>>>) {
+        let queries = Arc::new(Mutex::new(Vec::new()));
+        let app = Router::new().route("/", post(fake_api)).with_state(queries.clone());
+        let server = axum::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(app.into_make_service());
+        let api_url = format!("http://{}/", server.local_addr());
+        tokio::spawn(server);
+
+        let config = BaiduConfig {
+            app_id: "app".to_string(),
+            secret_key: "secret".to_string(),
+            api_url,
+            qps: 0,
+            ..BaiduConfig::default()
+        };
+        let proxy = ProxyConfig {
+            enabled: ProxyMode::Disable,
+            ..ProxyConfig::default()
+        };
+        (BaiduTranslator::new(config, &proxy), queries)
+    }
+
+    #[test]
+    fn chunks_queries_by_joined_size() {
+        let queries = ["aaaa", "bb", "cccccc", "d"];
+        // 拼接后的换行符也计入字节数
+        assert_eq!(chunk_queries(&queries, 7), vec![vec!["aaaa", "bb"], vec!["cccccc"], vec!["d"]]);
+        assert_eq!(chunk_queries(&queries, 8), vec![vec!["aaaa", "bb"], vec!["cccccc", "d"]]);
+        assert_eq!(chunk_queries(&queries, 100), vec![queries.to_vec()]);
+        // 超长的一行独占一组
+        assert_eq!(chunk_queries(&["a", "toolong", "b"], 3), vec![vec!["a"], vec!["toolong"], vec!["b"]]);
+        assert!(chunk_queries(&[], 10).is_empty());
+    }
+
+    #[tokio::test]
+    async fn batches_lines_and_realigns_them() {
+        let (translator, queries) = translator().await;
+        let texts = vec![
+            "  hello\n\n\tworld ".to_string(),
+            String::new(),
+            "a".repeat(3000),
+            "b".repeat(3000),
+        ];
+        let translations = translator.translate_batch(&texts, "en", "zh").await.unwrap();
+        assert_eq!(translations, vec!["  HELLO\n\n\tWORLD ".to_string(), String::new(), "A".repeat(3000), "B".repeat(3000)]);
+
+        // 只提交去掉首尾空白的非空行，超过 BATCH_MAX_BYTES 时拆成多次请求
+        let queries = queries.lock().unwrap();
+        assert_eq!(queries.len(), 2);
+        assert_eq!(queries[0], format!("hello\nworld\n{}", "a".repeat(3000)));
+        assert_eq!(queries[1], "b".repeat(3000));
+        assert!(queries.iter().all(|q| q.len() <= BATCH_MAX_BYTES));
+    }
+
+    #[tokio::test]
+    async fn rejects_misaligned_batch_results() {
+        let (translator, _) = translator().await;
+        let texts = vec!["one".to_string(), "skip".to_string()];
+        let error = translator.translate_batch(&texts, "en", "zh").await.unwrap_err();
+        assert_eq!(error.to_string(), "批量翻译结果数量不匹配: 提交 2 行，返回 1 行");
+    }
+}
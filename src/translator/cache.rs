@@ -0,0 +1,155 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use crate::language::{Language, Provider};
+use crate::translator::trait_def::{DictionaryEntry, TranslationNotices, TranslationResult, Translator};
+
+/// (源语言, 目标语言, 原文)
+type CacheKey = (String, String, String);
+
+struct CacheEntry {
+    target: String,
+    /// 自动检测到的源语言，批量翻译得到的条目没有此信息
+    detected_language: Option<String>,
+}
+
+/// 缓存条目，超出容量时淘汰最早加入的条目
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<CacheKey, CacheEntry>,
+    order: VecDeque<CacheKey>,
+}
+
+/// 在内存中缓存译文的翻译器包装
+///
+/// 长时间运行的服务（HTTP 服务、编辑器集成等）中相同的文本只请求一次
+pub struct CachedTranslator<T> {
+    inner: T,
+    /// 最多缓存的条目数，0 表示不缓存
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+impl<T: Translator> CachedTranslator<T> {
+    pub fn new(inner: T, capacity: usize) -> Self {
+        Self {
+            inner,
+            capacity,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    fn key(text: &str, from: &str, to: &str) -> CacheKey {
+        (from.to_string(), to.to_string(), text.to_string())
+    }
+
+    fn insert(&self, key: CacheKey, entry: CacheEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        if state.entries.insert(key.clone(), entry).is_none() {
+            state.order.push_back(key);
+        }
+        while state.order.len() > self.capacity {
+            if let Some(oldest) = state.order.pop_front() {
+                state.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<T: Translator> Translator for CachedTranslator<T> {
+    async fn translate(&self, text: &str, from: &str, to: &str) -> Result<TranslationResult> {
+        let key = Self::key(text, from, to);
+        {
+            let state = self.state.lock().unwrap();
+            // 自动检测时需要源语言，没有记录检测结果的条目视为未命中
+            let hit = state
+                .entries
+                .get(&key)
+                .filter(|entry| from != "auto" || entry.detected_language.is_some());
+            if let Some(entry) = hit {
+                return Ok(TranslationResult {
+                    source: text.to_string(),
+                    target: entry.target.clone(),
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    detected_language: entry.detected_language.clone(),
+                });
+            }
+        }
+
+        let result = self.inner.translate(text, from, to).await?;
+        self.insert(
+            key,
+            CacheEntry {
+                target: result.target.clone(),
+                detected_language: result.detected_language.clone(),
+            },
+        );
+        Ok(result)
+    }
+
+    async fn translate_batch(&self, texts: &[String], from: &str, to: &str) -> Result<Vec<String>> {
+        let mut results: Vec<Option<String>> = {
+            let state = self.state.lock().unwrap();
+            texts
+                .iter()
+                .map(|text| state.entries.get(&Self::key(text, from, to)).map(|e| e.target.clone()))
+                .collect()
+        };
+
+        let misses: Vec<String> = texts
+            .iter()
+            .zip(&results)
+            .filter(|(_, hit)| hit.is_none())
+            .map(|(text, _)| text.clone())
+            .collect();
+        if !misses.is_empty() {
+            let translated = self.inner.translate_batch(&misses, from, to).await?;
+            let mut translated = misses.into_iter().zip(translated);
+            for result in results.iter_mut().filter(|r| r.is_none()) {
+                if let Some((text, target)) = translated.next() {
+                    self.insert(
+                        Self::key(&text, from, to),
+                        CacheEntry {
+                            target: target.clone(),
+                            detected_language: None,
+                        },
+                    );
+                    *result = Some(target);
+                }
+            }
+        }
+
+        Ok(results.into_iter().map(Option::unwrap_or_default).collect())
+    }
+
+    async fn lookup(&self, word: &str, from: &str, to: &str) -> Result<DictionaryEntry> {
+        self.inner.lookup(word, from, to).await
+    }
+
+    fn notices(&self, text: &str, translation: &str, from: &str, to: &str) -> TranslationNotices {
+        self.inner.notices(text, translation, from, to)
+    }
+
+    fn provider(&self) -> Provider {
+        self.inner.provider()
+    }
+
+    fn supported_languages(&self) -> Vec<&'static Language> {
+        self.inner.supported_languages()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+}
@@ -0,0 +1,467 @@
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::formats::xml::{self, Token};
+use crate::language::{registry, Language, Provider};
+use crate::translator::protect::{ProtectKind, Protected};
+use crate::translator::trait_def::{DictionaryEntry, TranslationNotices, TranslationResult, Translator};
+
+/// 术语表：每个概念在各语言中的译法
+///
+/// 从 CSV（首行为语言代码）或 TBX 文件加载，同一个文件可以包含多种语言，
+/// 翻译时按语言对取出对应的术语
+#[derive(Debug, Clone, Default)]
+pub struct Glossary {
+    /// 每个概念的 {BCP-47 语言标签: 术语}
+    concepts: Vec<HashMap<String, String>>,
+}
+
+/// 译文没有按术语表翻译的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlossaryViolation {
+    /// 原文中出现的术语
+    pub source_term: String,
+    /// 术语表规定的译法
+    pub target_term: String,
+    /// 译文
+    pub translation: String,
+}
+
+/// 某个语言对下的术语
+struct TermSet {
+    /// 小写原文术语 -> 译法
+    targets: HashMap<String, String>,
+    pattern: Regex,
+}
+
+impl Glossary {
+    /// 根据扩展名加载 CSV 或 TBX 术语表
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("读取术语表失败: {}", path.display()))?;
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+        let glossary = match ext.as_str() {
+            "tbx" | "xml" => Self::from_tbx(&content),
+            _ => Self::from_csv(&content),
+        }
+        .map_err(|e| anyhow::anyhow!("解析术语表失败: {}: {}", path.display(), e))?;
+
+        if glossary.concepts.is_empty() {
+            bail!("术语表为空: {}", path.display());
+        }
+        Ok(glossary)
+    }
+
+    /// 解析 CSV 术语表，首行为语言代码，例如 `zh,en,ja`
+    pub fn from_csv(content: &str) -> Result<Self> {
+        let mut lines = content
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .filter(|line| !line.trim().is_empty());
+        let header = lines.next().context("缺少表头")?;
+        let languages: Vec<String> = parse_csv_line(header).iter().map(|c| c.trim().to_string()).collect();
+        if languages.len() < 2 {
+            bail!("表头至少需要两列语言代码，例如: zh,en");
+        }
+        if let Some(code) = languages.iter().find(|c| registry::resolve(c).is_none()) {
+            bail!("表头中的 \"{}\" 不是有效的语言代码", code);
+        }
+
+        let concepts = lines
+            .map(|line| {
+                languages
+                    .iter()
+                    .zip(parse_csv_line(line))
+                    .filter(|(_, term)| !term.trim().is_empty())
+                    .map(|(lang, term)| (registry::primary_tag(lang), term.trim().to_string()))
+                    .collect::<HashMap<_, _>>()
+            })
+            .filter(|concept| concept.len() >= 2)
+            .collect();
+        Ok(Self { concepts })
+    }
+
+    /// 解析 TBX 术语表，每个 termEntry 为一个概念，各语言取第一个 term
+    pub fn from_tbx(content: &str) -> Result<Self> {
+        let tokens = xml::tokenize(content);
+        let mut concepts = Vec::new();
+        let mut concept: Option<HashMap<String, String>> = None;
+        let mut lang: Option<String> = None;
+
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = &tokens[i];
+            match (token, token.local_name()) {
+                (Token::Start { self_closing: false, .. }, Some("termEntry" | "conceptEntry")) => {
+                    concept = Some(HashMap::new());
+                }
+                (Token::End { .. }, Some("termEntry" | "conceptEntry")) => {
+                    if let Some(done) = concept.take().filter(|c| c.len() >= 2) {
+                        concepts.push(done);
+                    }
+                }
+                (Token::Start { .. }, Some("langSet" | "langSec")) => {
+                    lang = token.attr("xml:lang").or_else(|| token.attr("lang")).map(registry::primary_tag);
+                }
+                (Token::Start { self_closing: false, .. }, Some("term")) => {
+                    let mut text = String::new();
+                    while let Some(Token::Text { range }) = tokens.get(i + 1) {
+                        text.push_str(&xml::unescape(&content[range.clone()]));
+                        i += 1;
+                    }
+                    if let (Some(concept), Some(lang)) = (concept.as_mut(), &lang) {
+                        if !text.trim().is_empty() {
+                            concept.entry(lang.clone()).or_insert_with(|| text.trim().to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        if concepts.is_empty() && !content.contains("termEntry") && !content.contains("conceptEntry") {
+            bail!("不是有效的 TBX 文件");
+        }
+        Ok(Self { concepts })
+    }
+
+    /// 取出某个语言对的术语，源语言为 auto 时使用目标语言以外的所有语言
+    fn terms(&self, from: &str, to: &str) -> Option<TermSet> {
+        let to = registry::primary_tag(to);
+        let from = (from != "auto").then(|| registry::primary_tag(from));
+
+        let mut targets = HashMap::new();
+        for concept in &self.concepts {
+            let Some(target) = concept.get(&to) else { continue };
+            for (lang, source) in concept {
+                if *lang != to && from.as_ref().is_none_or(|f| f == lang) {
+                    targets.entry(source.to_lowercase()).or_insert_with(|| target.clone());
+                }
+            }
+        }
+        if targets.is_empty() {
+            return None;
+        }
+
+        // 长的术语优先匹配；以字母数字开头或结尾的术语按整词匹配
+        let mut sources: Vec<&String> = targets.keys().collect();
+        sources.sort_by_key(|s| std::cmp::Reverse(s.chars().count()));
+        let alternatives: Vec<String> = sources
+            .iter()
+            .map(|s| {
+                let word = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+                format!(
+                    "{}{}{}",
+                    if word(s.chars().next()) { r"\b" } else { "" },
+                    regex::escape(s),
+                    if word(s.chars().last()) { r"\b" } else { "" }
+                )
+            })
+            .collect();
+        let pattern = Regex::new(&format!("(?i){}", alternatives.join("|"))).ok()?;
+
+        Some(TermSet { targets, pattern })
+    }
+}
+
+impl TermSet {
+    /// 文本中出现的术语及其译法
+    fn find(&self, text: &str) -> Vec<(String, String)> {
+        let mut found: Vec<(String, String)> = Vec::new();
+        for m in self.pattern.find_iter(text) {
+            let Some(target) = self.target(m.as_str()) else { continue };
+            if !found.iter().any(|(s, _)| s.to_lowercase() == m.as_str().to_lowercase()) {
+                found.push((m.as_str().to_string(), target.to_string()));
+            }
+        }
+        found
+    }
+
+    /// 匹配到的术语对应的译法
+    fn target(&self, matched: &str) -> Option<&str> {
+        self.targets.get(&matched.to_lowercase()).map(String::as_str)
+    }
+
+    /// 将术语替换为占位标记，还原时直接得到术语的译法
+    fn protect(&self, text: &str) -> Protected {
+        let mut protected = Protected::default();
+        let mut cursor = 0;
+        for m in self.pattern.find_iter(text) {
+            let Some(target) = self.target(m.as_str()) else { continue };
+            protected.push_text_with(&text[cursor..m.start()], &[ProtectKind::Token]);
+            protected.push_protected(target);
+            cursor = m.end();
+        }
+        protected.push_text_with(&text[cursor..], &[ProtectKind::Token]);
+        protected
+    }
+}
+
+/// 检查译文是否使用了规定的译法，返回没有使用的术语
+fn violations(found: &[(String, String)], translation: &str) -> Vec<GlossaryViolation> {
+    let lower = translation.to_lowercase();
+    found
+        .iter()
+        .filter(|(_, target)| !lower.contains(&target.to_lowercase()))
+        .map(|(source, target)| GlossaryViolation {
+            source_term: source.clone(),
+            target_term: target.clone(),
+            translation: translation.to_string(),
+        })
+        .collect()
+}
+
+/// 解析一行 CSV，支持双引号包裹的字段
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// 按术语表翻译的翻译器包装
+///
+/// 在请求前把术语替换为占位标记、翻译后换成规定的译法。服务商的术语干预（如百度控制台中的
+/// 术语库）只使用服务商那边维护的术语，不包含这里加载的术语表，因此始终在本地保护术语；
+/// 无论占位标记是否还原成功（被破坏时改为直接翻译原文），译文没有使用规定译法的都记录为违规
+pub struct GlossaryTranslator<T> {
+    inner: T,
+    glossary: Option<Glossary>,
+    violations: Mutex<Vec<GlossaryViolation>>,
+}
+
+impl<T: Translator> GlossaryTranslator<T> {
+    pub fn new(inner: T, glossary: Option<Glossary>) -> Self {
+        Self {
+            inner,
+            glossary,
+            violations: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 取出目前为止记录的违规
+    pub fn take_violations(&self) -> Vec<GlossaryViolation> {
+        std::mem::take(&mut *self.violations.lock().unwrap())
+    }
+
+    /// 记录译文没有使用规定译法的术语
+    fn verify(&self, found: &[(String, String)], translation: &str) {
+        self.violations.lock().unwrap().extend(violations(found, translation));
+    }
+}
+
+#[async_trait]
+impl<T: Translator> Translator for GlossaryTranslator<T> {
+    async fn translate(&self, text: &str, from: &str, to: &str) -> Result<TranslationResult> {
+        let Some(terms) = self.glossary.as_ref().and_then(|g| g.terms(from, to)) else {
+            return self.inner.translate(text, from, to).await;
+        };
+        let found = terms.find(text);
+        if found.is_empty() {
+            return self.inner.translate(text, from, to).await;
+        }
+
+        let protected = terms.protect(text);
+        let mut result = self.inner.translate(&protected.text, from, to).await?;
+        if let Ok(target) = protected.restore(&result.target) {
+            result.source = text.to_string();
+            result.target = target;
+            self.verify(&found, &result.target);
+            return Ok(result);
+        }
+
+        // 占位标记被破坏时直接翻译原文，再检查译法
+        let result = self.inner.translate(text, from, to).await?;
+        self.verify(&found, &result.target);
+        Ok(result)
+    }
+
+    async fn translate_batch(&self, texts: &[String], from: &str, to: &str) -> Result<Vec<String>> {
+        let Some(terms) = self.glossary.as_ref().and_then(|g| g.terms(from, to)) else {
+            return self.inner.translate_batch(texts, from, to).await;
+        };
+
+        let protected: Vec<Protected> = texts.iter().map(|text| terms.protect(text)).collect();
+        let queries: Vec<String> = protected.iter().map(|p| p.text.clone()).collect();
+        let translated = self.inner.translate_batch(&queries, from, to).await?;
+
+        let mut results = Vec::with_capacity(texts.len());
+        for ((text, protected), target) in texts.iter().zip(&protected).zip(translated) {
+            let translation = match protected.restore(&target) {
+                Ok(restored) => restored,
+                Err(_) => self.inner.translate(text, from, to).await?.target,
+            };
+            self.verify(&terms.find(text), &translation);
+            results.push(translation);
+        }
+        Ok(results)
+    }
+
+    async fn lookup(&self, word: &str, from: &str, to: &str) -> Result<DictionaryEntry> {
+        self.inner.lookup(word, from, to).await
+    }
+
+    fn notices(&self, text: &str, translation: &str, from: &str, to: &str) -> TranslationNotices {
+        let mut notices = self.inner.notices(text, translation, from, to);
+        if let Some(terms) = self.glossary.as_ref().and_then(|g| g.terms(from, to)) {
+            notices.violations.extend(violations(&terms.find(text), translation));
+        }
+        notices
+    }
+
+    fn provider(&self) -> Provider {
+        self.inner.provider()
+    }
+
+    fn supported_languages(&self) -> Vec<&'static Language> {
+        self.inner.supported_languages()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::stub::UppercaseTranslator;
+
+    /// 丢掉占位标记后转为大写，模拟服务商破坏术语的占位标记
+    struct LossyTranslator;
+
+    #[async_trait]
+    impl Translator for LossyTranslator {
+        async fn translate(&self, text: &str, from: &str, to: &str) -> Result<TranslationResult> {
+            Ok(TranslationResult {
+                source: text.to_string(),
+                target: Regex::new(r"__\d+__").unwrap().replace_all(text, "").to_uppercase(),
+                from: from.to_string(),
+                to: to.to_string(),
+                detected_language: None,
+            })
+        }
+
+        fn provider(&self) -> Provider {
+            Provider::Baidu
+        }
+
+        fn name(&self) -> &'static str {
+            "lossy"
+        }
+    }
+
+    const CSV: &str = "\u{feff}en,zh-CN,ja\n\"Cloud, Inc\",云公司,\nbucket,存储桶,バケット\ncloud,云,\ncloud storage,云存储,\n\nalone,,\n";
+
+    #[test]
+    fn parses_csv_with_quotes_and_regions() {
+        let glossary = Glossary::from_csv(CSV).unwrap();
+        assert_eq!(glossary.concepts.len(), 4);
+        assert_eq!(glossary.concepts[0]["en"], "Cloud, Inc");
+        assert_eq!(glossary.concepts[1]["zh"], "存储桶");
+        assert_eq!(glossary.concepts[1]["ja"], "バケット");
+
+        assert!(Glossary::from_csv("en\nbucket\n").is_err());
+        assert!(Glossary::from_csv("en,notalang\nbucket,x\n").is_err());
+    }
+
+    #[test]
+    fn parses_tbx_lang_sets() {
+        let tbx = r#"<?xml version="1.0"?>
+<martif type="TBX"><text><body>
+  <termEntry id="1">
+    <langSet xml:lang="en-US"><tig><term>pull request</term></tig><tig><term>PR</term></tig></langSet>
+    <langSet xml:lang="zh-Hans"><tig><term>拉取请求</term></tig></langSet>
+  </termEntry>
+  <termEntry id="2"><langSet xml:lang="en"><tig><term>lonely</term></tig></langSet></termEntry>
+</body></text></martif>"#;
+        let glossary = Glossary::from_tbx(tbx).unwrap();
+        assert_eq!(glossary.concepts.len(), 1);
+        assert_eq!(glossary.concepts[0]["en"], "pull request");
+        assert_eq!(glossary.concepts[0]["zh"], "拉取请求");
+
+        assert!(Glossary::from_tbx("<html></html>").is_err());
+    }
+
+    #[test]
+    fn matches_languages_by_primary_tag() {
+        let glossary = Glossary::from_csv(CSV).unwrap();
+        let terms = glossary.terms("en-GB", "zh_CN").unwrap();
+        assert_eq!(terms.target("BUCKET"), Some("存储桶"));
+        assert_eq!(terms.target("バケット"), None);
+
+        // 源语言为 auto 时目标语言以外的术语都参与匹配
+        let terms = glossary.terms("auto", "zh").unwrap();
+        assert_eq!(terms.target("バケット"), Some("存储桶"));
+
+        assert!(glossary.terms("en", "fr").is_none());
+        // 繁体中文是单独的语言，不使用简体中文的术语
+        assert!(glossary.terms("en", "zh-TW").is_none());
+    }
+
+    #[tokio::test]
+    async fn substitutes_longest_whole_word_terms() {
+        let translator = GlossaryTranslator::new(UppercaseTranslator, Some(Glossary::from_csv(CSV).unwrap()));
+        let result = translator
+            .translate("Cloud storage beats a cloud; empty the Bucket, not buckets", "en", "zh")
+            .await
+            .unwrap();
+        assert_eq!(result.source, "Cloud storage beats a cloud; empty the Bucket, not buckets");
+        assert_eq!(result.target, "云存储 BEATS A 云; EMPTY THE 存储桶, NOT BUCKETS");
+
+        let batch = translator
+            .translate_batch(&["a bucket".to_string(), "no terms".to_string()], "en", "zh")
+            .await
+            .unwrap();
+        assert_eq!(batch, ["A 存储桶", "NO TERMS"]);
+        assert!(translator.take_violations().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reports_violations_when_placeholders_break() {
+        let translator = GlossaryTranslator::new(LossyTranslator, Some(Glossary::from_csv(CSV).unwrap()));
+        let result = translator.translate("empty the bucket", "en", "zh").await.unwrap();
+        assert_eq!(result.target, "EMPTY THE BUCKET");
+        translator
+            .translate_batch(&["a cloud".to_string()], "en", "zh")
+            .await
+            .unwrap();
+
+        let violations = translator.take_violations();
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].source_term, "bucket");
+        assert_eq!(violations[0].target_term, "存储桶");
+        assert_eq!(violations[0].translation, "EMPTY THE BUCKET");
+        assert_eq!(violations[1].target_term, "云");
+        assert!(translator.take_violations().is_empty());
+
+        // 按参数检查译文，不依赖之前记录的违规
+        let notices = translator.notices("empty the bucket", "清空存储桶", "en", "zh");
+        assert!(notices.violations.is_empty());
+        let notices = translator.notices("empty the bucket", "清空桶", "en", "zh");
+        assert_eq!(notices.violations[0].target_term, "存储桶");
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+/// 图片中文字块的位置（像素坐标）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BoundingBox {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl BoundingBox {
+    /// 解析百度返回的 "x y width height" 格式
+    pub fn parse(rect: &str) -> Option<Self> {
+        let values: Vec<u32> = rect
+            .split_whitespace()
+            .map(|v| v.parse().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        match values.as_slice() {
+            [x, y, width, height] => Some(Self {
+                x: *x,
+                y: *y,
+                width: *width,
+                height: *height,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// 图片中识别出的一个文字块及其译文
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageTextBlock {
+    /// 识别出的原文
+    pub source: String,
+    /// 译文
+    pub target: String,
+    /// 文字块位置
+    pub bounding_box: Option<BoundingBox>,
+    /// 文字块占用的行数
+    pub line_count: u32,
+}
+
+/// 图片翻译结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageTranslationResult {
+    /// 源语言
+    pub from: String,
+    /// 目标语言
+    pub to: String,
+    /// 各文字块的识别与翻译结果
+    pub blocks: Vec<ImageTextBlock>,
+    /// 全部原文拼接
+    pub source: String,
+    /// 全部译文拼接
+    pub target: String,
+}
+
+/// 根据文件扩展名获取图片的MIME类型，仅支持百度图片翻译接受的格式
+pub fn image_mime_type(path: &std::path::Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn parses_baidu_rects() {
+        let expected = BoundingBox {
+            x: 12,
+            y: 34,
+            width: 560,
+            height: 78,
+        };
+        assert_eq!(BoundingBox::parse("12 34 560 78"), Some(expected));
+        assert_eq!(BoundingBox::parse("  12\t34  560 78 "), Some(expected));
+        assert_eq!(BoundingBox::parse(""), None);
+        assert_eq!(BoundingBox::parse("12 34 560"), None);
+        assert_eq!(BoundingBox::parse("12 34 560 78 9"), None);
+        assert_eq!(BoundingBox::parse("12 -34 560 78"), None);
+        assert_eq!(BoundingBox::parse("12 34 5.6 78"), None);
+    }
+
+    #[test]
+    fn accepts_png_and_jpeg_only() {
+        assert_eq!(image_mime_type(Path::new("shot.PNG")), Some("image/png"));
+        assert_eq!(image_mime_type(Path::new("a/photo.jpeg")), Some("image/jpeg"));
+        assert_eq!(image_mime_type(Path::new("photo.jpg")), Some("image/jpeg"));
+        assert_eq!(image_mime_type(Path::new("anim.gif")), None);
+        assert_eq!(image_mime_type(Path::new("png")), None);
+    }
+}
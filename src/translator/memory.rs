@@ -0,0 +1,112 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::language::{Language, Provider};
+use crate::memory::{MemoryMatch, MemoryUnit, TranslationMemory};
+use crate::translator::trait_def::{DictionaryEntry, TranslationNotices, TranslationResult, Translator};
+
+/// 优先使用翻译记忆的翻译器包装
+///
+/// 记忆库中有完全匹配的句段时直接使用审校过的译文，不再请求服务商
+pub struct MemoryTranslator<T> {
+    inner: T,
+    memory: Option<TranslationMemory>,
+    /// 模糊匹配的最低相似度
+    threshold: f64,
+}
+
+impl<T: Translator> MemoryTranslator<T> {
+    pub fn new(inner: T, memory: Option<TranslationMemory>, threshold: f64) -> Self {
+        Self {
+            inner,
+            memory,
+            threshold,
+        }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// 记忆库中完全匹配的句段
+    pub fn exact_match(&self, text: &str, from: &str, to: &str) -> Option<&MemoryUnit> {
+        self.memory.as_ref()?.exact(text, from, to)
+    }
+
+    /// 记忆库中与文本相似但不完全相同的句段
+    pub fn fuzzy_matches(&self, text: &str, from: &str, to: &str) -> Vec<MemoryMatch> {
+        let Some(memory) = &self.memory else {
+            return Vec::new();
+        };
+        memory
+            .search(text, from, to, self.threshold)
+            .into_iter()
+            .filter(|m| m.score < 1.0)
+            .collect()
+    }
+
+}
+
+#[async_trait]
+impl<T: Translator> Translator for MemoryTranslator<T> {
+    async fn translate(&self, text: &str, from: &str, to: &str) -> Result<TranslationResult> {
+        let Some(unit) = self.exact_match(text, from, to) else {
+            return self.inner.translate(text, from, to).await;
+        };
+        Ok(TranslationResult {
+            source: text.to_string(),
+            target: unit.target.clone(),
+            from: from.to_string(),
+            to: to.to_string(),
+            detected_language: (from == "auto").then(|| unit.source_lang.clone()),
+        })
+    }
+
+    async fn translate_batch(&self, texts: &[String], from: &str, to: &str) -> Result<Vec<String>> {
+        let mut results: Vec<Option<String>> = texts
+            .iter()
+            .map(|t| self.exact_match(t, from, to).map(|unit| unit.target.clone()))
+            .collect();
+
+        // 只把记忆库中没有的句段交给服务商
+        let misses: Vec<String> = texts
+            .iter()
+            .zip(&results)
+            .filter(|(_, hit)| hit.is_none())
+            .map(|(text, _)| text.clone())
+            .collect();
+        if !misses.is_empty() {
+            let mut translated = self.inner.translate_batch(&misses, from, to).await?.into_iter();
+            for result in results.iter_mut().filter(|r| r.is_none()) {
+                *result = translated.next();
+            }
+        }
+
+        Ok(results.into_iter().map(Option::unwrap_or_default).collect())
+    }
+
+    async fn lookup(&self, word: &str, from: &str, to: &str) -> Result<DictionaryEntry> {
+        self.inner.lookup(word, from, to).await
+    }
+
+    fn notices(&self, text: &str, translation: &str, from: &str, to: &str) -> TranslationNotices {
+        let mut notices = self.inner.notices(text, translation, from, to);
+        notices.from_memory = self.exact_match(text, from, to).is_some();
+        if !notices.from_memory {
+            notices.fuzzy_matches = self.fuzzy_matches(text, from, to);
+        }
+        notices
+    }
+
+    fn provider(&self) -> Provider {
+        self.inner.provider()
+    }
+
+    fn supported_languages(&self) -> Vec<&'static Language> {
+        self.inner.supported_languages()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+}
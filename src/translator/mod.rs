@@ -1,5 +1,13 @@
 pub mod trait_def;
 pub mod baidu;
+pub mod cache;
+pub mod glossary;
+pub mod image;
+pub mod memory;
+pub mod protect;
+pub mod rate_limit;
+#[cfg(test)]
+pub mod stub;
 
 pub use trait_def::Translator;
 pub use baidu::BaiduTranslator; 
\ No newline at end of file
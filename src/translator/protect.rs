@@ -0,0 +1,345 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use regex::Regex;
+use std::sync::OnceLock;
+use thiserror::Error;
+
+use crate::language::{Language, Provider};
+use crate::translator::trait_def::{DictionaryEntry, TranslationNotices, TranslationResult, Translator};
+
+/// 需要在翻译前保护的内容类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtectKind {
+    /// 插值占位符: {{name}}、%{count}、{0}、{name}
+    Interpolation,
+    /// printf 风格占位符: %s、%d、%1$s、%@、%(name)s
+    Printf,
+    /// HTML/XML 标签: <b>、</a>、<br/>、<a href="...">
+    HtmlTag,
+    /// 网址: https://example.com/path
+    Url,
+    /// 邮箱地址
+    Email,
+    /// 反引号包裹的行内代码: `cargo build`
+    Code,
+    /// 上一层已经替换出的占位标记 __N__，嵌套保护时原样保留
+    Token,
+}
+
+/// i18n 资源文件使用的保护类型
+pub const I18N_KINDS: &[ProtectKind] = &[ProtectKind::Interpolation, ProtectKind::Printf];
+
+/// 所有翻译请求默认使用的保护类型
+pub const DEFAULT_KINDS: &[ProtectKind] = &[
+    ProtectKind::Token,
+    ProtectKind::Code,
+    ProtectKind::Url,
+    ProtectKind::Email,
+    ProtectKind::HtmlTag,
+    ProtectKind::Interpolation,
+    ProtectKind::Printf,
+];
+
+impl ProtectKind {
+    fn pattern(&self) -> &'static Regex {
+        static INTERPOLATION: OnceLock<Regex> = OnceLock::new();
+        static PRINTF: OnceLock<Regex> = OnceLock::new();
+        static HTML_TAG: OnceLock<Regex> = OnceLock::new();
+        static URL: OnceLock<Regex> = OnceLock::new();
+        static EMAIL: OnceLock<Regex> = OnceLock::new();
+        static CODE: OnceLock<Regex> = OnceLock::new();
+        static TOKEN: OnceLock<Regex> = OnceLock::new();
+
+        match self {
+            ProtectKind::Interpolation => INTERPOLATION.get_or_init(|| {
+                Regex::new(r"\{\{[^{}]*\}\}|%\{[^{}]*\}|\{[A-Za-z0-9_.,:\- ]*\}").unwrap()
+            }),
+            ProtectKind::Printf => PRINTF.get_or_init(|| {
+                Regex::new(r"%(?:\d+\$)?(?:\([A-Za-z0-9_]+\))?[-+0#]*\d*(?:\.\d+)?(?:hh|h|ll|l|L|z|j|t)?[sdifuxXoeEgGcp@%]").unwrap()
+            }),
+            ProtectKind::HtmlTag => HTML_TAG.get_or_init(|| {
+                Regex::new(r#"</?[A-Za-z][A-Za-z0-9:-]*(?:\s+[^<>]*?)?/?>|<!--.*?-->"#).unwrap()
+            }),
+            // 结尾的标点通常属于句子而不是网址
+            ProtectKind::Url => URL.get_or_init(|| {
+                Regex::new(r#"\b(?:https?|ftp)://[^\s<>"'`()（）]*[^\s<>"'`()（）.,;:!?。，；：！？]"#).unwrap()
+            }),
+            ProtectKind::Email => EMAIL.get_or_init(|| {
+                Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b").unwrap()
+            }),
+            ProtectKind::Code => CODE.get_or_init(|| Regex::new(r"`[^`\n]+`").unwrap()),
+            ProtectKind::Token => TOKEN.get_or_init(|| Regex::new(r"__\d+__").unwrap()),
+        }
+    }
+}
+
+/// 恢复占位符时发现的问题
+#[derive(Debug, Error)]
+pub enum ProtectError {
+    #[error("译文中缺少占位符 {0}")]
+    Missing(String),
+    #[error("译文中占位符 {0} 重复出现")]
+    Duplicated(String),
+}
+
+/// 被保护后的文本
+#[derive(Debug, Clone, Default)]
+pub struct Protected {
+    /// 用占位标记替换后的文本，用于发送给翻译服务
+    pub text: String,
+    /// 占位标记对应的原始内容，下标即标记序号
+    originals: Vec<String>,
+    /// 保护换行、缩进等空白的标记序号，还原时去掉两侧的空格
+    breaks: Vec<usize>,
+}
+
+/// 将文本中需要保护的内容替换为 __N__ 形式的占位标记
+pub fn protect(text: &str, kinds: &[ProtectKind]) -> Protected {
+    let mut protected = Protected::default();
+    protected.push_text_with(text, kinds);
+    protected
+}
+
+fn token(index: usize) -> String {
+    format!("__{}__", index)
+}
+
+impl Protected {
+    /// 追加一段普通文本
+    pub fn push_text(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    /// 追加一段文本，其中匹配 `kinds` 的内容会被保护
+    pub fn push_text_with(&mut self, text: &str, kinds: &[ProtectKind]) {
+        // 收集所有匹配区间，按起始位置排序，重叠时保留先出现的
+        let mut spans: Vec<(usize, usize)> = kinds
+            .iter()
+            .flat_map(|kind| kind.pattern().find_iter(text).map(|m| (m.start(), m.end())))
+            .collect();
+        spans.sort_by_key(|(start, end)| (*start, std::cmp::Reverse(*end)));
+
+        let mut cursor = 0;
+        for (start, end) in spans {
+            if start < cursor {
+                continue;
+            }
+            self.push_text(&text[cursor..start]);
+            self.push_protected(&text[start..end]);
+            cursor = end;
+        }
+        self.push_text(&text[cursor..]);
+    }
+
+    /// 追加一段需要保护的原始内容，例如内联标签
+    pub fn push_protected(&mut self, original: &str) {
+        self.text.push_str(&token(self.originals.len()));
+        self.originals.push(original.to_string());
+    }
+
+    /// 追加一段需要原样保留的空白，例如换行连同下一行的缩进
+    ///
+    /// 翻译时标记两侧加空格以分隔单词，还原时去掉这些空格，空白不会变多
+    pub fn push_break(&mut self, original: &str) {
+        self.breaks.push(self.originals.len());
+        self.push_text(" ");
+        self.push_protected(original);
+        self.push_text(" ");
+    }
+
+    /// 是否包含被保护的内容
+    pub fn is_empty(&self) -> bool {
+        self.originals.is_empty()
+    }
+
+    /// 将译文中的占位标记还原为原始内容
+    ///
+    /// 翻译服务有时会在标记内插入空格（如 "__ 0 __"），还原时一并识别；
+    /// 任一标记缺失或重复都视为失败，避免产生被破坏的译文
+    pub fn restore(&self, translated: &str) -> Result<String, ProtectError> {
+        if self.is_empty() {
+            return Ok(translated.to_string());
+        }
+
+        static TOKEN: OnceLock<Regex> = OnceLock::new();
+        let pattern = TOKEN.get_or_init(|| Regex::new(r"__\s*(\d+)\s*__").unwrap());
+        let translated = self.tidy_breaks(translated);
+
+        let mut seen = vec![false; self.originals.len()];
+        let mut error = None;
+        let restored = pattern.replace_all(&translated, |caps: &regex::Captures| {
+            let original = caps[1]
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| self.originals.get(i).map(|o| (i, o)));
+            match original {
+                Some((index, original)) => {
+                    if std::mem::replace(&mut seen[index], true) {
+                        error.get_or_insert(ProtectError::Duplicated(original.clone()));
+                    }
+                    original.clone()
+                }
+                None => caps[0].to_string(),
+            }
+        });
+
+        if let Some(error) = error {
+            return Err(error);
+        }
+        if let Some(index) = seen.iter().position(|s| !s) {
+            return Err(ProtectError::Missing(self.originals[index].clone()));
+        }
+        Ok(restored.into_owned())
+    }
+
+    /// 去掉空白标记两侧的空格和制表符，其他标记不变
+    fn tidy_breaks(&self, translated: &str) -> String {
+        if self.breaks.is_empty() {
+            return translated.to_string();
+        }
+        static SPACED: OnceLock<Regex> = OnceLock::new();
+        let pattern = SPACED.get_or_init(|| Regex::new(r"[ \t]*__\s*(\d+)\s*__[ \t]*").unwrap());
+        pattern
+            .replace_all(translated, |caps: &regex::Captures| match caps[1].parse::<usize>() {
+                Ok(index) if self.breaks.contains(&index) => token(index),
+                _ => caps[0].to_string(),
+            })
+            .into_owned()
+    }
+}
+
+/// 在翻译前后保护占位符、标签、网址、邮箱和行内代码的翻译器包装
+///
+/// 翻译服务经常改写 `{name}`、丢掉 `%s` 或在标签中插入空格，
+/// 这里先把这些内容替换为占位标记，翻译后再还原；标记丢失或重复时返回错误
+pub struct ProtectedTranslator<T> {
+    inner: T,
+    kinds: &'static [ProtectKind],
+}
+
+impl<T: Translator> ProtectedTranslator<T> {
+    /// 使用默认保护类型包装翻译器
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            kinds: DEFAULT_KINDS,
+        }
+    }
+
+    /// 被包装的翻译器
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<T: Translator> Translator for ProtectedTranslator<T> {
+    async fn translate(&self, text: &str, from: &str, to: &str) -> Result<TranslationResult> {
+        let protected = protect(text, self.kinds);
+        if protected.is_empty() {
+            return self.inner.translate(text, from, to).await;
+        }
+
+        let mut result = self.inner.translate(&protected.text, from, to).await?;
+        result.source = text.to_string();
+        result.target = protected
+            .restore(&result.target)
+            .map_err(|e| anyhow!("译文校验失败: {}", e))?;
+        Ok(result)
+    }
+
+    async fn translate_batch(&self, texts: &[String], from: &str, to: &str) -> Result<Vec<String>> {
+        let protected: Vec<Protected> = texts.iter().map(|t| protect(t, self.kinds)).collect();
+        let queries: Vec<String> = protected.iter().map(|p| p.text.clone()).collect();
+        let translated = self.inner.translate_batch(&queries, from, to).await?;
+
+        let mut results = Vec::with_capacity(texts.len());
+        for ((protected, query), target) in protected.iter().zip(&queries).zip(translated) {
+            match protected.restore(&target) {
+                Ok(restored) => results.push(restored),
+                // 合并请求时上下文可能影响标记，单独重试一次
+                Err(_) => {
+                    let retry = self.inner.translate(query, from, to).await?;
+                    let restored = protected
+                        .restore(&retry.target)
+                        .map_err(|e| anyhow!("译文校验失败: {}", e))?;
+                    results.push(restored);
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    async fn lookup(&self, word: &str, from: &str, to: &str) -> Result<DictionaryEntry> {
+        self.inner.lookup(word, from, to).await
+    }
+
+    fn notices(&self, text: &str, translation: &str, from: &str, to: &str) -> TranslationNotices {
+        self.inner.notices(text, translation, from, to)
+    }
+
+    fn provider(&self) -> Provider {
+        self.inner.provider()
+    }
+
+    fn supported_languages(&self) -> Vec<&'static Language> {
+        self.inner.supported_languages()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restore_accepts_spaces_inside_tokens() {
+        let protected = protect("Hello {name}, you have %d messages", DEFAULT_KINDS);
+        assert_eq!(protected.text, "Hello __0__, you have __1__ messages");
+        assert_eq!(
+            protected.restore("你好 __ 0 __，你有 __1 __ 条消息").unwrap(),
+            "你好 {name}，你有 %d 条消息"
+        );
+    }
+
+    #[test]
+    fn restore_rejects_missing_and_duplicated_tokens() {
+        let protected = protect("Open <b>{file}</b>", DEFAULT_KINDS);
+        assert_eq!(protected.text, "Open __0____1____2__");
+
+        let missing = protected.restore("打开 __0____2__").unwrap_err();
+        assert!(matches!(missing, ProtectError::Missing(ref s) if s == "{file}"));
+        let duplicated = protected.restore("打开 __0____1____1____2__").unwrap_err();
+        assert!(matches!(duplicated, ProtectError::Duplicated(ref s) if s == "{file}"));
+    }
+
+    #[test]
+    fn nested_tokens_are_kept_verbatim() {
+        let mut protected = Protected::default();
+        protected.push_protected("**");
+        protected.push_text_with("see __7__ and `code`", &[ProtectKind::Token, ProtectKind::Code]);
+        assert_eq!(protected.text, "__0__see __1__ and __2__");
+        assert_eq!(protected.restore("__0__SEE __1__ AND __2__").unwrap(), "**SEE __7__ AND `code`");
+    }
+
+    #[test]
+    fn breaks_drop_the_padding_spaces_only() {
+        let mut protected = Protected::default();
+        protected.push_protected("*");
+        protected.push_text("one");
+        protected.push_break("\n  ");
+        protected.push_text("two");
+        assert_eq!(protected.text, "__0__one __1__ two");
+        assert_eq!(protected.restore("__0__ 一 __ 1 __ 二").unwrap(), "* 一\n  二");
+    }
+
+    #[test]
+    fn text_without_protected_content_is_returned_as_is() {
+        let protected = protect("plain text", DEFAULT_KINDS);
+        assert!(protected.is_empty());
+        assert_eq!(protected.restore("纯文本 __0__").unwrap(), "纯文本 __0__");
+    }
+}
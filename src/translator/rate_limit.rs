@@ -0,0 +1,37 @@
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// 简单的请求频率限制器，保证相邻两次请求之间的最小间隔
+pub struct RateLimiter {
+    interval: Option<Duration>,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// 创建频率限制器，`qps` 为每秒允许的最大请求数，0 表示不限制
+    pub fn new(qps: u32) -> Self {
+        let interval = (qps > 0).then(|| Duration::from_secs(1) / qps);
+        Self {
+            interval,
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// 等待直到允许发送下一个请求
+    pub async fn acquire(&self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+
+        let wait_until = {
+            let mut next_slot = self.next_slot.lock().await;
+            let now = Instant::now();
+            let slot = (*next_slot).max(now);
+            *next_slot = slot + interval;
+            slot
+        };
+
+        tokio::time::sleep_until(wait_until).await;
+    }
+}
@@ -0,0 +1,29 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::language::Provider;
+use crate::translator::trait_def::{TranslationResult, Translator};
+
+/// 测试用翻译器，把文本转为大写，占位标记 __N__ 不受影响；自动检测时报告源语言为英语
+pub struct UppercaseTranslator;
+
+#[async_trait]
+impl Translator for UppercaseTranslator {
+    async fn translate(&self, text: &str, from: &str, to: &str) -> Result<TranslationResult> {
+        Ok(TranslationResult {
+            source: text.to_string(),
+            target: text.to_uppercase(),
+            from: from.to_string(),
+            to: to.to_string(),
+            detected_language: (from == "auto").then(|| "en".to_string()),
+        })
+    }
+
+    fn provider(&self) -> Provider {
+        Provider::Baidu
+    }
+
+    fn name(&self) -> &'static str {
+        "stub"
+    }
+}
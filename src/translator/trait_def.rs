@@ -1,7 +1,11 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
+use crate::language::{registry, Language, Provider};
+use crate::memory::MemoryMatch;
+use crate::translator::glossary::GlossaryViolation;
+
 /// 翻译结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranslationResult {
@@ -17,6 +21,51 @@ pub struct TranslationResult {
     pub detected_language: Option<String>,
 }
 
+/// 词典查询结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictionaryEntry {
+    /// 查询的单词
+    pub word: String,
+    /// 译文
+    pub translation: String,
+    /// 音标或拼音
+    #[serde(default)]
+    pub phonetics: Vec<Phonetic>,
+    /// 按词性分组的释义
+    #[serde(default)]
+    pub definitions: Vec<Definition>,
+}
+
+/// 音标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Phonetic {
+    /// 口音: uk、us 或 pinyin
+    pub accent: String,
+    pub text: String,
+}
+
+/// 某个词性下的释义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Definition {
+    /// 词性，如 n.、v.
+    pub part_of_speech: String,
+    pub meanings: Vec<String>,
+}
+
+/// 随译文给出的提示
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TranslationNotices {
+    /// 译文直接取自翻译记忆
+    #[serde(default)]
+    pub from_memory: bool,
+    /// 翻译记忆中相似的句段
+    #[serde(default)]
+    pub fuzzy_matches: Vec<MemoryMatch>,
+    /// 没有按术语表翻译的术语
+    #[serde(default)]
+    pub violations: Vec<GlossaryViolation>,
+}
+
 /// 翻译器抽象接口
 #[async_trait]
 pub trait Translator: Send + Sync {
@@ -31,8 +80,58 @@ pub trait Translator: Send + Sync {
     /// 返回翻译结果
     async fn translate(&self, text: &str, from: &str, to: &str) -> Result<TranslationResult>;
     
+    /// 批量翻译多段文本
+    /// 
+    /// 返回的译文与输入一一对应，空白文本原样返回。默认逐段调用 `translate`，
+    /// 支持单次请求提交多段文本的实现应覆盖此方法以减少请求次数
+    async fn translate_batch(&self, texts: &[String], from: &str, to: &str) -> Result<Vec<String>> {
+        let mut results = Vec::with_capacity(texts.len());
+        for text in texts {
+            if text.trim().is_empty() {
+                results.push(text.clone());
+            } else {
+                results.push(self.translate(text, from, to).await?.target);
+            }
+        }
+        Ok(results)
+    }
+    
+    /// 检测文本的语言，返回 BCP-47 标签
+    /// 
+    /// 默认以自动检测模式翻译一次，取服务商识别出的源语言
+    async fn detect(&self, text: &str) -> Result<String> {
+        let result = self.translate(text, "auto", "zh").await?;
+        result.detected_language.context("无法识别文本的语言")
+    }
+    
+    /// 查询单词的词典释义
+    /// 
+    /// 默认只返回译文，支持词典资源的实现应覆盖此方法
+    async fn lookup(&self, word: &str, from: &str, to: &str) -> Result<DictionaryEntry> {
+        let result = self.translate(word, from, to).await?;
+        Ok(DictionaryEntry {
+            word: word.to_string(),
+            translation: result.target,
+            phonetics: Vec::new(),
+            definitions: Vec::new(),
+        })
+    }
+    
+    /// 检查一段文本的译文，给出翻译记忆的命中情况、术语表违规等提示
+    /// 
+    /// 只根据参数计算，不依赖之前的请求，并发翻译时也不会混入其他文本的提示。
+    /// 默认没有提示，包装翻译器应在内层翻译器的提示上补充自己的信息
+    fn notices(&self, _text: &str, _translation: &str, _from: &str, _to: &str) -> TranslationNotices {
+        TranslationNotices::default()
+    }
+    
+    /// 获取翻译器对应的提供商，用于在语言表中查找其专有代码
+    fn provider(&self) -> Provider;
+    
     /// 获取支持的语言列表
-    fn supported_languages(&self) -> Vec<(&'static str, &'static str)>;
+    fn supported_languages(&self) -> Vec<&'static Language> {
+        registry::supported(self.provider())
+    }
     
     /// 获取翻译器名称
     fn name(&self) -> &'static str;
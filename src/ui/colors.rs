@@ -1,4 +1,5 @@
 use colored::*;
+use crate::translator::image::ImageTranslationResult;
 use crate::translator::trait_def::TranslationResult;
 
 /// 显示翻译结果，带有颜色和格式化
@@ -52,6 +53,33 @@ fn display_plain_translation(result: &TranslationResult, show_detection: bool) {
     println!("译文: {}", result.target);
 }
 
+/// 显示图片翻译结果，逐块列出识别出的原文和译文
+pub fn display_image_translation(result: &ImageTranslationResult, enable_colors: bool) {
+    let lang_info = format!("[{} → {}]", result.from.to_uppercase(), result.to.to_uppercase());
+    if enable_colors {
+        println!("{}", lang_info.blue().bold());
+    } else {
+        println!("{}", lang_info);
+    }
+
+    if result.blocks.is_empty() {
+        println!("未识别到文字");
+        return;
+    }
+
+    for (index, block) in result.blocks.iter().enumerate() {
+        if enable_colors {
+            println!("{}", format!("#{}", index + 1).yellow());
+            println!("  {} {}", "原文:".green().bold(), block.source.white());
+            println!("  {} {}", "译文:".cyan().bold(), block.target.bright_white().bold());
+        } else {
+            println!("#{}", index + 1);
+            println!("  原文: {}", block.source);
+            println!("  译文: {}", block.target);
+        }
+    }
+}
+
 /// 显示错误信息
 pub fn display_error(error: &str, enable_colors: bool) {
     if enable_colors {
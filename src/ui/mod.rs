@@ -2,6 +2,7 @@ pub mod colors;
 
 pub use colors::{
     display_translation, 
+    display_image_translation,
     display_error, 
     display_success, 
     display_info, 
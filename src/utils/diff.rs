@@ -0,0 +1,78 @@
+/// 上下文行数
+const CONTEXT: usize = 3;
+
+/// 生成统一格式 (unified) 的 diff
+///
+/// 按行号对齐比较，适用于只修改行内容、不增删行的改动（例如逐行翻译注释）；
+/// 行数不同时多出的行视为新增或删除
+pub fn unified_diff(old_name: &str, new_name: &str, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let total = old_lines.len().max(new_lines.len());
+
+    let changed: Vec<usize> = (0..total)
+        .filter(|&i| old_lines.get(i) != new_lines.get(i))
+        .collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    // 相邻改动之间的距离不超过两倍上下文时合并为一个区块
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &line in &changed {
+        let start = line.saturating_sub(CONTEXT);
+        let end = (line + CONTEXT + 1).min(total);
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut output = format!("--- {}\n+++ {}\n", old_name, new_name);
+    for (start, end) in hunks {
+        let old_count = old_lines.len().min(end).saturating_sub(start);
+        let new_count = new_lines.len().min(end).saturating_sub(start);
+        output.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(start, old_count),
+            hunk_range(start, new_count)
+        ));
+
+        let mut i = start;
+        while i < end {
+            if old_lines.get(i) == new_lines.get(i) {
+                push_line(&mut output, ' ', old_lines[i]);
+                i += 1;
+                continue;
+            }
+            // 连续的改动先输出全部删除行，再输出全部新增行
+            let run_end = (i..end)
+                .find(|&j| old_lines.get(j) == new_lines.get(j))
+                .unwrap_or(end);
+            for line in old_lines.iter().take(run_end).skip(i) {
+                push_line(&mut output, '-', line);
+            }
+            for line in new_lines.iter().take(run_end).skip(i) {
+                push_line(&mut output, '+', line);
+            }
+            i = run_end;
+        }
+    }
+    output
+}
+
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+fn push_line(output: &mut String, prefix: char, line: &str) {
+    output.push(prefix);
+    output.push_str(line);
+    if !line.ends_with('\n') {
+        output.push_str("\n\\ No newline at end of file\n");
+    }
+}
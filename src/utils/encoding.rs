@@ -0,0 +1,132 @@
+use anyhow::Result;
+
+/// 文本文件编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    /// 带BOM的UTF-8
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Gbk,
+}
+
+impl TextEncoding {
+    /// 编码名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf8Bom => "UTF-8 (BOM)",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+            TextEncoding::Gbk => "GBK",
+        }
+    }
+}
+
+/// 检测编码并解码文件内容
+///
+/// 依次检查 BOM、UTF-8 合法性，都不满足时按 GBK 解码
+pub fn decode(bytes: &[u8]) -> Result<(String, TextEncoding)> {
+    if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        let text = std::str::from_utf8(rest).map_err(|_| anyhow::anyhow!("文件包含无效的UTF-8字符"))?;
+        return Ok((text.to_string(), TextEncoding::Utf8Bom));
+    }
+
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        let (text, had_errors) = encoding_rs::UTF_16LE.decode_without_bom_handling(rest);
+        if had_errors {
+            anyhow::bail!("文件包含无效的UTF-16LE字符");
+        }
+        return Ok((text.into_owned(), TextEncoding::Utf16Le));
+    }
+
+    if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let (text, had_errors) = encoding_rs::UTF_16BE.decode_without_bom_handling(rest);
+        if had_errors {
+            anyhow::bail!("文件包含无效的UTF-16BE字符");
+        }
+        return Ok((text.into_owned(), TextEncoding::Utf16Be));
+    }
+
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Ok((text.to_string(), TextEncoding::Utf8));
+    }
+
+    let (text, had_errors) = encoding_rs::GBK.decode_without_bom_handling(bytes);
+    if had_errors {
+        anyhow::bail!("无法识别文件编码（支持 UTF-8、UTF-16 BOM 和 GBK）");
+    }
+    Ok((text.into_owned(), TextEncoding::Gbk))
+}
+
+/// 按指定编码将文本编码为字节，UTF-16 和带BOM的UTF-8会写回BOM
+pub fn encode(text: &str, encoding: TextEncoding) -> Result<Vec<u8>> {
+    let bytes = match encoding {
+        TextEncoding::Utf8 => text.as_bytes().to_vec(),
+        TextEncoding::Utf8Bom => [&[0xEF, 0xBB, 0xBF][..], text.as_bytes()].concat(),
+        TextEncoding::Utf16Le => {
+            let mut bytes = vec![0xFF, 0xFE];
+            bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            bytes
+        }
+        TextEncoding::Utf16Be => {
+            let mut bytes = vec![0xFE, 0xFF];
+            bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+            bytes
+        }
+        TextEncoding::Gbk => {
+            let (bytes, _, had_errors) = encoding_rs::GBK.encode(text);
+            if had_errors {
+                anyhow::bail!("译文包含无法用GBK编码的字符");
+            }
+            bytes.into_owned()
+        }
+    };
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_and_round_trips_encodings() {
+        let text = "翻译 fanyi\n";
+        for encoding in [
+            TextEncoding::Utf8,
+            TextEncoding::Utf8Bom,
+            TextEncoding::Utf16Le,
+            TextEncoding::Utf16Be,
+            TextEncoding::Gbk,
+        ] {
+            let bytes = encode(text, encoding).unwrap();
+            assert_eq!(decode(&bytes).unwrap(), (text.to_string(), encoding), "{}", encoding.name());
+        }
+    }
+
+    #[test]
+    fn writes_byte_order_marks() {
+        assert_eq!(encode("a", TextEncoding::Utf8Bom).unwrap(), [0xEF, 0xBB, 0xBF, b'a']);
+        assert_eq!(encode("a", TextEncoding::Utf16Le).unwrap(), [0xFF, 0xFE, b'a', 0]);
+        assert_eq!(encode("a", TextEncoding::Utf16Be).unwrap(), [0xFE, 0xFF, 0, b'a']);
+        // "中" 的 GBK 编码
+        assert_eq!(encode("中", TextEncoding::Gbk).unwrap(), [0xD6, 0xD0]);
+    }
+
+    #[test]
+    fn prefers_utf8_without_bom() {
+        // 纯 ASCII 同时是合法的 GBK，按 UTF-8 处理
+        assert_eq!(decode(b"hello").unwrap().1, TextEncoding::Utf8);
+        assert_eq!(decode("中文".as_bytes()).unwrap().1, TextEncoding::Utf8);
+        assert_eq!(decode(&[0xD6, 0xD0, 0xCE, 0xC4]).unwrap(), ("中文".to_string(), TextEncoding::Gbk));
+    }
+
+    #[test]
+    fn rejects_undecodable_bytes() {
+        assert!(decode(&[0xEF, 0xBB, 0xBF, 0xFF]).is_err());
+        assert!(decode(&[0xFF, 0xFE, 0x00, 0xD8]).is_err());
+        assert!(decode(&[0x81]).is_err());
+        assert!(encode("😀", TextEncoding::Gbk).is_err());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod diff;
+pub mod encoding;
+pub mod text;
+
+pub use text::edit_distance;